@@ -64,7 +64,7 @@ fn main() {
             .with_samples(1000)
             .run(|| rrt_connect(
                 start,
-                goal,
+                &goal,
                 KdTreeMap::new(WeightedPoseDistance {
                     position_metric: SquaredEuclidean,
                     position_weight: 1.0,
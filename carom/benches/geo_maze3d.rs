@@ -79,7 +79,7 @@ fn main() {
     benches.push(Bench::new("geo_maze3d_rrtc").run(|| {
         rrt_connect(
             black_box(start),
-            black_box(goal),
+            &black_box(goal),
             KdTreeMap::new(SquaredEuclidean),
             &valid,
             &sampler,
@@ -101,7 +101,7 @@ fn main() {
     benches.push(Bench::new("geo_maze3d_rrtc_kiddo").run(|| {
         rrt_connect(
             black_box(start),
-            black_box(goal),
+            &black_box(goal),
             rumple::nn::KiddoMap::<_, 3, SquaredEuclidean>::new(),
             &valid,
             &sampler,
@@ -130,7 +130,7 @@ fn main() {
     benches.push(Bench::new("geo_maze3d_rrtc_simd").run(|| {
         rrt_connect(
             black_box(start),
-            black_box(goal),
+            &black_box(goal),
             KdTreeMap::new(SquaredEuclidean),
             &rake_valid,
             &sampler,
@@ -152,7 +152,7 @@ fn main() {
     benches.push(Bench::new("geo_maze3d_rrtc_simd_kiddo").run(|| {
         rrt_connect(
             black_box(start),
-            black_box(goal),
+            &black_box(goal),
             rumple::nn::KiddoMap::<_, 3, SquaredEuclidean>::new(),
             &rake_valid,
             &sampler,
@@ -4,6 +4,7 @@ use rand::SeedableRng;
 use rand_chacha::ChaCha20Rng;
 use rumple::{
     geo::rrt_connect,
+    metric::SquaredEuclidean,
     nn::KiddoMap,
     sample::Rectangle,
     space::Vector,
@@ -52,8 +53,8 @@ fn main() {
     benches.push(Bench::new("panda_sphere_cage").run(|| {
         rrt_connect(
             q_start,
-            q_end,
-            KiddoMap::new(),
+            &q_end,
+            KiddoMap::<_, 7, SquaredEuclidean>::new(),
             &rake,
             &Rectangle {
                 min: Panda::BOUNDS[0],
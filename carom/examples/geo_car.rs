@@ -4,10 +4,9 @@ use rand::SeedableRng;
 use rand_chacha::ChaCha20Rng;
 use rumple::{
     geo::RrtConnect,
-    metric::SquaredEuclidean,
     nn::KdTreeMap,
     sample::Rectangle,
-    space::{Angle, Pose2d, PoseRadius, Vector, WeightedPoseDistance},
+    space::{Angle, CurveStep, Pose2d, ReedsShepp, ReedsSheppPose, Vector},
     time::Solved,
     valid::SampleInterpolate,
 };
@@ -28,48 +27,32 @@ fn main() {
     env.add_aabb(5.0, 8.0, 9.0, 9.0);
     env.add_aabb(8.0, 5.0, 9.0, 8.0);
 
-    let start = Pose2d {
+    let start = ReedsSheppPose(Pose2d {
         position: Vector([2.0, 3.0]),
         angle: Angle::new(f64::PI() / 2.0),
-    };
-    let goal = Pose2d {
+    });
+    let goal = ReedsSheppPose(Pose2d {
         position: Vector([7.0, 3.0]),
         angle: Angle::new(f64::PI() / 2.0),
+    });
+    let curve = ReedsShepp {
+        turning_radius: 0.5,
     };
 
     let half_w = 0.5;
     let half_h = 0.25;
 
     let valid = SampleInterpolate::new(
-        |&Pose2d {
+        |&ReedsSheppPose(Pose2d {
              position: Vector([x, y]),
              angle,
-         }: &Pose2d| {
-            dbg!(x, y, angle);
-            !env.collides_rect(x, y, angle.get(), half_w, half_h)
-        },
-        PoseRadius {
-            angle_dist: f64::PI() / 180.0,
-            position_dist: 1.0,
-        },
+         }): &ReedsSheppPose| { !env.collides_rect(x, y, angle.get(), half_w, half_h) },
+        CurveStep { curve, step: 0.05 },
     );
 
-    let grow_radius = PoseRadius {
-        angle_dist: f64::PI() / 4.0,
-        position_dist: 2.0,
-    };
+    let grow_radius = CurveStep { curve, step: 2.0 };
 
-    let mut rrtc = RrtConnect::new(
-        KdTreeMap::new(WeightedPoseDistance {
-            position_metric: SquaredEuclidean,
-            position_weight: 1.0,
-            angle_metric: SquaredEuclidean,
-            angle_weight: 1.0,
-        }),
-        start,
-        goal,
-        &valid,
-    );
+    let mut rrtc = RrtConnect::new(KdTreeMap::new(curve), start, goal, &valid);
 
     let _traj = rrtc
         .grow(
@@ -12,6 +12,7 @@ use rand_chacha::ChaCha20Rng;
 use rubullet::{nalgebra::Isometry3, MultiBodyOptions, UrdfOptions};
 use rumple::{
     geo::rrt_connect,
+    metric::SquaredEuclidean,
     nn::KiddoMap,
     sample::Rectangle,
     space::{Interpolate, Vector},
@@ -70,8 +71,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut node_limit = LimitNodes::new(1_000_000);
     let traj = rrt_connect(
         q_start,
-        q_end,
-        KiddoMap::new(),
+        &q_end,
+        KiddoMap::<_, 7, SquaredEuclidean>::new(),
         &rake,
         &Rectangle {
             min: Panda::BOUNDS[0],
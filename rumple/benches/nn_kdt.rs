@@ -0,0 +1,69 @@
+use core::hint::black_box;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use rumple::{
+    metric::SquaredEuclidean,
+    nn::{KdTreeMap, NearestNeighborsMap},
+    sample::{Rectangle, Sample},
+    space::Vector,
+};
+
+use brunch::{Bench, Benches};
+
+type F = f32;
+const N: usize = 3;
+
+fn main() {
+    let mut benches = Benches::default();
+    for n in [10_000, 100_000] {
+        let walk = random_walk(n);
+        benches.push(
+            Bench::new(format!("kdt_insert_random_walk_{n}"))
+                .with_samples(50)
+                .run(|| insert_all(black_box(&walk))),
+        );
+        let points = uniform(n);
+        benches.push(
+            Bench::new(format!("kdt_insert_uniform_{n}"))
+                .with_samples(50)
+                .run(|| insert_all(black_box(&points))),
+        );
+    }
+    benches.finish();
+}
+
+/// Generate `n` points, each of which takes a short step from a random earlier point, as the
+/// samples added to an RRT do.
+fn random_walk(n: usize) -> Vec<Vector<N, F>> {
+    let mut rng = ChaCha20Rng::seed_from_u64(2707);
+    let step = Rectangle {
+        min: Vector::new([-0.05; N]),
+        max: Vector::new([0.05; N]),
+    };
+    let mut points = vec![Vector::new([0.0; N])];
+    while points.len() < n {
+        let Vector(base) = points[rng.gen_range(0..points.len())];
+        let Vector(d) = step.sample(&mut rng);
+        points.push(Vector::new(core::array::from_fn(|i| base[i] + d[i])));
+    }
+    points
+}
+
+/// Generate `n` points uniformly at random in a box.
+fn uniform(n: usize) -> Vec<Vector<N, F>> {
+    let mut rng = ChaCha20Rng::seed_from_u64(2707);
+    let region = Rectangle {
+        min: Vector::new([-10.0; N]),
+        max: Vector::new([10.0; N]),
+    };
+    (0..n).map(|_| region.sample(&mut rng)).collect()
+}
+
+#[inline(never)]
+fn insert_all(points: &[Vector<N, F>]) -> KdTreeMap<Vector<N, F>, usize, SquaredEuclidean> {
+    let mut t = KdTreeMap::new(SquaredEuclidean);
+    for (i, &p) in points.iter().enumerate() {
+        t.insert(p, i);
+    }
+    t
+}
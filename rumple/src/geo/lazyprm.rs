@@ -0,0 +1,310 @@
+//! Lazy probabilistic roadmaps.
+
+use core::ops::Add;
+
+use alloc::vec::Vec;
+use num_traits::Zero;
+
+use crate::{
+    metric::Metric,
+    nn::{NearestEntry, RangeNearestNeighborsMap},
+    sample::Sample,
+    time::Timeout,
+    valid::GeoValidate,
+};
+
+use super::{
+    prm::{a_star, SetForest},
+    Graph,
+};
+
+/// A lazy probabilistic roadmap, which defers edge validation until query time.
+///
+/// Unlike [`Prm`](super::Prm), a `LazyPrm` connects each new node to all of its neighbors without
+/// checking whether the transitions between them are valid. When a path is requested, it searches
+/// over the unchecked roadmap, validates only the edges along the candidate path, and removes any
+/// invalid edges before searching again. This is useful when edge validation is expensive and
+/// most edges of the roadmap will never be used by a query.
+///
+/// # Generic parameters
+///
+/// - `C`: The configurations of the robot.
+/// - `NN`: The nearest-neighbor data structure to use. To be useful, `NN` should implement
+///   [`RangeNearestNeighborsMap`].
+/// - `V`: The state validator. `V` should implement [`GeoValidate`].
+///
+/// # Citation
+///
+/// ```bibtex
+/// @inproceedings{bohlin2000path,
+///   title={Path planning using lazy PRM},
+///   author={Bohlin, Robert and Kavraki, Lydia E},
+///   booktitle={Proceedings 2000 ICRA. Millennium Conference. IEEE International Conference on Robotics and Automation. Symposia Proceedings (Cat. No. 00CH37065)},
+///   volume={1},
+///   pages={521--528},
+///   year={2000},
+///   organization={IEEE}
+/// }
+/// ```
+pub struct LazyPrm<'a, C, NN, V> {
+    /// List of configurations for each node.
+    configurations: Vec<C>,
+    /// Adjacency list of nodes.
+    edges: Vec<Vec<Edge>>,
+    /// The connected components of the unchecked roadmap.
+    /// Since edges may be removed after they are found to be invalid, two nodes in the same
+    /// component are not necessarily connected.
+    components: SetForest,
+    nn: NN,
+    valid: &'a V,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// An edge in a [`LazyPrm`].
+struct Edge {
+    /// The node at the other end of this edge.
+    node: usize,
+    /// Whether this edge is known to be valid.
+    checked: bool,
+}
+
+impl<'a, C, NN, V> LazyPrm<'a, C, NN, V> {
+    #[must_use]
+    /// Construct a new lazy PRM.
+    pub const fn new(nn: NN, valid: &'a V) -> Self {
+        Self {
+            configurations: Vec::new(),
+            edges: Vec::new(),
+            components: SetForest::new(),
+            nn,
+            valid,
+        }
+    }
+
+    /// Grow this PRM until `timeout` runs out, connecting all nodes within `radius` of one another.
+    /// Generated nodes will only be sampled from `sample` using `rng` as the source of randomness.
+    pub fn grow_r<R, TC, S, RNG>(&mut self, radius: R, timeout: &mut TC, sample: &S, rng: &mut RNG)
+    where
+        V: GeoValidate<C>,
+        NN: RangeNearestNeighborsMap<C, usize, Distance = R>,
+        TC: Timeout,
+        S: Sample<C, RNG>,
+        C: Clone,
+        R: Clone,
+    {
+        while !timeout.is_over() {
+            timeout.update_sample_count(1);
+            let c = sample.sample(rng);
+            if self.insert_r(c, radius.clone()).is_some() {
+                timeout.update_node_count(1);
+            }
+        }
+    }
+
+    /// Insert a configuration into the graph, connecting it to all other nodes in the graph within
+    /// a distance of `radius` without checking the validity of the transitions. Returns the ID of
+    /// the node it created, or `None` if the given configuration was invalid.
+    pub fn insert_r<R>(&mut self, c: C, radius: R) -> Option<usize>
+    where
+        V: GeoValidate<C>,
+        NN: RangeNearestNeighborsMap<C, usize, Distance = R>,
+        C: Clone,
+    {
+        if !self.valid.is_valid_configuration(&c) {
+            return None;
+        }
+
+        let i = self.edges.len();
+        self.edges.push(Vec::new());
+        let new_component = self.components.create();
+        for n in self.nn.nearest_within_r(&c, radius).map(|e| *e.value()) {
+            self.components.unify(new_component, n);
+            // assume bidirectionality
+            self.edges[i].push(Edge {
+                node: n,
+                checked: false,
+            });
+            self.edges[n].push(Edge {
+                node: i,
+                checked: false,
+            });
+        }
+
+        self.nn.insert(c.clone(), i);
+        self.configurations.push(c);
+        debug_assert_eq!(
+            self.edges.len(),
+            self.configurations.len(),
+            "configuration and edge buffers must have equal length"
+        );
+        Some(i)
+    }
+
+    /// Get the configuration in the graph corresponding to the given node ID.
+    ///
+    /// Returns `None` if no such node with the given ID exists.
+    pub fn configuration(&self, id: usize) -> Option<&C> {
+        self.configurations.get(id)
+    }
+
+    /// Compute a valid path between `start` and `end`.
+    ///
+    /// This will repeatedly search for a path over the roadmap and validate the edges along it,
+    /// removing any invalid edges from the roadmap, until either a valid path is found or no more
+    /// candidate paths remain.
+    ///
+    /// # Panics
+    ///
+    /// This function may panic if `start` or `end` point to nodes which do not exist in `self`.
+    pub fn path<M, D>(&mut self, start: usize, end: usize, cost: &M) -> Option<Vec<usize>>
+    where
+        V: GeoValidate<C>,
+        M: Metric<C, Distance = D>,
+        D: Add + Zero + PartialOrd + Clone,
+    {
+        assert!(
+            (0..self.configurations.len()).contains(&start),
+            "invalid start configuration ID"
+        );
+        assert!(
+            (0..self.configurations.len()).contains(&end),
+            "invalid end configuration ID"
+        );
+
+        if self.components.find(start) != self.components.find(end) {
+            // different components - no solution exists
+            return None;
+        }
+
+        'search: loop {
+            let path = a_star(
+                &self.configurations,
+                |n| self.edges[n].iter().map(|e| e.node),
+                start,
+                end,
+                cost,
+            )?;
+
+            for w in path.windows(2) {
+                let (a, b) = (w[0], w[1]);
+                let idx = self.edge_index(a, b);
+                if self.edges[a][idx].checked {
+                    continue;
+                }
+                let rev_idx = self.edge_index(b, a);
+                if self
+                    .valid
+                    .is_valid_transition(&self.configurations[a], &self.configurations[b])
+                {
+                    self.edges[a][idx].checked = true;
+                    self.edges[b][rev_idx].checked = true;
+                } else {
+                    self.edges[a].swap_remove(idx);
+                    self.edges[b].swap_remove(rev_idx);
+                    continue 'search;
+                }
+            }
+
+            return Some(path);
+        }
+    }
+
+    /// Get the index of the edge from `a` to `b` in `a`'s adjacency list.
+    fn edge_index(&self, a: usize, b: usize) -> usize {
+        self.edges[a]
+            .iter()
+            .position(|e| e.node == b)
+            .expect("edge must exist in both directions")
+    }
+}
+
+impl<C, NN, V> Graph for LazyPrm<'_, C, NN, V> {
+    type Node = usize;
+    type Configuration = C;
+
+    fn configuration(&self, node: Self::Node) -> &Self::Configuration {
+        self.configuration(node).unwrap()
+    }
+
+    fn neighbors(&self, node: Self::Node) -> impl IntoIterator<Item = Self::Node> {
+        self.edges[node].iter().map(|e| e.node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use crate::{
+        metric::SquaredEuclidean,
+        nn::KdTreeMap,
+        sample::Rectangle,
+        space::Vector,
+        time::LimitNodes,
+        valid::{GeoValidate, SampleInterpolate, Validate},
+    };
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    use super::LazyPrm;
+
+    /// A validator which counts how many transitions it has checked.
+    struct CountTransitions<V> {
+        valid: V,
+        n_transitions: Cell<usize>,
+    }
+
+    impl<C, V: Validate<C>> Validate<C> for CountTransitions<V> {
+        fn is_valid_configuration(&self, c: &C) -> bool {
+            self.valid.is_valid_configuration(c)
+        }
+    }
+
+    impl<C, V: GeoValidate<C>> GeoValidate<C> for CountTransitions<V> {
+        fn is_valid_transition(&self, start: &C, end: &C) -> bool {
+            self.n_transitions.set(self.n_transitions.get() + 1);
+            self.valid.is_valid_transition(start, end)
+        }
+    }
+
+    #[test]
+    fn lazy_prm_wall() {
+        let r = 0.01;
+        // a wall at x = 0.5 with a gap at the top
+        let valid = CountTransitions {
+            valid: SampleInterpolate::new(
+                |c: &Vector<2, f64>| !((0.45..=0.55).contains(&c[0]) && c[1] < 0.8),
+                0.01,
+            ),
+            n_transitions: Cell::new(0),
+        };
+        let mut prm = LazyPrm::new(KdTreeMap::new(SquaredEuclidean), &valid);
+        let start = prm.insert_r(Vector::new([0.0, 0.0]), r).unwrap();
+        let end = prm.insert_r(Vector::new([1.0, 0.0]), r).unwrap();
+        prm.grow_r(
+            r,
+            &mut LimitNodes::new(1_000),
+            &Rectangle {
+                min: Vector::new([0.0; 2]),
+                max: Vector::new([1.0; 2]),
+            },
+            &mut ChaCha20Rng::seed_from_u64(2707),
+        );
+        // transitions are only checked once a path is requested
+        assert_eq!(valid.n_transitions.get(), 0);
+        let path = prm
+            .path(start, end, &SquaredEuclidean)
+            .expect("unable to find path");
+        assert!(valid.n_transitions.get() > 0);
+
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&end));
+        assert!(
+            path.windows(2).all(|w| valid.is_valid_transition(
+                prm.configuration(w[0]).unwrap(),
+                prm.configuration(w[1]).unwrap()
+            )),
+            "all transitions on the path must be valid"
+        );
+    }
+}
@@ -5,13 +5,19 @@
 //! interpolation between any state. This module contains implementations of common geometric
 //! planning algorithms as well as some useful primitives for working with geometric states.
 
+mod lazyprm;
 mod prm;
 mod rrt;
 mod rrtc;
+mod rrtstar;
+mod simplify;
 
+pub use lazyprm::LazyPrm;
 pub use prm::Prm;
 pub use rrt::{rrt, Rrt};
-pub use rrtc::{rrt_connect, RrtConnect};
+pub use rrtc::{rrt_connect, rrt_connect_multi, RrtConnect, RrtConnectSolution};
+pub use rrtstar::{rrt_star, RewireRadius, RrtStar};
+pub use simplify::{partial_shortcut, reduce_vertices, shortcut};
 
 /// A generic trait for planners which are geometric graphs.
 pub trait Graph {
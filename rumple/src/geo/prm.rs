@@ -1,10 +1,11 @@
 use alloc::collections::BinaryHeap;
-use core::{fmt::Debug, iter, mem::swap, ops::Add};
+use core::{cmp::Reverse, fmt::Debug, iter, mem::swap, ops::Add};
 
 use alloc::vec::Vec;
 use num_traits::Zero;
 
 use crate::{
+    goal::Goal,
     metric::Metric,
     nn::{NearestEntry, RangeNearestNeighborsMap},
     sample::Sample,
@@ -50,7 +51,7 @@ use super::Graph;
 #[derive(Clone, Debug)]
 /// A disjoint set forest.
 /// todo: should we move away from the S-o-A structure? Probably not, this is likely faster.
-struct SetForest {
+pub(super) struct SetForest {
     /// Integers representing the component each node belongs to.
     /// This is a random set forest, if `components[i] != i`, `i` belongs to the same component as
     /// `components[i]`.
@@ -59,6 +60,9 @@ struct SetForest {
     /// component. The size is only cached for true representatives, so you must find the
     /// representatitive node ID for a component before checking its size.
     sizes: Vec<usize>,
+    /// For some representative node `i`, `marked[i]` is whether any node in its component has
+    /// been marked. Like `sizes`, this is only cached for true representatives.
+    marked: Vec<bool>,
 }
 
 impl<'a, C, NN, V> Prm<'a, C, NN, V> {
@@ -125,6 +129,52 @@ impl<'a, C, NN, V> Prm<'a, C, NN, V> {
         }
     }
 
+    /// Grow this PRM while attempting to solve a problem connecting `start` to any configuration
+    /// which satisfies `goal`, connecting nodes within a radius of `radius` of each other.
+    /// This will only terminate when `timeout` is over.
+    ///
+    /// Every node already in the PRM is checked against `goal` at the start of each call, so
+    /// successive calls may use different goals.
+    pub fn grow_r_solve_goal<R, TC, S, G, RNG>(
+        &mut self,
+        radius: R,
+        timeout: &mut TC,
+        sample: &S,
+        rng: &mut RNG,
+        start: usize,
+        goal: &G,
+    ) where
+        V: GeoValidate<C>,
+        NN: RangeNearestNeighborsMap<C, usize, Distance = R>,
+        TC: Timeout,
+        S: Sample<C, RNG>,
+        G: Goal<C>,
+        C: Clone,
+        R: Clone,
+    {
+        // marks from an earlier call may have been for a different goal
+        self.components.clear_marks();
+        for (i, c) in self.configurations.iter().enumerate() {
+            if goal.is_satisfied(c) {
+                self.components.mark(i);
+            }
+        }
+        while !timeout.is_over() {
+            timeout.update_sample_count(1);
+            let c = sample.sample(rng);
+            let is_goal = goal.is_satisfied(&c);
+            if let Some(i) = self.insert_r(c, radius.clone()) {
+                timeout.update_node_count(1);
+                if is_goal {
+                    self.components.mark(i);
+                }
+            }
+            if self.components.is_marked(start) {
+                timeout.notify_solved();
+            }
+        }
+    }
+
     /// Insert a configuration into the graph, connecting it to all other nodes in the graph within
     /// a distance of `radius`. Returns the ID of the node it created, or `None` if the given
     /// configuration was invalid.
@@ -194,56 +244,81 @@ impl<'a, C, NN, V> Prm<'a, C, NN, V> {
             return None;
         }
 
-        // open may include duplicate entries if we find alternate paths to open nodes
+        Some(
+            a_star(
+                &self.configurations,
+                |n| self.edges[n].iter().copied(),
+                start,
+                end,
+                cost,
+            )
+            .expect("if start and goal are in same connected component, A* must find a path"),
+        )
+    }
+}
+
+impl<C, NN, V> Prm<'_, C, NN, V> {
+    /// Compute the lowest-cost path from `start` to any node which satisfies `goal`.
+    /// Returns `None` if no such node is connected to `start`.
+    ///
+    /// # Panics
+    ///
+    /// This function may panic if `start` points to a node which does not exist in `self`.
+    pub fn path_to_goal<G, M, D>(&self, start: usize, goal: &G, cost: &M) -> Option<Vec<usize>>
+    where
+        G: Goal<C>,
+        M: Metric<C, Distance = D>,
+        D: Add + Zero + PartialOrd + Clone,
+    {
+        assert!(
+            (0..self.configurations.len()).contains(&start),
+            "invalid start configuration ID"
+        );
+
+        // use Dijkstra's algorithm, since there is no single goal to guide a heuristic
         let mut open = BinaryHeap::new();
-        let mut parent = vec![0; self.configurations.len()];
-        let mut g_score: Vec<_> = iter::repeat_with(|| None)
+        let mut parent = vec![usize::MAX; self.configurations.len()];
+        let mut g_score: Vec<Option<D>> = iter::repeat_with(|| None)
             .take(self.configurations.len())
             .collect();
-        g_score[end] = Some(D::zero());
-
-        // plan from goal to start to save a reversal
-        let end_c = &self.configurations[end];
-
-        open.push(Open {
-            node: end,
+        g_score[start] = Some(D::zero());
+        open.push(Reverse(Open {
             f_score: D::zero(),
-        });
-
-        while let Some(Open { node, .. }) = open.pop() {
-            if node == start {
-                // done
-                let mut traj = vec![start];
-                let mut n = start;
-                while n != end {
+            node: start,
+        }));
+
+        while let Some(Reverse(Open { f_score, node })) = open.pop() {
+            if g_score[node].as_ref().is_some_and(|g| g < &f_score) {
+                // stale entry for a node which has since been reached more cheaply
+                continue;
+            }
+            let nc = &self.configurations[node];
+            if goal.is_satisfied(nc) {
+                let mut traj = vec![node];
+                let mut n = node;
+                while n != start {
                     n = parent[n];
                     traj.push(n);
                 }
+                traj.reverse();
                 return Some(traj);
             }
-            let nc = &self.configurations[node];
 
             for &neighbor in &self.edges[node] {
-                let nbr_c = &self.configurations[neighbor];
-                let new_g_score = cost.distance(nbr_c, nc)
-                    + g_score[node]
-                        .clone()
-                        .expect("nodes in open set must have extant g-score");
-                if g_score[neighbor]
-                    .as_ref().is_none_or(|d| &new_g_score < d)
-                {
-                    // found a shorter path to neighbor
+                let new_g_score =
+                    f_score.clone() + cost.distance(nc, &self.configurations[neighbor]);
+                if g_score[neighbor].as_ref().is_none_or(|d| &new_g_score < d) {
                     parent[neighbor] = node;
                     g_score[neighbor] = Some(new_g_score.clone());
-                    open.push(Open {
+                    open.push(Reverse(Open {
+                        f_score: new_g_score,
                         node: neighbor,
-                        f_score: new_g_score + cost.distance(nbr_c, end_c),
-                    });
+                    }));
                 }
             }
         }
 
-        unreachable!("if start and goal are in same connected component, A* must find a path");
+        None
     }
 }
 
@@ -260,12 +335,88 @@ impl<C, NN, V> Graph for Prm<'_, C, NN, V> {
     }
 }
 
+/// Use A* to search for a path from `start` to `end` in the graph whose configurations are
+/// `configurations` and whose adjacency is given by `neighbors`.
+/// Returns `None` if no path exists.
+pub(super) fn a_star<C, M, D, N, I>(
+    configurations: &[C],
+    neighbors: N,
+    start: usize,
+    end: usize,
+    cost: &M,
+) -> Option<Vec<usize>>
+where
+    M: Metric<C, Distance = D>,
+    D: Add + Zero + PartialOrd + Clone,
+    N: Fn(usize) -> I,
+    I: IntoIterator<Item = usize>,
+{
+    // open may include duplicate entries if we find alternate paths to open nodes
+    let mut open = BinaryHeap::new();
+    let mut parent = vec![0; configurations.len()];
+    let mut closed = vec![false; configurations.len()];
+    let mut g_score: Vec<_> = iter::repeat_with(|| None)
+        .take(configurations.len())
+        .collect();
+    g_score[end] = Some(D::zero());
+
+    // plan from goal to start to save a reversal, so the heuristic is the distance to the start
+    let start_c = &configurations[start];
+
+    open.push(Reverse(Open {
+        node: end,
+        f_score: D::zero(),
+    }));
+
+    while let Some(Reverse(Open { node, .. })) = open.pop() {
+        if closed[node] {
+            // stale entry for a node which has already been expanded
+            continue;
+        }
+        closed[node] = true;
+        if node == start {
+            // done
+            let mut traj = vec![start];
+            let mut n = start;
+            while n != end {
+                n = parent[n];
+                traj.push(n);
+            }
+            return Some(traj);
+        }
+        let nc = &configurations[node];
+
+        for neighbor in neighbors(node) {
+            if closed[neighbor] {
+                continue;
+            }
+            let nbr_c = &configurations[neighbor];
+            let new_g_score = cost.distance(nbr_c, nc)
+                + g_score[node]
+                    .clone()
+                    .expect("nodes in open set must have extant g-score");
+            if g_score[neighbor].as_ref().is_none_or(|d| &new_g_score < d) {
+                // found a shorter path to neighbor
+                parent[neighbor] = node;
+                g_score[neighbor] = Some(new_g_score.clone());
+                open.push(Reverse(Open {
+                    node: neighbor,
+                    f_score: new_g_score + cost.distance(nbr_c, start_c),
+                }));
+            }
+        }
+    }
+
+    None
+}
+
 impl SetForest {
     #[must_use]
     pub const fn new() -> Self {
         Self {
             parents: Vec::new(),
             sizes: Vec::new(),
+            marked: Vec::new(),
         }
     }
 
@@ -273,6 +424,7 @@ impl SetForest {
         let i = self.parents.len();
         self.parents.push(i);
         self.sizes.push(1);
+        self.marked.push(false);
         i
     }
 
@@ -280,7 +432,7 @@ impl SetForest {
     /// internally updating the disjoint-set data structure.
     /// TODO make `components` mutexed so this function can be called with `&self`?
     /// Alternately, use atomic operations?
-    fn find_cache(&mut self, mut x: usize) -> usize {
+    pub fn find_cache(&mut self, mut x: usize) -> usize {
         let root = self.find(x);
 
         // downward pass - simplify path
@@ -295,7 +447,7 @@ impl SetForest {
 
     /// Find the representative node ID for the graph component containing `node`,
     /// without updating the cache.
-    fn find(&self, x: usize) -> usize {
+    pub fn find(&self, x: usize) -> usize {
         let mut root = x;
 
         // upward pass - find root
@@ -320,9 +472,27 @@ impl SetForest {
         }
         self.parents[b_root] = a_root;
         self.sizes[a_root] += self.sizes[b_root];
+        self.marked[a_root] |= self.marked[b_root];
 
         a_root
     }
+
+    /// Mark the set containing `x`.
+    pub fn mark(&mut self, x: usize) {
+        let root = self.find_cache(x);
+        self.marked[root] = true;
+    }
+
+    /// Unmark every set.
+    pub fn clear_marks(&mut self) {
+        self.marked.fill(false);
+    }
+
+    /// Determine whether any node in the set containing `x` has been marked.
+    pub fn is_marked(&mut self, x: usize) -> bool {
+        let root = self.find_cache(x);
+        self.marked[root]
+    }
 }
 
 #[derive(Clone, Copy, PartialEq)]
@@ -351,18 +521,34 @@ impl<D: PartialOrd + PartialEq> Ord for Open<D> {
 #[cfg(test)]
 mod tests {
     use crate::{
-        metric::{Metric, SquaredEuclidean},
+        goal::{Goal, Tolerance},
+        metric::{Euclidean, Metric, SquaredEuclidean},
         nn::KdTreeMap,
         sample::Rectangle,
         space::Vector,
-        time::{LimitNodes, Solved},
+        time::{Any, LimitNodes, LimitSamples, Solved},
         valid::AlwaysValid,
     };
     use alloc::vec::Vec;
     use rand::SeedableRng;
     use rand_chacha::ChaCha20Rng;
 
-    use super::Prm;
+    use super::{Prm, SetForest};
+    use crate::geo::LazyPrm;
+
+    #[test]
+    fn marks_follow_unions() {
+        let mut forest = SetForest::new();
+        let ids: Vec<usize> = (0..4).map(|_| forest.create()).collect();
+        forest.mark(ids[3]);
+        assert!(!forest.is_marked(ids[0]));
+        forest.unify(ids[0], ids[1]);
+        forest.unify(ids[2], ids[3]);
+        assert!(!forest.is_marked(ids[1]));
+        assert!(forest.is_marked(ids[2]));
+        forest.unify(ids[1], ids[2]);
+        assert!(ids.iter().all(|&i| forest.is_marked(i)));
+    }
 
     #[test]
     fn prm2d() {
@@ -444,4 +630,109 @@ mod tests {
             "all transitions must be within growth radius"
         );
     }
+
+    #[test]
+    fn prm_goal_region() {
+        let r = 0.05;
+        let mut prm: Prm<Vector<2>, _, _> =
+            Prm::new(KdTreeMap::new(SquaredEuclidean), &AlwaysValid);
+        let start = prm.insert_r(Vector::new([0.0, 0.0]), r).unwrap();
+        let goal = Tolerance {
+            center: Vector::new([1.0, 1.0]),
+            metric: Euclidean,
+            tolerance: 0.1,
+        };
+        prm.grow_r_solve_goal(
+            r,
+            &mut Solved::new(),
+            &Rectangle {
+                min: Vector::new([0.0; 2]),
+                max: Vector::new([1.0; 2]),
+            },
+            &mut ChaCha20Rng::seed_from_u64(2707),
+            start,
+            &goal,
+        );
+        let path = prm
+            .path_to_goal(start, &goal, &Euclidean)
+            .expect("unable to find path");
+
+        assert_eq!(path[0], start);
+        let last = prm.configuration(*path.last().unwrap()).unwrap();
+        assert!(goal.is_satisfied(last));
+        // no node along the way may satisfy the goal, or the path would not be shortest
+        assert!(path[..path.len() - 1]
+            .iter()
+            .all(|&id| !goal.is_satisfied(prm.configuration(id).unwrap())));
+        assert!(path.windows(2).all(|a| {
+            SquaredEuclidean.distance(
+                prm.configuration(a[0]).unwrap(),
+                prm.configuration(a[1]).unwrap(),
+            ) <= r
+        }));
+
+        // a later call with a different goal must not be solved by the old goal's nodes
+        let mut timeout = Any((Solved::new(), LimitSamples::new(100)));
+        prm.grow_r_solve_goal(
+            r,
+            &mut timeout,
+            &Rectangle {
+                min: Vector::new([0.0; 2]),
+                max: Vector::new([1.0; 2]),
+            },
+            &mut ChaCha20Rng::seed_from_u64(2707),
+            start,
+            &|c: &Vector<2>| c[0] < 0.0,
+        );
+        assert_eq!(timeout.0 .1.n_sampled(), 100);
+    }
+
+    #[test]
+    fn path_is_shortest() {
+        let r = 0.003;
+        let region = Rectangle {
+            min: Vector::new([0.0; 2]),
+            max: Vector::new([1.0; 2]),
+        };
+        let mut prm: Prm<Vector<2>, _, _> =
+            Prm::new(KdTreeMap::new(SquaredEuclidean), &AlwaysValid);
+        let mut lazy = LazyPrm::new(KdTreeMap::new(SquaredEuclidean), &AlwaysValid);
+        for roadmap_start in [Vector::new([0.0, 0.0]), Vector::new([1.0, 1.0])] {
+            prm.insert_r(roadmap_start, r).unwrap();
+            lazy.insert_r(roadmap_start, r).unwrap();
+        }
+        // with the same samples and no invalid edges, both roadmaps are the same
+        prm.grow_r(
+            r,
+            &mut LimitNodes::new(3_000),
+            &region,
+            &mut ChaCha20Rng::seed_from_u64(2707),
+        );
+        lazy.grow_r(
+            r,
+            &mut LimitNodes::new(3_000),
+            &region,
+            &mut ChaCha20Rng::seed_from_u64(2707),
+        );
+
+        let cost = |path: &[usize]| -> f64 {
+            path.windows(2)
+                .map(|w| {
+                    Euclidean.distance(
+                        prm.configuration(w[0]).unwrap(),
+                        prm.configuration(w[1]).unwrap(),
+                    )
+                })
+                .sum()
+        };
+        let end = *prm.configuration(1).unwrap();
+        let best = cost(
+            &prm.path_to_goal(0, &|c: &Vector<2>| *c == end, &Euclidean)
+                .expect("unable to find path"),
+        );
+        let path = prm.path(0, 1, &Euclidean).expect("unable to find path");
+        assert!((cost(&path) - best).abs() < 1e-9);
+        let lazy_path = lazy.path(0, 1, &Euclidean).expect("unable to find path");
+        assert!((cost(&lazy_path) - best).abs() < 1e-9);
+    }
 }
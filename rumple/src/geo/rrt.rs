@@ -1,4 +1,5 @@
 use crate::{
+    goal::SampleGoal,
     nn::{NearestEntry, NearestNeighborsMap},
     sample::Sample,
     space::Interpolate,
@@ -51,12 +52,12 @@ pub struct Rrt<'a, C, NN, V> {
 /// - `start`: The start configuration.
 /// - `valid`: The state validator.
 /// - `space_sampler`: A sampler for states in the configuration space.
-/// - `goal`: The goal state or sampler for goal states.
+/// - `goal`: The goal which the RRT must reach. Any node which satisfies it is a solution.
 /// - `radius`: The radius by which to expand the RRT.
 /// - `timeout`: The timeout condition. The planning algorithm will continue until `timeout` is
 ///   over.
 /// - `target_goal_distn`: A sampler which returns `true` with some probability; every time it
-///   returns `true`, the RRT grows toward the goal instead of to fill the space.
+///   returns `true`, the RRT grows toward a sample from the goal instead of to fill the space.
 /// - `rng`: The source of randomness.
 pub fn rrt<C, NN, V, SP, G, TC, TG, R, RNG>(
     start: C,
@@ -73,7 +74,7 @@ where
     NN: NearestNeighborsMap<C, usize>,
     V: GeoValidate<C>,
     SP: Sample<C, RNG>,
-    G: Sample<C, RNG>,
+    G: SampleGoal<C, RNG>,
     R: Clone,
     C: Clone + Interpolate<Distance = R>,
     TC: Timeout,
@@ -122,7 +123,7 @@ impl<'a, C, NN, V> Rrt<'a, C, NN, V> {
     where
         V: GeoValidate<C>,
         SP: Sample<C, RNG>,
-        G: Sample<C, RNG>,
+        G: SampleGoal<C, RNG>,
         NN: NearestNeighborsMap<C, usize>,
         R: Clone,
         C: Clone + Interpolate<Distance = R>,
@@ -131,25 +132,28 @@ impl<'a, C, NN, V> Rrt<'a, C, NN, V> {
         if !self.valid.is_valid_configuration(&self.configurations[0]) {
             return None; // invalid configuration
         }
-        let mut soln = None;
+        let mut soln = goal.is_satisfied(&self.configurations[0]).then_some(0);
+        if soln.is_some() {
+            timeout.notify_solved();
+        }
         while !timeout.is_over() {
             timeout.update_sample_count(1);
-            let sample_goal = target_goal_distn.sample(rng);
-            let target = if sample_goal {
-                goal.sample(rng)
+            let goal_target = if target_goal_distn.sample(rng) {
+                goal.sample_goal(rng)
             } else {
-                space_sampler.sample(rng)
+                None
             };
+            let target = goal_target.unwrap_or_else(|| space_sampler.sample(rng));
             let &start_id = self
                 .nn
                 .nearest(&target)
                 .expect("NN must always have elements")
                 .value();
             let start_cfg = &self.configurations[start_id];
-            let (reached, end_cfg) = start_cfg
+            let end_cfg = start_cfg
                 .interpolate(&target, radius.clone())
                 .next()
-                .map_or_else(|| (true, target.clone()), |c| (false, c));
+                .unwrap_or_else(|| target.clone());
             if !self.valid.is_valid_configuration(&end_cfg)
                 || !self.valid.is_valid_transition(start_cfg, &end_cfg)
             {
@@ -164,8 +168,9 @@ impl<'a, C, NN, V> Rrt<'a, C, NN, V> {
                 self.parent_ids.len(),
                 "number of configurations and parents must be equal"
             );
+            let reached = goal.is_satisfied(&end_cfg);
             self.nn.insert(end_cfg, new_id);
-            if sample_goal && reached {
+            if reached {
                 timeout.notify_solved();
                 soln = Some(new_id);
             }
@@ -179,12 +184,12 @@ impl<'a, C, NN, V> Rrt<'a, C, NN, V> {
     /// # Parameters
     ///
     /// - `space_sampler`: A sampler for states in the configuration space.
-    /// - `goal`: The goal state or sampler for goal states.
+    /// - `goal`: The goal which the RRT must reach. Any node which satisfies it is a solution.
     /// - `radius`: The radius by which to expand the RRT.
     /// - `timeout`: The timeout condition. The planning algorithm will continue until `timeout` is
     ///   over.
     /// - `target_goal_distn`: A sampler which returns `true` with some probability; every time it
-    ///   returns `true`, the RRT grows toward the goal instead of to fill the space.
+    ///   returns `true`, the RRT grows toward a sample from the goal instead of to fill the space.
     /// - `rng`: The source of randomness.
     pub fn grow_toward<SP, G, TC, TG, R, RNG>(
         &mut self,
@@ -198,7 +203,7 @@ impl<'a, C, NN, V> Rrt<'a, C, NN, V> {
     where
         V: GeoValidate<C>,
         SP: Sample<C, RNG>,
-        G: Sample<C, RNG>,
+        G: SampleGoal<C, RNG>,
         TG: Sample<bool, RNG>,
         TC: Timeout,
         NN: NearestNeighborsMap<C, usize>,
@@ -222,3 +227,72 @@ impl<'a, C, NN, V> Rrt<'a, C, NN, V> {
         self.configurations.len()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        goal::Tolerance,
+        metric::{Euclidean, Metric},
+        nn::KdTreeMap,
+        sample::Rectangle,
+        space::Vector,
+        time::Solved,
+        valid::AlwaysValid,
+    };
+    use rand::{distributions::Bernoulli, SeedableRng};
+    use rand_chacha::ChaCha20Rng;
+
+    /// Plan with an RRT from the origin of the unit square to `goal`, and check that the path
+    /// reaches `goal` in steps no longer than the growth radius.
+    fn plan_to<G: SampleGoal<Vector<2>, ChaCha20Rng>>(goal: &G) {
+        let start = Vector::new([0.0, 0.0]);
+        let radius = 0.05;
+        let res = rrt(
+            start,
+            KdTreeMap::new(Euclidean),
+            &AlwaysValid,
+            &Rectangle {
+                min: Vector::new([0.0; 2]),
+                max: Vector::new([1.0; 2]),
+            },
+            goal,
+            radius,
+            &mut Solved::new(),
+            &Bernoulli::new(0.1).unwrap(),
+            &mut ChaCha20Rng::seed_from_u64(2707),
+        )
+        .unwrap();
+
+        assert_eq!(res[0], start);
+        assert!(goal.is_satisfied(res.last().unwrap()));
+        assert!(
+            res.windows(2)
+                .all(|a| Euclidean.distance(&a[0], &a[1]) <= radius + 1e-9),
+            "all transitions must be within growth radius"
+        );
+    }
+
+    #[test]
+    fn rrt_tolerance_goal() {
+        plan_to(&Tolerance {
+            center: Vector::new([0.9, 0.8]),
+            metric: Euclidean,
+            tolerance: 0.05,
+        });
+    }
+
+    #[test]
+    fn rrt_rectangle_goals() {
+        plan_to(&vec![
+            Rectangle {
+                min: Vector::new([0.8, 0.0]),
+                max: Vector::new([1.0, 0.1]),
+            },
+            Rectangle {
+                min: Vector::new([0.0, 0.9]),
+                max: Vector::new([0.2, 1.0]),
+            },
+        ]);
+    }
+}
@@ -3,11 +3,12 @@
 use alloc::vec::Vec;
 
 use crate::{
+    goal::SampleGoal,
     nn::{NearestEntry, NearestNeighborsMap},
     sample::Sample,
     space::Interpolate,
     time::Timeout,
-    valid::GeoValidate,
+    valid::{GeoValidate, Validate},
 };
 
 #[derive(Clone, Debug)]
@@ -29,6 +30,8 @@ use crate::{
 pub struct RrtConnect<'a, C, NN, V> {
     trees: [HalfTree<C, NN>; 2],
     cross_edges: Vec<(usize, usize)>,
+    /// The number of times a root has been sampled for the goal tree.
+    goal_samples: usize,
     valid: &'a V,
     next: u8,
 }
@@ -36,28 +39,46 @@ pub struct RrtConnect<'a, C, NN, V> {
 #[derive(Clone, Debug)]
 struct HalfTree<C, NN> {
     configurations: Vec<C>,
+    /// The parent of each node. Roots are their own parents.
     parents: Vec<usize>,
+    /// The IDs of the roots of this tree, in the order they were added.
+    roots: Vec<usize>,
     nn: NN,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// A path found by an [`RrtConnect`], along with the roots it connects.
+pub struct RrtConnectSolution<C> {
+    /// The configurations along the path, beginning at a start.
+    pub path: Vec<C>,
+    /// The index of the start at the beginning of `path`, in the order returned by
+    /// [`RrtConnect::starts`].
+    pub start: usize,
+    /// The index of the goal root at the end of `path`, in the order returned by
+    /// [`RrtConnect::goals`].
+    /// This is `None` if the tree grown from the start reached the goal on its own.
+    pub goal: Option<usize>,
+}
+
 #[expect(clippy::too_many_arguments)]
-/// Plan between two configurations using an [`RrtConnect`].
+/// Plan from a configuration to a goal using an [`RrtConnect`].
+///
+/// The tree grown from the goal is rooted at samples from `goal`.
+/// If `goal` cannot be sampled, only the tree grown from `start` can reach it.
 ///
 /// # Parameters
 ///
 /// - `start`: The start configuration.
+/// - `goal`: The goal which the planner must reach.
 /// - `valid`: The state validator.
 /// - `space_sampler`: A sampler for states in the configuration space.
-/// - `goal`: The goal state or sampler for goal states.
 /// - `radius`: The radius by which to expand the RRT.
 /// - `timeout`: The timeout condition. The planning algorithm will continue until `timeout` is
 ///   over.
-/// - `target_goal_distn`: A sampler which returns `true` with some probability; every time it
-///   returns `true`, the RRT grows toward the goal instead of to fill the space.
 /// - `rng`: The source of randomness.
-pub fn rrt_connect<C, NN, V, SP, TC, R, RNG>(
+pub fn rrt_connect<C, NN, V, G, SP, TC, R, RNG>(
     start: C,
-    goal: C,
+    goal: &G,
     nn: NN,
     valid: &V,
     space_sampler: &SP,
@@ -68,44 +89,140 @@ pub fn rrt_connect<C, NN, V, SP, TC, R, RNG>(
 where
     NN: NearestNeighborsMap<C, usize> + Clone,
     V: GeoValidate<C>,
+    G: SampleGoal<C, RNG>,
     SP: Sample<C, RNG>,
     R: Clone,
     C: Clone + Interpolate<Distance = R>,
     TC: Timeout,
 {
-    let mut trees = RrtConnect::new(nn, start, goal, valid);
-    trees.grow(space_sampler, radius, timeout, rng)
+    rrt_connect_multi(
+        [start],
+        goal,
+        nn,
+        valid,
+        space_sampler,
+        radius,
+        timeout,
+        rng,
+    )
+    .map(|soln| soln.path)
+}
+
+#[expect(clippy::too_many_arguments)]
+/// Plan from any of several configurations to a goal using an [`RrtConnect`].
+///
+/// The tree grown from `starts` has one root for each start, and the tree grown from the goal
+/// gains new roots sampled from `goal` as it grows.
+/// The returned solution records which start and goal root the path connects; starts are
+/// indexed in the order they are given.
+///
+/// # Parameters
+///
+/// - `starts`: The start configurations, all of which must be valid.
+/// - `goal`: The goal which the planner must reach.
+/// - `valid`: The state validator.
+/// - `space_sampler`: A sampler for states in the configuration space.
+/// - `radius`: The radius by which to expand the RRT.
+/// - `timeout`: The timeout condition. The planning algorithm will continue until `timeout` is
+///   over.
+/// - `rng`: The source of randomness.
+pub fn rrt_connect_multi<C, NN, V, G, SP, TC, R, RNG>(
+    starts: impl IntoIterator<Item = C>,
+    goal: &G,
+    nn: NN,
+    valid: &V,
+    space_sampler: &SP,
+    radius: R,
+    timeout: &mut TC,
+    rng: &mut RNG,
+) -> Option<RrtConnectSolution<C>>
+where
+    NN: NearestNeighborsMap<C, usize> + Clone,
+    V: GeoValidate<C>,
+    G: SampleGoal<C, RNG>,
+    SP: Sample<C, RNG>,
+    R: Clone,
+    C: Clone + Interpolate<Distance = R>,
+    TC: Timeout,
+{
+    let mut trees = RrtConnect::with_starts(nn, starts, valid);
+    trees.grow_toward(space_sampler, goal, radius, timeout, rng)
 }
 
 impl<'a, C, NN, V> RrtConnect<'a, C, NN, V> {
-    pub fn new(mut nn: NN, start: C, goal: C, valid: &'a V) -> Self
+    pub fn new(nn: NN, start: C, goal: C, valid: &'a V) -> Self
     where
         NN: Clone + NearestNeighborsMap<C, usize>,
+        V: Validate<C>,
         C: Clone,
     {
-        let mut nn1 = nn.clone();
-        nn.insert(start.clone(), 0);
-        nn1.insert(goal.clone(), 0);
-        Self {
-            trees: [
-                HalfTree {
-                    configurations: vec![start],
-                    parents: vec![0],
-                    nn,
-                },
-                HalfTree {
-                    configurations: vec![goal],
-                    parents: vec![0],
-                    nn: nn1,
-                },
-            ],
+        let mut rrtc = Self::with_starts(nn, [start], valid);
+        rrtc.add_goal(goal);
+        rrtc
+    }
+
+    /// Construct a new `RrtConnect` whose start tree is rooted at each of `starts`.
+    /// The goal tree is initially empty; its roots may be added with [`RrtConnect::add_goal`] or
+    /// sampled while growing with [`RrtConnect::grow_toward`].
+    pub fn with_starts(nn: NN, starts: impl IntoIterator<Item = C>, valid: &'a V) -> Self
+    where
+        NN: Clone + NearestNeighborsMap<C, usize>,
+        V: Validate<C>,
+        C: Clone,
+    {
+        let empty = HalfTree {
+            configurations: Vec::new(),
+            parents: Vec::new(),
+            roots: Vec::new(),
+            nn,
+        };
+        let mut rrtc = Self {
+            trees: [empty.clone(), empty],
             cross_edges: Vec::new(),
+            goal_samples: 0,
             valid,
             next: 0,
+        };
+        for start in starts {
+            rrtc.add_start(start);
         }
+        rrtc
+    }
+
+    /// Add a new root to the tree grown from the start.
+    /// If `start` is invalid, it is still counted among the starts, but is never grown from.
+    pub fn add_start(&mut self, start: C)
+    where
+        NN: NearestNeighborsMap<C, usize>,
+        V: Validate<C>,
+        C: Clone,
+    {
+        let is_valid = self.valid.is_valid_configuration(&start);
+        self.trees[0].add_root(start, is_valid);
+    }
+
+    /// Add a new root to the tree grown from the goal.
+    /// If `goal` is invalid, it is still counted among the goals, but is never grown from.
+    pub fn add_goal(&mut self, goal: C)
+    where
+        NN: NearestNeighborsMap<C, usize>,
+        V: Validate<C>,
+        C: Clone,
+    {
+        let is_valid = self.valid.is_valid_configuration(&goal);
+        self.trees[1].add_root(goal, is_valid);
+    }
+
+    /// Get the roots of the tree grown from the start, in the order they were added.
+    pub fn starts(&self) -> impl Iterator<Item = &C> {
+        self.trees[0].roots()
+    }
+
+    /// Get the roots of the tree grown from the goal, in the order they were added.
+    pub fn goals(&self) -> impl Iterator<Item = &C> {
+        self.trees[1].roots()
     }
 
-    #[expect(clippy::missing_panics_doc)]
     pub fn grow<SP, TC, R, RNG>(
         &mut self,
         space_sampler: &SP,
@@ -121,23 +238,67 @@ impl<'a, C, NN, V> RrtConnect<'a, C, NN, V> {
         R: Clone,
         C: Clone + Interpolate<Distance = R>,
     {
-        if !self
-            .trees
-            .iter()
-            .all(|t| self.valid.is_valid_configuration(&t.configurations[0]))
-        {
-            // invalid start/goal
-            return None;
-        }
+        self.grow_toward(space_sampler, &|_: &C| false, radius, timeout, rng)
+            .map(|soln| soln.path)
+    }
 
-        let mut res = None;
+    #[expect(clippy::missing_panics_doc)]
+    /// Grow the trees until `timeout` is over, returning the most recently found solution.
+    ///
+    /// A solution is found either when the two trees connect, or when the tree grown from the
+    /// start reaches a configuration which satisfies `goal`.
+    ///
+    /// While growing, new roots for the tree grown from the goal are sampled from `goal`, so long
+    /// as that tree has no roots or fewer samples have been drawn than half the number of nodes in
+    /// that tree.
+    /// Sampled roots which are invalid or within `radius` of the goal tree are discarded.
+    ///
+    /// If a start already satisfies `goal`, it is a solution on its own.
+    /// Returns `None` immediately if no start is valid.
+    pub fn grow_toward<SP, G, TC, R, RNG>(
+        &mut self,
+        space_sampler: &SP,
+        goal: &G,
+        radius: R,
+        timeout: &mut TC,
+        rng: &mut RNG,
+    ) -> Option<RrtConnectSolution<C>>
+    where
+        V: GeoValidate<C>,
+        SP: Sample<C, RNG>,
+        G: SampleGoal<C, RNG>,
+        TC: Timeout,
+        NN: NearestNeighborsMap<C, usize>,
+        R: Clone,
+        C: Clone + Interpolate<Distance = R>,
+    {
+        let mut res = {
+            let mut valid_starts = self.valid_starts().peekable();
+            valid_starts.peek()?;
+            valid_starts
+                .find(|(_, c)| goal.is_satisfied(c))
+                .map(|(i, c)| RrtConnectSolution {
+                    path: vec![c.clone()],
+                    start: i,
+                    goal: None,
+                })
+        };
+        if res.is_some() {
+            timeout.notify_solved();
+        }
 
         'a: while !timeout.is_over() {
+            self.sample_goal_root(goal, radius.clone(), rng);
+
             // begin with RRTC extend procedure
             timeout.update_sample_count(1);
             let q_rand = space_sampler.sample(rng);
             let t = &mut self.trees[self.next as usize];
-            let &q_near_id = t.nn.nearest(&q_rand).expect("NN must be nonempty").value();
+            let Some(q_near_id) = t.nn.nearest(&q_rand).map(|e| *e.value()) else {
+                // this tree has no roots yet
+                self.next ^= 1;
+                continue;
+            };
             let q_near = &t.configurations[q_near_id];
             let q_new = q_near
                 .interpolate(&q_rand, radius.clone())
@@ -154,12 +315,23 @@ impl<'a, C, NN, V> RrtConnect<'a, C, NN, V> {
             t.configurations.push(q_new.clone());
             t.parents.push(q_near_id);
             t.nn.insert(q_new.clone(), q_new_id);
+            if self.next == 0 && goal.is_satisfied(&q_new) {
+                timeout.notify_solved();
+                let (path, start) = t.path_from_root(q_new_id);
+                res = Some(RrtConnectSolution {
+                    path,
+                    start,
+                    goal: None,
+                });
+            }
 
             self.next ^= 1;
 
             // attempt to connect the two trees with this newly created node
             let tb = &mut self.trees[self.next as usize];
-            let &id = tb.nn.nearest(&q_new).expect("NN must be nonempty").value();
+            let Some(id) = tb.nn.nearest(&q_new).map(|e| *e.value()) else {
+                continue;
+            };
             let mut q_old_connect_id = id;
             let mut q_old_connect = tb.configurations[id].clone();
 
@@ -178,6 +350,16 @@ impl<'a, C, NN, V> RrtConnect<'a, C, NN, V> {
                 tb.parents.push(q_old_connect_id);
                 tb.nn.insert(q_new_connect.clone(), q_new_connect_id);
 
+                if self.next == 0 && goal.is_satisfied(&q_new_connect) {
+                    timeout.notify_solved();
+                    let (path, start) = tb.path_from_root(q_new_connect_id);
+                    res = Some(RrtConnectSolution {
+                        path,
+                        start,
+                        goal: None,
+                    });
+                }
+
                 q_old_connect = q_new_connect;
                 q_old_connect_id = q_new_connect_id;
             }
@@ -191,46 +373,131 @@ impl<'a, C, NN, V> RrtConnect<'a, C, NN, V> {
                     (q_old_connect_id, q_new_id)
                 });
 
-                let mut traj = Vec::new();
-                let (mut p0, mut p1) = self.cross_edges.last().unwrap();
-
-                // extract first half of path
-                while p0 != 0 {
-                    traj.push(self.trees[0].configurations[p0].clone());
-                    p0 = self.trees[0].parents[p0];
-                }
-                traj.push(self.trees[0].configurations[0].clone());
-                traj.reverse();
+                let &(p0, p1) = self.cross_edges.last().unwrap();
+                let (mut path, start) = self.trees[0].path_from_root(p0);
+                let (mut second_half, goal) = self.trees[1].path_from_root(p1);
+                second_half.reverse();
+                path.extend(second_half);
 
-                // extract second half of path
-                while p1 != 0 {
-                    traj.push(self.trees[1].configurations[p1].clone());
-                    p1 = self.trees[1].parents[p1];
-                }
-                traj.push(self.trees[1].configurations[0].clone());
-
-                res = Some(traj);
+                res = Some(RrtConnectSolution {
+                    path,
+                    start,
+                    goal: Some(goal),
+                });
             }
         }
 
         res
     }
 
+    /// Get the valid starts, along with their indices in [`RrtConnect::starts`].
+    fn valid_starts(&self) -> impl Iterator<Item = (usize, &C)>
+    where
+        V: Validate<C>,
+    {
+        let t = &self.trees[0];
+        t.roots
+            .iter()
+            .map(|&id| &t.configurations[id])
+            .enumerate()
+            .filter(|(_, c)| self.valid.is_valid_configuration(c))
+    }
+
+    /// Sample a new root for the goal tree from `goal` if it has too few roots.
+    /// The sample is discarded if it is invalid or within `radius` of the goal tree.
+    fn sample_goal_root<G, R, RNG>(&mut self, goal: &G, radius: R, rng: &mut RNG)
+    where
+        V: GeoValidate<C>,
+        G: SampleGoal<C, RNG>,
+        NN: NearestNeighborsMap<C, usize>,
+        C: Clone + Interpolate<Distance = R>,
+    {
+        if !self.trees[1].roots.is_empty()
+            && self.goal_samples > self.trees[1].configurations.len() / 2
+        {
+            return;
+        }
+        self.goal_samples += 1;
+        let Some(q_goal) = goal.sample_goal(rng) else {
+            return;
+        };
+        if !self.valid.is_valid_configuration(&q_goal) {
+            return;
+        }
+        let t = &mut self.trees[1];
+        if let Some(id) = t.nn.nearest(&q_goal).map(|e| *e.value()) {
+            if t.configurations[id]
+                .interpolate(&q_goal, radius)
+                .next()
+                .is_none()
+            {
+                // the goal tree already covers this sample
+                return;
+            }
+        }
+        t.add_root(q_goal, true);
+    }
+
     pub fn num_nodes(&self) -> usize {
         self.trees.iter().map(|t| t.configurations.len()).sum()
     }
 }
 
+impl<C, NN> HalfTree<C, NN> {
+    /// Add `c` as a new root of this tree.
+    /// Only valid roots are added to the nearest-neighbor map, so that invalid roots are never
+    /// grown from.
+    fn add_root(&mut self, c: C, is_valid: bool)
+    where
+        NN: NearestNeighborsMap<C, usize>,
+        C: Clone,
+    {
+        let id = self.configurations.len();
+        if is_valid {
+            self.nn.insert(c.clone(), id);
+        }
+        self.configurations.push(c);
+        self.parents.push(id);
+        self.roots.push(id);
+    }
+
+    /// Get the configurations at the roots of this tree.
+    fn roots(&self) -> impl Iterator<Item = &C> {
+        self.roots.iter().map(|&id| &self.configurations[id])
+    }
+
+    /// Get the configurations along the path from a root of this tree to the node `id`, as well as
+    /// the index of that root.
+    fn path_from_root(&self, mut id: usize) -> (Vec<C>, usize)
+    where
+        C: Clone,
+    {
+        let mut traj = Vec::new();
+        while self.parents[id] != id {
+            traj.push(self.configurations[id].clone());
+            id = self.parents[id];
+        }
+        traj.push(self.configurations[id].clone());
+        traj.reverse();
+        let root = self
+            .roots
+            .binary_search(&id)
+            .expect("path must end at a root");
+        (traj, root)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
+        goal::Goal,
         metric::{Metric, SquaredEuclidean},
         nn::KdTreeMap,
         sample::Rectangle,
         space::Vector,
         time::Solved,
-        valid::AlwaysValid,
+        valid::{AlwaysValid, SampleInterpolate},
     };
     use rand::SeedableRng;
     use rand_chacha::ChaCha20Rng;
@@ -267,4 +534,123 @@ mod tests {
             "all transitions must be within growth radius"
         );
     }
+
+    #[test]
+    fn rrtc_goal_region() {
+        let goal = crate::goal::Tolerance {
+            center: Vector::new([1.0, 1.0]),
+            metric: SquaredEuclidean,
+            tolerance: 0.01,
+        };
+        let radius = 0.05;
+        let res = rrt_connect(
+            Vector::new([0.0, 0.0]),
+            &goal,
+            KdTreeMap::new(SquaredEuclidean),
+            &AlwaysValid,
+            &Rectangle {
+                min: Vector::new([0.0, 0.0]),
+                max: Vector::new([1.1, 1.1]),
+            },
+            radius,
+            &mut Solved::new(),
+            &mut ChaCha20Rng::seed_from_u64(2707),
+        )
+        .unwrap();
+
+        assert_eq!(res[0], Vector::new([0.0, 0.0]));
+        assert!(goal.is_satisfied(res.last().unwrap()));
+    }
+
+    #[test]
+    fn rrtc_multi_goal() {
+        // several "IK solutions", of which only the last is reachable
+        let goals = vec![
+            Vector::new([3.0, 3.0]),
+            Vector::new([-3.0, 3.0]),
+            Vector::new([0.0, 3.0]),
+        ];
+        let valid = SampleInterpolate::new(|c: &Vector<2>| c[0].abs() < 2.0 || c[1] < 2.0, 0.01);
+        let starts = [Vector::new([3.0, 0.0]), Vector::new([-3.0, 0.0])];
+        let radius = 0.05;
+        let soln = rrt_connect_multi(
+            starts,
+            &goals,
+            KdTreeMap::new(SquaredEuclidean),
+            &valid,
+            &Rectangle {
+                min: Vector::new([-4.0; 2]),
+                max: Vector::new([4.0; 2]),
+            },
+            radius,
+            &mut Solved::new(),
+            &mut ChaCha20Rng::seed_from_u64(2707),
+        )
+        .unwrap();
+
+        assert_eq!(soln.path[0], starts[soln.start]);
+        assert_eq!(soln.path.last(), Some(&goals[2]));
+        assert!(soln.goal.is_some());
+        assert!(soln
+            .path
+            .windows(2)
+            .all(|a| valid.is_valid_transition(&a[0], &a[1])));
+    }
+
+    #[test]
+    fn rrtc_skip_invalid_start() {
+        let valid = SampleInterpolate::new(|c: &Vector<2>| c[0] < 2.0, 0.01);
+        let starts = [Vector::new([3.0, 0.0]), Vector::new([0.0, 0.0])];
+        let goal = Vector::new([1.0, 1.0]);
+        let radius = 0.05;
+        let soln = rrt_connect_multi(
+            starts,
+            &goal,
+            KdTreeMap::new(SquaredEuclidean),
+            &valid,
+            &Rectangle {
+                min: Vector::new([-4.0; 2]),
+                max: Vector::new([4.0; 2]),
+            },
+            radius,
+            &mut Solved::new(),
+            &mut ChaCha20Rng::seed_from_u64(2707),
+        )
+        .unwrap();
+
+        assert_eq!(soln.start, 1);
+        assert_eq!(soln.path[0], starts[1]);
+        assert!(soln
+            .path
+            .windows(2)
+            .all(|a| valid.is_valid_transition(&a[0], &a[1])));
+    }
+
+    #[test]
+    fn rrtc_start_satisfies_goal() {
+        let goal = crate::goal::Tolerance {
+            center: Vector::new([1.0, 1.0]),
+            metric: SquaredEuclidean,
+            tolerance: 0.01,
+        };
+        let starts = [Vector::new([0.0, 0.0]), Vector::new([1.0, 1.05])];
+        let soln = rrt_connect_multi(
+            starts,
+            &goal,
+            KdTreeMap::new(SquaredEuclidean),
+            &AlwaysValid,
+            &Rectangle {
+                min: Vector::new([0.0, 0.0]),
+                max: Vector::new([1.1, 1.1]),
+            },
+            0.05,
+            &mut Solved::new(),
+            &mut ChaCha20Rng::seed_from_u64(2707),
+        )
+        .unwrap();
+
+        assert_eq!(soln.start, 1);
+        assert_eq!(soln.path, vec![starts[1]]);
+        assert_eq!(soln.goal, None);
+    }
 }
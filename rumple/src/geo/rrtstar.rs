@@ -0,0 +1,456 @@
+//! Asymptotically-optimal RRT.
+
+use alloc::vec::Vec;
+use num_traits::{Float, Zero};
+
+use crate::{
+    metric::Metric,
+    nn::{NearestEntry, NearestNeighborsMap, RangeNearestNeighborsMap},
+    sample::Sample,
+    space::Interpolate,
+    time::Timeout,
+    valid::{GeoValidate, Validate},
+};
+
+use super::Tree;
+
+#[derive(Clone, Debug)]
+/// An optimizing rapidly-exploring random tree.
+///
+/// Unlike [`Rrt`](super::Rrt), an `RrtStar` selects the lowest-cost parent for each new node from
+/// all of its neighbors within a shrinking radius, and then rewires those neighbors through the new
+/// node if doing so reduces their cost. It will continue improving its solution after it has
+/// first found one, and returns the best path found once its timeout is over.
+///
+/// # Generic parameters
+///
+/// - `C` should be the configuration of a robot.
+/// - `NN` should be the nearest neighbors data structure, which can use `C` as a key and implement
+///   [`RangeNearestNeighborsMap`].
+/// - `V` should be a state validator; it must implement [`GeoValidate`] for `C`.
+/// - `M` is the metric used to compute the cost of each edge.
+///
+/// # Citation
+///
+/// ```bibtex
+/// @article{karaman2011sampling,
+///   title={Sampling-based algorithms for optimal motion planning},
+///   author={Karaman, Sertac and Frazzoli, Emilio},
+///   journal={The International Journal of Robotics Research},
+///   volume={30},
+///   number={7},
+///   pages={846--894},
+///   year={2011},
+///   publisher={SAGE Publications}
+/// }
+/// ```
+pub struct RrtStar<'a, C, NN, V, M>
+where
+    M: Metric<C>,
+{
+    /// buffer of saved configurations
+    /// configurations[0] is the root
+    configurations: Vec<C>,
+    /// ids for each configuration
+    /// `parent_ids[0]` is ignorable
+    parent_ids: Vec<usize>,
+    /// The children of each node.
+    children: Vec<Vec<usize>>,
+    /// The cost-to-come of each node from the root.
+    costs: Vec<M::Distance>,
+    /// The IDs of all nodes which reached the goal.
+    goal_ids: Vec<usize>,
+    /// The nearest neighbors lookup.
+    nn: NN,
+    /// The state validator.
+    valid: &'a V,
+    /// The metric used for edge costs.
+    metric: M,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The radius within which an [`RrtStar`] searches for parents and rewiring candidates.
+///
+/// When the tree contains `n` nodes, the rewiring radius is `min(max, gamma * (ln(n) / n) ^
+/// exponent)`. The radius is measured by the distance of the nearest-neighbor structure, so for a
+/// `d`-dimensional space `exponent` should be `1 / d` when using a Euclidean distance and `2 / d`
+/// when using a squared-Euclidean distance.
+pub struct RewireRadius<T> {
+    /// The scaling constant for the radius.
+    pub gamma: T,
+    /// The exponent on `ln(n) / n`.
+    pub exponent: T,
+    /// The largest radius that will ever be searched.
+    pub max: T,
+}
+
+impl<T: Float> RewireRadius<T> {
+    /// Compute the rewiring radius for a tree containing `n` nodes.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `n` cannot be represented as a `T`.
+    pub fn radius(&self, n: usize) -> T {
+        let n = T::from(n).expect("number of nodes must be representable");
+        (self.gamma * (n.ln() / n).powf(self.exponent)).min(self.max)
+    }
+}
+
+#[expect(clippy::too_many_arguments)]
+/// Plan between two configurations using an [`RrtStar`].
+///
+/// # Parameters
+///
+/// - `start`: The start configuration.
+/// - `nn`: The nearest-neighbor structure.
+/// - `valid`: The state validator.
+/// - `metric`: The metric for the cost of each edge.
+/// - `space_sampler`: A sampler for states in the configuration space.
+/// - `goal`: The goal state or sampler for goal states.
+/// - `radius`: The radius by which to expand the tree.
+/// - `rewire`: The radius within which parents are chosen and neighbors are rewired.
+/// - `timeout`: The timeout condition. The planning algorithm will continue until `timeout` is
+///   over.
+/// - `target_goal_distn`: A sampler which returns `true` with some probability; every time it
+///   returns `true`, the tree grows toward the goal instead of to fill the space.
+/// - `rng`: The source of randomness.
+pub fn rrt_star<C, NN, V, M, SP, G, TC, TG, R, RNG>(
+    start: C,
+    nn: NN,
+    valid: &V,
+    metric: M,
+    space_sampler: &SP,
+    goal: &G,
+    radius: R,
+    rewire: &RewireRadius<NN::Distance>,
+    timeout: &mut TC,
+    target_goal_distn: &TG,
+    rng: &mut RNG,
+) -> Option<Vec<C>>
+where
+    NN: RangeNearestNeighborsMap<C, usize>,
+    NN::Distance: Float,
+    V: GeoValidate<C>,
+    M: Metric<C>,
+    M::Distance: Clone,
+    SP: Sample<C, RNG>,
+    G: Sample<C, RNG>,
+    R: Clone,
+    C: Clone + Interpolate<Distance = R>,
+    TC: Timeout,
+    TG: Sample<bool, RNG>,
+{
+    let mut rrt = RrtStar::new(start, nn, valid, metric);
+    rrt.grow_toward(
+        space_sampler,
+        goal,
+        radius,
+        rewire,
+        timeout,
+        target_goal_distn,
+        rng,
+    )
+}
+
+impl<'a, C, NN, V, M> RrtStar<'a, C, NN, V, M>
+where
+    M: Metric<C>,
+{
+    /// Construct a new RRT* rooted at `root`, using `nn` as its nearest-neighbor structure, `valid`
+    /// as its state validator, and `metric` as its cost function.
+    pub fn new(root: C, mut nn: NN, valid: &'a V, metric: M) -> Self
+    where
+        NN: NearestNeighborsMap<C, usize>,
+        C: Clone,
+        V: Validate<C>,
+    {
+        nn.insert(root.clone(), 0);
+        Self {
+            configurations: vec![root],
+            parent_ids: vec![usize::MAX],
+            children: vec![Vec::new()],
+            costs: vec![M::Distance::zero()],
+            goal_ids: Vec::new(),
+            nn,
+            valid,
+            metric,
+        }
+    }
+
+    #[expect(clippy::too_many_arguments, clippy::missing_panics_doc)]
+    /// Grow this tree toward the provided goal `goal`, returning the lowest-cost path to the goal
+    /// once `timeout` is over.
+    ///
+    /// # Parameters
+    ///
+    /// - `space_sampler`: A sampler for states in the configuration space.
+    /// - `goal`: The goal state or sampler for goal states.
+    /// - `radius`: The radius by which to expand the tree.
+    /// - `rewire`: The radius within which parents are chosen and neighbors are rewired.
+    /// - `timeout`: The timeout condition. The planning algorithm will continue until `timeout` is
+    ///   over.
+    /// - `target_goal_distn`: A sampler which returns `true` with some probability; every time it
+    ///   returns `true`, the tree grows toward the goal instead of to fill the space.
+    /// - `rng`: The source of randomness.
+    pub fn grow_toward<SP, G, TC, TG, R, RNG>(
+        &mut self,
+        space_sampler: &SP,
+        goal: &G,
+        radius: R,
+        rewire: &RewireRadius<NN::Distance>,
+        timeout: &mut TC,
+        target_goal_distn: &TG,
+        rng: &mut RNG,
+    ) -> Option<Vec<C>>
+    where
+        V: GeoValidate<C>,
+        SP: Sample<C, RNG>,
+        G: Sample<C, RNG>,
+        TG: Sample<bool, RNG>,
+        TC: Timeout,
+        NN: RangeNearestNeighborsMap<C, usize>,
+        NN::Distance: Float,
+        M::Distance: Clone,
+        R: Clone,
+        C: Clone + Interpolate<Distance = R>,
+    {
+        if !self.valid.is_valid_configuration(&self.configurations[0]) {
+            return None; // invalid configuration
+        }
+        let mut near = Vec::new();
+        while !timeout.is_over() {
+            timeout.update_sample_count(1);
+            let sample_goal = target_goal_distn.sample(rng);
+            let target = if sample_goal {
+                goal.sample(rng)
+            } else {
+                space_sampler.sample(rng)
+            };
+            let &nearest_id = self
+                .nn
+                .nearest(&target)
+                .expect("NN must always have elements")
+                .value();
+            let nearest_cfg = &self.configurations[nearest_id];
+            let (reached, new_cfg) = nearest_cfg
+                .interpolate(&target, radius.clone())
+                .next()
+                .map_or_else(|| (true, target.clone()), |c| (false, c));
+            if !self.valid.is_valid_configuration(&new_cfg)
+                || !self.valid.is_valid_transition(nearest_cfg, &new_cfg)
+            {
+                continue;
+            }
+            timeout.update_node_count(1);
+            let new_id = self.configurations.len();
+
+            near.clear();
+            near.extend(
+                self.nn
+                    .nearest_within_r(&new_cfg, rewire.radius(new_id + 1))
+                    .map(|e| *e.value()),
+            );
+
+            // choose the lowest-cost parent among all neighbors
+            let mut parent_id = nearest_id;
+            let mut cost =
+                self.costs[nearest_id].clone() + self.metric.distance(nearest_cfg, &new_cfg);
+            for &id in &near {
+                if id == nearest_id {
+                    continue;
+                }
+                let cfg = &self.configurations[id];
+                let new_cost = self.costs[id].clone() + self.metric.distance(cfg, &new_cfg);
+                if new_cost < cost && self.valid.is_valid_transition(cfg, &new_cfg) {
+                    parent_id = id;
+                    cost = new_cost;
+                }
+            }
+
+            self.configurations.push(new_cfg.clone());
+            self.parent_ids.push(parent_id);
+            self.children.push(Vec::new());
+            self.children[parent_id].push(new_id);
+            self.costs.push(cost);
+            debug_assert_eq!(
+                self.configurations.len(),
+                self.parent_ids.len(),
+                "number of configurations and parents must be equal"
+            );
+
+            // rewire neighbors through the new node if it improves their cost
+            for &id in &near {
+                if id == parent_id {
+                    continue;
+                }
+                let cfg = &self.configurations[id];
+                let new_cost = self.costs[new_id].clone() + self.metric.distance(&new_cfg, cfg);
+                if new_cost < self.costs[id] && self.valid.is_valid_transition(&new_cfg, cfg) {
+                    self.reparent(id, new_id, new_cost);
+                }
+            }
+
+            self.nn.insert(new_cfg, new_id);
+            if sample_goal && reached {
+                timeout.notify_solved();
+                self.goal_ids.push(new_id);
+            }
+        }
+
+        self.best_path()
+    }
+
+    /// Make `parent_id` the parent of `id`, which will now have cost `cost`, and update the costs
+    /// of all of its descendants.
+    fn reparent(&mut self, id: usize, parent_id: usize, cost: M::Distance)
+    where
+        M::Distance: Clone,
+    {
+        let old_parent = self.parent_ids[id];
+        let siblings = &mut self.children[old_parent];
+        let idx = siblings
+            .iter()
+            .position(|&c| c == id)
+            .expect("node must be a child of its parent");
+        siblings.swap_remove(idx);
+        self.children[parent_id].push(id);
+        self.parent_ids[id] = parent_id;
+        self.costs[id] = cost;
+
+        let mut stack = vec![id];
+        while let Some(p) = stack.pop() {
+            for &c in &self.children[p] {
+                self.costs[c] = self.costs[p].clone()
+                    + self
+                        .metric
+                        .distance(&self.configurations[p], &self.configurations[c]);
+                stack.push(c);
+            }
+        }
+    }
+
+    /// Get the lowest-cost path to the goal found so far, or `None` if the goal has not yet been
+    /// reached.
+    pub fn best_path(&self) -> Option<Vec<C>>
+    where
+        C: Clone,
+    {
+        let mut id = self.best_goal()?;
+        let mut traj = Vec::new();
+        while id != 0 {
+            traj.push(self.configurations[id].clone());
+            id = self.parent_ids[id];
+        }
+        traj.push(self.configurations[0].clone());
+        traj.reverse();
+        Some(traj)
+    }
+
+    /// Get the cost of the lowest-cost path to the goal found so far, or `None` if the goal has
+    /// not yet been reached.
+    pub fn best_cost(&self) -> Option<&M::Distance> {
+        self.best_goal().map(|id| &self.costs[id])
+    }
+
+    fn best_goal(&self) -> Option<usize> {
+        self.goal_ids.iter().copied().reduce(|best, id| {
+            if self.costs[id] < self.costs[best] {
+                id
+            } else {
+                best
+            }
+        })
+    }
+
+    #[must_use]
+    /// Get the number of total nodes in this tree.
+    pub const fn num_nodes(&self) -> usize {
+        self.configurations.len()
+    }
+}
+
+impl<C, NN, V, M> Tree for RrtStar<'_, C, NN, V, M>
+where
+    M: Metric<C>,
+{
+    type Node = usize;
+    type Configuration = C;
+
+    fn configuration(&self, node: Self::Node) -> &Self::Configuration {
+        &self.configurations[node]
+    }
+
+    fn children(&self, node: Self::Node) -> impl IntoIterator<Item = Self::Node> {
+        self.children[node].iter().copied()
+    }
+
+    fn parent(&self, node: Self::Node) -> Option<Self::Node> {
+        (node != 0).then(|| self.parent_ids[node])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        metric::{Euclidean, SquaredEuclidean},
+        nn::KdTreeMap,
+        sample::Rectangle,
+        space::Vector,
+        time::LimitNodes,
+        valid::AlwaysValid,
+    };
+    use rand::{distributions::Bernoulli, SeedableRng};
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn rrt_star_plane2d() {
+        let mut rrt = RrtStar::new(
+            Vector::new([0.0, 0.0]),
+            KdTreeMap::new(SquaredEuclidean),
+            &AlwaysValid,
+            Euclidean,
+        );
+        let radius = 0.05;
+        let res = rrt
+            .grow_toward(
+                &Rectangle {
+                    min: Vector::new([0.0; 2]),
+                    max: Vector::new([1.0; 2]),
+                },
+                &Vector::new([1.0, 1.0]),
+                radius,
+                &RewireRadius {
+                    gamma: 1.0,
+                    exponent: 1.0,
+                    max: 0.04,
+                },
+                &mut LimitNodes::new(3_000),
+                &Bernoulli::new(0.05).unwrap(),
+                &mut ChaCha20Rng::seed_from_u64(2707),
+            )
+            .unwrap();
+
+        #[cfg(feature = "std")]
+        {
+            println!("Created {} nodes", rrt.num_nodes());
+            println!("{res:?}");
+        }
+        assert_eq!(res.first(), Some(&Vector::new([0.0, 0.0])));
+        assert_eq!(res.last(), Some(&Vector::new([1.0, 1.0])));
+        assert!(
+            res.windows(2)
+                .all(|a| Euclidean.distance(&a[0], &a[1]) <= 0.2 + 1e-5),
+            "all transitions must be within rewiring radius"
+        );
+        let cost: f64 = res
+            .windows(2)
+            .map(|a| Euclidean.distance(&a[0], &a[1]))
+            .sum();
+        assert!((cost - rrt.best_cost().unwrap()).abs() < 1e-5);
+        assert!(
+            cost < 1.1 * core::f64::consts::SQRT_2,
+            "path cost {cost} is too high"
+        );
+    }
+}
@@ -0,0 +1,290 @@
+//! Path simplification.
+//!
+//! Paths produced by sampling-based planners are usually valid but full of needless detours.
+//! The functions in this module remove those detours while keeping every transition valid.
+//! [`reduce_vertices`] makes a single pass over the path, stopping early if `timeout` is over.
+//! [`shortcut`] and [`partial_shortcut`] instead keep making random attempts until `timeout` is
+//! over, so they must be given a timeout which eventually expires, such as
+//! [`LimitSamples`](crate::time::LimitSamples).
+//! Every attempted shortcut counts as a sample.
+
+use alloc::vec::Vec;
+
+use num_traits::{Float, Zero};
+use rand::Rng;
+
+use crate::{metric::Metric, space::Vector, time::Timeout, valid::GeoValidate};
+
+/// Compute the total length of `path` as measured by `metric`.
+fn path_cost<C, M: Metric<C>>(path: &[C], metric: &M) -> M::Distance {
+    path.windows(2).fold(M::Distance::zero(), |total, w| {
+        total + metric.distance(&w[0], &w[1])
+    })
+}
+
+/// Choose two random indices `i` and `j` into a path of length `n` which have at least one
+/// vertex between them.
+fn random_span<RNG: Rng>(n: usize, rng: &mut RNG) -> (usize, usize) {
+    let i = rng.gen_range(0..n - 2);
+    let j = rng.gen_range(i + 2..n);
+    (i, j)
+}
+
+/// Simplify a path by repeatedly connecting two randomly chosen vertices with a single transition.
+///
+/// A shortcut is taken only if it is valid and shorter, according to `metric`, than the part of
+/// the path it replaces.
+/// This stops early if the path is reduced to a single transition.
+///
+/// # Citation
+///
+/// ```bibtex
+/// @article{geraerts2007creating,
+///   title={Creating high-quality paths for motion planning},
+///   author={Geraerts, Roland and Overmars, Mark H},
+///   journal={The International Journal of Robotics Research},
+///   volume={26},
+///   number={8},
+///   pages={845--863},
+///   year={2007}
+/// }
+/// ```
+pub fn shortcut<C, V, M, TC, RNG>(
+    mut path: Vec<C>,
+    valid: &V,
+    metric: &M,
+    timeout: &mut TC,
+    rng: &mut RNG,
+) -> Vec<C>
+where
+    V: GeoValidate<C>,
+    M: Metric<C>,
+    TC: Timeout,
+    RNG: Rng,
+{
+    while path.len() > 2 && !timeout.is_over() {
+        timeout.update_sample_count(1);
+        let (i, j) = random_span(path.len(), rng);
+        if metric.distance(&path[i], &path[j]) < path_cost(&path[i..=j], metric)
+            && valid.is_valid_transition(&path[i], &path[j])
+        {
+            path.drain(i + 1..j);
+        }
+    }
+    path
+}
+
+/// Simplify a path by greedily removing vertices.
+///
+/// Beginning at the start of the path, each vertex is connected directly to the farthest later
+/// vertex which it can reach with a valid transition that is shorter, according to `metric`, than
+/// the part of the path it replaces.
+/// Unlike [`shortcut`], this is deterministic and visits each vertex at most once.
+pub fn reduce_vertices<C, V, M, TC>(
+    mut path: Vec<C>,
+    valid: &V,
+    metric: &M,
+    timeout: &mut TC,
+) -> Vec<C>
+where
+    V: GeoValidate<C>,
+    M: Metric<C>,
+    M::Distance: Clone,
+    TC: Timeout,
+{
+    let mut i = 0;
+    while i + 2 < path.len() {
+        // distance along the path from vertex `i` to each later vertex
+        let mut costs = Vec::with_capacity(path.len() - i);
+        costs.push(M::Distance::zero());
+        for w in path[i..].windows(2) {
+            costs.push(costs[costs.len() - 1].clone() + metric.distance(&w[0], &w[1]));
+        }
+
+        for j in (i + 2..path.len()).rev() {
+            if timeout.is_over() {
+                return path;
+            }
+            timeout.update_sample_count(1);
+            if metric.distance(&path[i], &path[j]) < costs[j - i]
+                && valid.is_valid_transition(&path[i], &path[j])
+            {
+                path.drain(i + 1..j);
+                break;
+            }
+        }
+        i += 1;
+    }
+    path
+}
+
+/// Simplify a path by repeatedly straightening it along a single axis.
+///
+/// Each attempt picks two random vertices and a random axis, then replaces the coordinates along
+/// that axis of every vertex between them by linearly interpolating (by distance along the path)
+/// between the two chosen vertices.
+/// The other coordinates are unchanged.
+/// An attempt is kept only if every modified vertex and transition is valid and the new path is
+/// shorter according to `metric`.
+///
+/// This removes detours along individual axes, which [`shortcut`] cannot do when a direct
+/// transition between vertices is invalid.
+///
+/// # Citation
+///
+/// ```bibtex
+/// @article{geraerts2007creating,
+///   title={Creating high-quality paths for motion planning},
+///   author={Geraerts, Roland and Overmars, Mark H},
+///   journal={The International Journal of Robotics Research},
+///   volume={26},
+///   number={8},
+///   pages={845--863},
+///   year={2007}
+/// }
+/// ```
+pub fn partial_shortcut<const N: usize, T, V, M, TC, RNG>(
+    mut path: Vec<Vector<N, T>>,
+    valid: &V,
+    metric: &M,
+    timeout: &mut TC,
+    rng: &mut RNG,
+) -> Vec<Vector<N, T>>
+where
+    T: Float,
+    V: GeoValidate<Vector<N, T>>,
+    M: Metric<Vector<N, T>, Distance = T>,
+    TC: Timeout,
+    RNG: Rng,
+{
+    while path.len() > 2 && !timeout.is_over() {
+        timeout.update_sample_count(1);
+        let (i, j) = random_span(path.len(), rng);
+        let axis = rng.gen_range(0..N);
+
+        // distance along the path from vertex `i` to each vertex through `j`
+        let mut dists = Vec::with_capacity(j - i + 1);
+        dists.push(T::zero());
+        for w in path[i..=j].windows(2) {
+            dists.push(dists[dists.len() - 1] + metric.distance(&w[0], &w[1]));
+        }
+        let old_cost = dists[dists.len() - 1];
+        if old_cost.is_zero() {
+            continue;
+        }
+
+        let (lo, hi) = (path[i][axis], path[j][axis]);
+        let mut straightened = path[i..=j].to_vec();
+        let last = straightened.len() - 1;
+        for (q, &d) in straightened[1..last].iter_mut().zip(&dists[1..]) {
+            q[axis] = lo + (hi - lo) * d / old_cost;
+        }
+
+        if path_cost(&straightened, metric) < old_cost
+            && straightened[1..last]
+                .iter()
+                .all(|q| valid.is_valid_configuration(q))
+            && straightened
+                .windows(2)
+                .all(|w| valid.is_valid_transition(&w[0], &w[1]))
+        {
+            path.splice(i..=j, straightened);
+        }
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    use super::*;
+    use crate::{
+        metric::Euclidean,
+        time::LimitSamples,
+        valid::{AlwaysValid, SampleInterpolate, Validate},
+    };
+
+    /// A zig-zagging path from (0, 0) to (4, 0) which detours around a wall at `x = 2`.
+    fn detour() -> Vec<Vector<2>> {
+        vec![
+            Vector::new([0.0, 0.0]),
+            Vector::new([0.5, 1.0]),
+            Vector::new([1.0, -1.0]),
+            Vector::new([1.5, 2.5]),
+            Vector::new([2.0, 2.5]),
+            Vector::new([2.5, 2.5]),
+            Vector::new([3.0, -1.0]),
+            Vector::new([3.5, 1.0]),
+            Vector::new([4.0, 0.0]),
+        ]
+    }
+
+    fn wall() -> SampleInterpolate<impl Fn(&Vector<2>) -> bool, f64> {
+        SampleInterpolate::new(|q: &Vector<2>| (q[0] - 2.0).abs() > 0.1 || q[1] > 2.0, 0.01)
+    }
+
+    #[test]
+    fn shortcut_and_reduce() {
+        let valid = wall();
+        let old_cost = path_cost(&detour(), &Euclidean);
+        let mut rng = ChaCha20Rng::seed_from_u64(2707);
+
+        let free = shortcut(
+            detour(),
+            &AlwaysValid,
+            &Euclidean,
+            &mut LimitSamples::new(1000),
+            &mut rng,
+        );
+        assert_eq!(free, [Vector::new([0.0, 0.0]), Vector::new([4.0, 0.0])]);
+
+        let paths = [
+            shortcut(
+                detour(),
+                &valid,
+                &Euclidean,
+                &mut LimitSamples::new(1000),
+                &mut rng,
+            ),
+            reduce_vertices(detour(), &valid, &Euclidean, &mut LimitSamples::new(1000)),
+        ];
+        for path in paths {
+            assert_eq!(path.first(), Some(&Vector::new([0.0, 0.0])));
+            assert_eq!(path.last(), Some(&Vector::new([4.0, 0.0])));
+            assert!(path.len() < detour().len());
+            assert!(path_cost(&path, &Euclidean) < old_cost);
+            assert!(path
+                .windows(2)
+                .all(|w| valid.is_valid_transition(&w[0], &w[1])));
+        }
+
+        // the greedy reduction should go straight to and from the top of the wall and back down
+        assert_eq!(
+            reduce_vertices(detour(), &valid, &Euclidean, &mut LimitSamples::new(1000)).len(),
+            3
+        );
+    }
+
+    #[test]
+    fn partial_straightens() {
+        let valid = wall();
+        let old_cost = path_cost(&detour(), &Euclidean);
+        let path = partial_shortcut(
+            detour(),
+            &valid,
+            &Euclidean,
+            &mut LimitSamples::new(1000),
+            &mut ChaCha20Rng::seed_from_u64(2707),
+        );
+        assert_eq!(path.len(), detour().len());
+        assert_eq!(path.first(), Some(&Vector::new([0.0, 0.0])));
+        assert_eq!(path.last(), Some(&Vector::new([4.0, 0.0])));
+        assert!(path_cost(&path, &Euclidean) < old_cost);
+        assert!(path.iter().all(|q| valid.is_valid_configuration(q)));
+        assert!(path
+            .windows(2)
+            .all(|w| valid.is_valid_transition(&w[0], &w[1])));
+    }
+}
@@ -0,0 +1,239 @@
+//! Goal conditions for planners.
+
+use alloc::vec::Vec;
+
+use num_traits::Float;
+use rand::{distributions::uniform::SampleUniform, Rng};
+
+use crate::{
+    metric::Metric,
+    sample::{Rectangle, Sample},
+    space::{Angle, DubinsPose, Orient, Pose2d, Pose3d, Product, ReedsSheppPose, Vector},
+};
+
+/// A set of configurations which a planner must reach.
+///
+/// Configurations such as [`Vector`] and [`Pose2d`] are goals which are only satisfied by an
+/// exactly equal configuration.
+/// To accept any configuration near a goal configuration, use [`Tolerance`].
+pub trait Goal<C> {
+    /// Return `true` if `c` satisfies this goal and `false` otherwise.
+    fn is_satisfied(&self, c: &C) -> bool;
+}
+
+/// A goal which may be able to produce configurations that satisfy it.
+///
+/// Planners use goal samples to bias their growth toward the goal, or as roots of trees grown
+/// from the goal.
+pub trait SampleGoal<C, RNG>: Goal<C> {
+    /// Sample a configuration which satisfies this goal, using `rng` as a source of randomness.
+    /// Returns `None` if this goal cannot be sampled.
+    fn sample_goal(&self, rng: &mut RNG) -> Option<C>;
+}
+
+#[derive(Clone, Copy, Debug)]
+/// A goal which is satisfied by any configuration within some distance of a center
+/// configuration.
+pub struct Tolerance<C, M: Metric<C>> {
+    /// The configuration at the center of the goal.
+    pub center: C,
+    /// The metric used to measure distance from `center`.
+    pub metric: M,
+    /// The largest distance from `center` which satisfies the goal.
+    pub tolerance: M::Distance,
+}
+
+impl<F, C> Goal<C> for F
+where
+    F: Fn(&C) -> bool,
+{
+    fn is_satisfied(&self, c: &C) -> bool {
+        self(c)
+    }
+}
+
+/// Closures cannot be sampled.
+impl<F, C, RNG> SampleGoal<C, RNG> for F
+where
+    F: Fn(&C) -> bool,
+{
+    fn sample_goal(&self, _: &mut RNG) -> Option<C> {
+        None
+    }
+}
+
+impl<C, M: Metric<C>> Goal<C> for Tolerance<C, M> {
+    fn is_satisfied(&self, c: &C) -> bool {
+        self.metric.distance(&self.center, c) <= self.tolerance
+    }
+}
+
+/// The only sample of a `Tolerance` is its center.
+impl<C: Clone, M: Metric<C>, RNG> SampleGoal<C, RNG> for Tolerance<C, M> {
+    fn sample_goal(&self, _: &mut RNG) -> Option<C> {
+        Some(self.center.clone())
+    }
+}
+
+/// A list of goals is satisfied when any of its goals are satisfied.
+impl<C, G: Goal<C>> Goal<C> for Vec<G> {
+    fn is_satisfied(&self, c: &C) -> bool {
+        self.iter().any(|g| g.is_satisfied(c))
+    }
+}
+
+/// A list of goals is sampled by sampling one of its goals, chosen uniformly at random.
+impl<C, G: SampleGoal<C, RNG>, RNG: Rng> SampleGoal<C, RNG> for Vec<G> {
+    fn sample_goal(&self, rng: &mut RNG) -> Option<C> {
+        if self.is_empty() {
+            return None;
+        }
+        let i = rng.gen_range(0..self.len());
+        self[i].sample_goal(rng)
+    }
+}
+
+impl<const N: usize, T: PartialOrd> Goal<Vector<N, T>> for Rectangle<Vector<N, T>> {
+    fn is_satisfied(&self, c: &Vector<N, T>) -> bool {
+        (0..N).all(|i| self.min[i] <= c[i] && c[i] <= self.max[i])
+    }
+}
+
+impl<const N: usize, T, RNG> SampleGoal<Vector<N, T>, RNG> for Rectangle<Vector<N, T>>
+where
+    T: Float + SampleUniform,
+    RNG: Rng,
+{
+    fn sample_goal(&self, rng: &mut RNG) -> Option<Vector<N, T>> {
+        Some(self.sample(rng))
+    }
+}
+
+/// Implement `Goal` and `SampleGoal` for a configuration type `$t` with generic parameters `$g`,
+/// such that it is only satisfied by configurations equal to itself.
+macro_rules! exact_impl {
+    ($t: ty; $($g: tt)*) => {
+        impl<$($g)*> Goal<$t> for $t
+        where
+            $t: PartialEq,
+        {
+            fn is_satisfied(&self, c: &$t) -> bool {
+                self == c
+            }
+        }
+
+        impl<$($g)*, RNG> SampleGoal<$t, RNG> for $t
+        where
+            $t: PartialEq + Clone,
+        {
+            fn sample_goal(&self, _: &mut RNG) -> Option<$t> {
+                Some(self.clone())
+            }
+        }
+    };
+}
+
+exact_impl!(Vector<N, T>; const N: usize, T);
+exact_impl!(Angle<T>; T);
+exact_impl!(Orient<T>; T);
+exact_impl!(Pose2d<T>; T);
+exact_impl!(Pose3d<T>; T);
+exact_impl!(DubinsPose<T>; T);
+exact_impl!(ReedsSheppPose<T>; T);
+
+/// Implement `Goal` and `SampleGoal` for tuples of goals, where each goal `$g` constrains the
+/// component `$c` at tuple index `$idx`.
+macro_rules! product_impl {
+    ($(($c: ident, $g: ident, $idx: tt)),+) => {
+        impl<$($c, $g),+> Goal<($($c,)+)> for ($($g,)+)
+        where
+            $($g: Goal<$c>,)+
+        {
+            fn is_satisfied(&self, c: &($($c,)+)) -> bool {
+                $(self.$idx.is_satisfied(&c.$idx))&&+
+            }
+        }
+
+        impl<RNG, $($c, $g),+> SampleGoal<($($c,)+), RNG> for ($($g,)+)
+        where
+            $($g: SampleGoal<$c, RNG>,)+
+        {
+            fn sample_goal(&self, rng: &mut RNG) -> Option<($($c,)+)> {
+                Some(($(self.$idx.sample_goal(rng)?,)+))
+            }
+        }
+    };
+}
+
+product_impl!((A, GA, 0), (B, GB, 1));
+product_impl!((A, GA, 0), (B, GB, 1), (C, GC, 2));
+product_impl!((A, GA, 0), (B, GB, 1), (C, GC, 2), (D, GD, 3));
+
+impl<C, G: Goal<C>> Goal<Product<C>> for Product<G> {
+    fn is_satisfied(&self, c: &Product<C>) -> bool {
+        self.0.is_satisfied(&c.0)
+    }
+}
+
+impl<C, G: SampleGoal<C, RNG>, RNG> SampleGoal<Product<C>, RNG> for Product<G> {
+    fn sample_goal(&self, rng: &mut RNG) -> Option<Product<C>> {
+        self.0.sample_goal(rng).map(Product)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    use super::*;
+    use crate::metric::Euclidean;
+
+    #[test]
+    fn goal_sets() {
+        let mut rng = ChaCha20Rng::seed_from_u64(2707);
+        let near_origin = Tolerance {
+            center: Vector::new([0.0, 0.0]),
+            metric: Euclidean,
+            tolerance: 0.5,
+        };
+        let box_goal = Rectangle {
+            min: Vector::new([2.0, 2.0]),
+            max: Vector::new([3.0, 3.0]),
+        };
+        assert!(near_origin.is_satisfied(&Vector::new([0.3, 0.3])));
+        assert!(!near_origin.is_satisfied(&Vector::new([0.4, 0.4])));
+        assert!(box_goal.is_satisfied(&Vector::new([2.5, 3.0])));
+
+        let far = Tolerance {
+            center: Vector::new([5.0, 0.0]),
+            ..near_origin
+        };
+        let goals = vec![near_origin, far];
+        let (mut n_near, mut n_far) = (0, 0);
+        for _ in 0..20 {
+            let c = goals.sample_goal(&mut rng).unwrap();
+            assert!(goals.is_satisfied(&c));
+            if near_origin.is_satisfied(&c) {
+                n_near += 1;
+            } else {
+                n_far += 1;
+            }
+        }
+        assert!(n_near > 0 && n_far > 0);
+        assert!(!goals.is_satisfied(&Vector::new([1.0, 1.0])));
+
+        // a product of goals constrains each component separately
+        let pair = (box_goal, Angle::new(1.0));
+        assert!(pair.is_satisfied(&(Vector::new([2.0, 2.0]), Angle::new(1.0))));
+        assert!(!pair.is_satisfied(&(Vector::new([2.0, 2.0]), Angle::new(1.5))));
+        assert!(SampleGoal::<(Vector<2>, Angle), _>::sample_goal(&pair, &mut rng).is_some());
+
+        let closure = |c: &Vector<2>| c[0] > 1.0;
+        assert!(closure.is_satisfied(&Vector::new([1.5, 0.0])));
+        assert_eq!(
+            SampleGoal::<Vector<2>, _>::sample_goal(&closure, &mut rng),
+            None
+        );
+    }
+}
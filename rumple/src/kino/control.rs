@@ -0,0 +1,221 @@
+//! Sampling distributions for controls and durations.
+
+use alloc::vec::Vec;
+
+use num_traits::{Float, NumCast};
+use rand::{distributions::uniform::SampleUniform, Rng};
+
+use crate::{metric::Metric, sample::Sample};
+
+use super::Propagate;
+
+/// A sampler for controls and the durations over which they are held.
+///
+/// `P` is the state propagator, `C` is the configuration type, `U` is the control type, `D` is the
+/// duration type, and `RNG` is a source of randomness.
+pub trait ControlSample<P, C, U, D, RNG> {
+    /// Sample a control and duration to apply from `start`, using `rng` as a source of randomness.
+    /// `target` is the state toward which the planner is attempting to grow; samplers may use it
+    /// to select a control, or ignore it entirely.
+    fn sample_control(&self, propagator: &P, start: &C, target: &C, rng: &mut RNG) -> (U, D);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// A control sampler which independently samples a control from `control` and a duration from
+/// `duration`, ignoring the start and target states.
+///
+/// For instance, a `control` of [`Rectangle`](crate::sample::Rectangle) samples controls
+/// uniformly from a box.
+pub struct Independent<SU, SD> {
+    /// The sampler for controls.
+    pub control: SU,
+    /// The sampler for durations.
+    pub duration: SD,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// A discrete set of controls, of which one is selected uniformly at random when sampled.
+///
+/// Sampling from an empty set will panic.
+pub struct ControlSet<U>(pub Vec<U>);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// A sampler for durations uniformly distributed between `min` and `max`, inclusive.
+pub struct DurationRange<T> {
+    /// The shortest duration.
+    pub min: T,
+    /// The longest duration.
+    pub max: T,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// A sampler for durations which are a whole number of fixed-size steps.
+/// The number of steps is selected uniformly between `min_steps` and `max_steps`, inclusive.
+pub struct DurationSteps<T> {
+    /// The length of each step.
+    pub step: T,
+    /// The fewest number of steps.
+    pub min_steps: usize,
+    /// The greatest number of steps.
+    pub max_steps: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// A control sampler which draws `k` controls from `sampler`, propagates each of them, and keeps
+/// the one whose resulting state is closest to the target under `metric`.
+pub struct BestOfK<S, M> {
+    /// The underlying control sampler.
+    pub sampler: S,
+    /// The metric for comparing propagated states to the target.
+    pub metric: M,
+    /// The number of controls to draw.
+    pub k: usize,
+}
+
+impl<P, C, U, D, RNG, SU, SD> ControlSample<P, C, U, D, RNG> for Independent<SU, SD>
+where
+    SU: Sample<U, RNG>,
+    SD: Sample<D, RNG>,
+{
+    fn sample_control(&self, _: &P, _: &C, _: &C, rng: &mut RNG) -> (U, D) {
+        (self.control.sample(rng), self.duration.sample(rng))
+    }
+}
+
+impl<U: Clone, RNG: Rng> Sample<U, RNG> for ControlSet<U> {
+    fn sample(&self, rng: &mut RNG) -> U {
+        self.0[rng.gen_range(0..self.0.len())].clone()
+    }
+}
+
+impl<T, RNG> Sample<T, RNG> for DurationRange<T>
+where
+    T: SampleUniform + PartialOrd + Clone,
+    RNG: Rng,
+{
+    fn sample(&self, rng: &mut RNG) -> T {
+        rng.gen_range(self.min.clone()..=self.max.clone())
+    }
+}
+
+impl<T: Float, RNG: Rng> Sample<T, RNG> for DurationSteps<T> {
+    fn sample(&self, rng: &mut RNG) -> T {
+        let n = rng.gen_range(self.min_steps..=self.max_steps);
+        self.step * <T as NumCast>::from(n).expect("number of steps must be representable")
+    }
+}
+
+impl<P, C, U, D, RNG, S, M> ControlSample<P, C, U, D, RNG> for BestOfK<S, M>
+where
+    P: Propagate<C, U, D>,
+    S: ControlSample<P, C, U, D, RNG>,
+    M: Metric<C>,
+    D: Clone,
+{
+    /// # Panics
+    ///
+    /// This function will panic if `self.k` is zero.
+    fn sample_control(&self, propagator: &P, start: &C, target: &C, rng: &mut RNG) -> (U, D) {
+        let mut best = None;
+        for _ in 0..self.k {
+            let (u, d) = self.sampler.sample_control(propagator, start, target, rng);
+            let dist = self
+                .metric
+                .distance(&propagator.propagate(start, &u, d.clone()), target);
+            if best.as_ref().is_none_or(|(best_dist, _)| dist < *best_dist) {
+                best = Some((dist, (u, d)));
+            }
+        }
+        best.expect("best-of-k sampler must draw at least one control")
+            .1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    use super::*;
+    use crate::{
+        kino::tests::Integrator, metric::SquaredEuclidean, sample::Rectangle, space::Vector,
+    };
+
+    #[test]
+    fn best_of_k() {
+        let mut rng = ChaCha20Rng::seed_from_u64(2707);
+        let sampler = BestOfK {
+            sampler: Independent {
+                control: ControlSet(vec![Vector::new([-1.0]), Vector::new([1.0])]),
+                duration: DurationSteps {
+                    step: 0.1,
+                    min_steps: 1,
+                    max_steps: 5,
+                },
+            },
+            metric: SquaredEuclidean,
+            k: 200,
+        };
+        for _ in 0..100 {
+            let (u, d) = sampler.sample_control(
+                &Integrator,
+                &Vector::new([0.0]),
+                &Vector::new([1.0]),
+                &mut rng,
+            );
+            assert_eq!(u, Vector::new([1.0]));
+            assert!((d - 0.5).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn independent_box_and_set() {
+        let mut rng = ChaCha20Rng::seed_from_u64(2707);
+        let boxed = Independent {
+            control: Rectangle {
+                min: Vector::new([-1.0, 0.0]),
+                max: Vector::new([1.0, 2.0]),
+            },
+            duration: DurationRange { min: 0.1, max: 0.2 },
+        };
+        for _ in 0..100 {
+            let (u, d): (Vector<2>, f64) =
+                boxed.sample_control(&(), &Vector::new([0.0]), &Vector::new([1.0]), &mut rng);
+            assert!((-1.0..=1.0).contains(&u[0]));
+            assert!((0.0..=2.0).contains(&u[1]));
+            assert!((0.1..=0.2).contains(&d));
+        }
+
+        let controls = vec![Vector::new([-1.0]), Vector::new([0.0]), Vector::new([1.0])];
+        let set = Independent {
+            control: ControlSet(controls.clone()),
+            duration: DurationSteps {
+                step: 0.1,
+                min_steps: 2,
+                max_steps: 2,
+            },
+        };
+        let mut seen = [false; 3];
+        for _ in 0..100 {
+            let (u, d): (Vector<1>, f64) = set.sample_control(
+                &Integrator,
+                &Vector::new([0.0]),
+                &Vector::new([1.0]),
+                &mut rng,
+            );
+            seen[controls.iter().position(|c| *c == u).unwrap()] = true;
+            assert!((d - 0.2).abs() < 1e-9);
+        }
+        assert_eq!(seen, [true; 3]);
+    }
+
+    #[test]
+    fn duration_range() {
+        let mut rng = ChaCha20Rng::seed_from_u64(2707);
+        let sampler = DurationRange { min: 0.1, max: 0.2 };
+        for _ in 0..100 {
+            let d: f64 = sampler.sample(&mut rng);
+            assert!((0.1..=0.2).contains(&d));
+        }
+    }
+}
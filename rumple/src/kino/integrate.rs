@@ -0,0 +1,304 @@
+//! Fixed-step numerical integrators for ordinary differential equations.
+
+use core::array;
+
+use num_traits::{Float, NumCast};
+
+use crate::space::Vector;
+
+use super::Propagate;
+
+/// A fixed-step numerical integration scheme.
+///
+/// `C` is the configuration type, `U` is the control type, and `T` is the duration type.
+pub trait Integrate<C, U, T> {
+    /// Get the largest sub-step which this integrator may take.
+    fn step_size(&self) -> T;
+
+    /// Advance `state` by a single sub-step of length `h` while holding `control`.
+    fn step(&self, state: &C, control: &U, h: T) -> C;
+
+    /// Get an iterator over every intermediate state visited while holding `control` from `start`
+    /// for `duration`. The iterator yields the state after each sub-step, so it does not include
+    /// `start` but does include the final state.
+    /// If `duration` is not positive, the iterator is empty.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `duration` is positive but the step size is not, or if
+    /// `duration` would take more than `usize::MAX` sub-steps.
+    fn substeps<'a>(&'a self, start: &C, control: &'a U, duration: T) -> Substeps<'a, Self, C, U, T>
+    where
+        Self: Sized,
+        C: Clone,
+        T: Float,
+    {
+        let step = self.step_size();
+        let n = if duration > T::zero() {
+            assert!(step > T::zero(), "integrator step size must be positive");
+            <usize as NumCast>::from((duration / step).ceil())
+                .expect("too many substeps for duration")
+        } else {
+            0
+        };
+        Substeps {
+            integrator: self,
+            state: start.clone(),
+            control,
+            h: if n == 0 {
+                T::zero()
+            } else {
+                duration / <T as NumCast>::from(n).unwrap()
+            },
+            n,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The forward Euler method: a first-order integrator.
+///
+/// `F` is the state derivative function, which maps a state and a control to the derivative of the
+/// state, and `T` is the duration type.
+pub struct Euler<F, T> {
+    /// The state derivative function.
+    pub dynamics: F,
+    /// The largest sub-step to take while integrating.
+    pub step: T,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The explicit midpoint method: a second-order integrator.
+///
+/// `F` is the state derivative function, which maps a state and a control to the derivative of the
+/// state, and `T` is the duration type.
+pub struct Midpoint<F, T> {
+    /// The state derivative function.
+    pub dynamics: F,
+    /// The largest sub-step to take while integrating.
+    pub step: T,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The classic fourth-order Runge-Kutta method.
+///
+/// `F` is the state derivative function, which maps a state and a control to the derivative of the
+/// state, and `T` is the duration type.
+pub struct Rk4<F, T> {
+    /// The state derivative function.
+    pub dynamics: F,
+    /// The largest sub-step to take while integrating.
+    pub step: T,
+}
+
+#[derive(Clone, Debug)]
+/// An iterator over the intermediate states of an integration.
+///
+/// Created by [`Integrate::substeps`].
+pub struct Substeps<'a, I, C, U, T> {
+    integrator: &'a I,
+    state: C,
+    control: &'a U,
+    h: T,
+    n: usize,
+}
+
+impl<I, C, U, T> Iterator for Substeps<'_, I, C, U, T>
+where
+    I: Integrate<C, U, T>,
+    C: Clone,
+    T: Clone,
+{
+    type Item = C;
+    fn next(&mut self) -> Option<Self::Item> {
+        (self.n > 0).then(|| {
+            self.n -= 1;
+            self.state = self
+                .integrator
+                .step(&self.state, self.control, self.h.clone());
+            self.state.clone()
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.n, Some(self.n))
+    }
+}
+
+/// Compute `x + h * dx`.
+fn add_scaled<const N: usize, T: Float>(x: &Vector<N, T>, dx: &Vector<N, T>, h: T) -> Vector<N, T> {
+    Vector(array::from_fn(|i| x[i] + h * dx[i]))
+}
+
+impl<F, U, T, const N: usize> Integrate<Vector<N, T>, U, T> for Euler<F, T>
+where
+    F: Fn(&Vector<N, T>, &U) -> Vector<N, T>,
+    T: Float,
+{
+    fn step_size(&self) -> T {
+        self.step
+    }
+
+    fn step(&self, state: &Vector<N, T>, control: &U, h: T) -> Vector<N, T> {
+        add_scaled(state, &(self.dynamics)(state, control), h)
+    }
+}
+
+impl<F, U, T, const N: usize> Integrate<Vector<N, T>, U, T> for Midpoint<F, T>
+where
+    F: Fn(&Vector<N, T>, &U) -> Vector<N, T>,
+    T: Float,
+{
+    fn step_size(&self) -> T {
+        self.step
+    }
+
+    fn step(&self, state: &Vector<N, T>, control: &U, h: T) -> Vector<N, T> {
+        let half = h / (T::one() + T::one());
+        let k1 = (self.dynamics)(state, control);
+        let k2 = (self.dynamics)(&add_scaled(state, &k1, half), control);
+        add_scaled(state, &k2, h)
+    }
+}
+
+impl<F, U, T, const N: usize> Integrate<Vector<N, T>, U, T> for Rk4<F, T>
+where
+    F: Fn(&Vector<N, T>, &U) -> Vector<N, T>,
+    T: Float,
+{
+    fn step_size(&self) -> T {
+        self.step
+    }
+
+    fn step(&self, state: &Vector<N, T>, control: &U, h: T) -> Vector<N, T> {
+        let two = T::one() + T::one();
+        let half = h / two;
+        let k1 = (self.dynamics)(state, control);
+        let k2 = (self.dynamics)(&add_scaled(state, &k1, half), control);
+        let k3 = (self.dynamics)(&add_scaled(state, &k2, half), control);
+        let k4 = (self.dynamics)(&add_scaled(state, &k3, h), control);
+        let sixth = h / (two + two + two);
+        Vector(array::from_fn(|i| {
+            state[i] + sixth * (k1[i] + two * k2[i] + two * k3[i] + k4[i])
+        }))
+    }
+}
+
+/// Implement `Propagate` for an integrator by taking every one of its sub-steps.
+macro_rules! propagate_impl {
+    ($t: ident) => {
+        impl<F, U, T, const N: usize> Propagate<Vector<N, T>, U, T> for $t<F, T>
+        where
+            F: Fn(&Vector<N, T>, &U) -> Vector<N, T>,
+            T: Float,
+        {
+            fn propagate(&self, state: &Vector<N, T>, control: &U, duration: T) -> Vector<N, T> {
+                self.substeps(state, control, duration)
+                    .last()
+                    .unwrap_or(*state)
+            }
+        }
+    };
+}
+
+propagate_impl!(Euler);
+propagate_impl!(Midpoint);
+propagate_impl!(Rk4);
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::valid::{CheckSubsteps, DynamicValidate};
+
+    /// Exponential decay: `dx/dt = -x`.
+    #[expect(
+        clippy::trivially_copy_pass_by_ref,
+        reason = "integrators take dynamics which borrow the state and control"
+    )]
+    fn decay(x: &Vector<1>, (): &()) -> Vector<1> {
+        Vector::new([-x[0]])
+    }
+
+    #[test]
+    fn accuracy() {
+        let start = Vector::new([1.0]);
+        let expected = (-1.0f64).exp();
+        let euler = Euler {
+            dynamics: decay,
+            step: 0.01,
+        };
+        let midpoint = Midpoint {
+            dynamics: decay,
+            step: 0.01,
+        };
+        let rk4 = Rk4 {
+            dynamics: decay,
+            step: 0.01,
+        };
+        let e_euler = (euler.propagate(&start, &(), 1.0)[0] - expected).abs();
+        let e_midpoint = (midpoint.propagate(&start, &(), 1.0)[0] - expected).abs();
+        let e_rk4 = (rk4.propagate(&start, &(), 1.0)[0] - expected).abs();
+
+        assert!(e_euler < 1e-2);
+        assert!(e_midpoint < 1e-4);
+        assert!(e_rk4 < 1e-9);
+        assert!(e_rk4 < e_midpoint && e_midpoint < e_euler);
+    }
+
+    #[test]
+    fn check_substeps() {
+        let euler = Euler {
+            dynamics: |_: &Vector<1>, u: &f64| Vector::new([*u]),
+            step: 0.1,
+        };
+        // states beyond 0.5 are invalid, so any motion that passes through them must be rejected
+        let valid = CheckSubsteps(|x: &Vector<1>| x[0] <= 0.5);
+        let start = Vector::new([0.0]);
+        assert!(valid.is_valid_transition(&euler, &start, &1.0, 0.5, &Vector::new([0.5])));
+        assert!(!valid.is_valid_transition(&euler, &start, &1.0, 0.6, &Vector::new([0.6])));
+    }
+
+    #[test]
+    fn substeps() {
+        let rk4 = Rk4 {
+            dynamics: decay,
+            step: 0.3,
+        };
+        let states: Vec<_> = rk4.substeps(&Vector::new([1.0]), &(), 1.0).collect();
+        // 1.0 / 0.3 rounds up to 4 sub-steps
+        assert_eq!(states.len(), 4);
+        assert_eq!(
+            states.last(),
+            Some(&rk4.propagate(&Vector::new([1.0]), &(), 1.0))
+        );
+        assert!(states.windows(2).all(|w| w[1][0] < w[0][0]));
+        assert_eq!(rk4.substeps(&Vector::new([1.0]), &(), 0.0).count(), 0);
+        assert_eq!(rk4.substeps(&Vector::new([1.0]), &(), -1.0).count(), 0);
+        assert_eq!(
+            rk4.propagate(&Vector::new([1.0]), &(), -1.0),
+            Vector::new([1.0])
+        );
+    }
+
+    #[test]
+    #[should_panic = "integrator step size must be positive"]
+    fn zero_step() {
+        let euler = Euler {
+            dynamics: decay,
+            step: 0.0,
+        };
+        let _ = euler.substeps(&Vector::new([1.0]), &(), 1.0);
+    }
+
+    #[test]
+    #[should_panic = "too many substeps for duration"]
+    fn tiny_step() {
+        let euler = Euler {
+            dynamics: decay,
+            step: f64::MIN_POSITIVE,
+        };
+        let _ = euler.substeps(&Vector::new([1.0]), &(), 1.0);
+    }
+}
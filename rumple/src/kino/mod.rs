@@ -1,5 +1,17 @@
 //! Kinodynamic planning algorithms.
 
+use alloc::vec::Vec;
+
+mod control;
+mod integrate;
+mod rrt;
+mod sst;
+
+pub use control::{BestOfK, ControlSample, ControlSet, DurationRange, DurationSteps, Independent};
+pub use integrate::{Euler, Integrate, Midpoint, Rk4, Substeps};
+pub use rrt::{rrt, Rrt};
+pub use sst::{Sst, SstRadius};
+
 /// A trait for dynamic propagators.
 ///
 /// `C` is the configuration type, `U` is the control type, and `D` is the duration type.
@@ -24,3 +36,37 @@ pub trait DynamicValidate<P, C, U, D> {
         end: &C,
     ) -> bool;
 }
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// A plan produced by a kinodynamic planner.
+///
+/// `C` is the configuration type, `U` is the control type, and `D` is the duration type.
+pub struct Plan<C, U, D> {
+    /// The sequence of states visited by the plan, beginning with the start state.
+    pub states: Vec<C>,
+    /// The sequence of controls, each paired with the duration for which it is held.
+    /// Holding `controls[i]` from `states[i]` yields `states[i + 1]`, so there is always one
+    /// fewer control than there are states.
+    pub controls: Vec<(U, D)>,
+}
+
+#[cfg(test)]
+mod tests {
+    use core::array;
+
+    use num_traits::Float;
+
+    use super::Propagate;
+    use crate::space::Vector;
+
+    /// A single integrator, whose control is its velocity.
+    pub struct Integrator;
+
+    impl<const N: usize> Propagate<Vector<N>, Vector<N>, f64> for Integrator {
+        fn propagate(&self, state: &Vector<N>, control: &Vector<N>, duration: f64) -> Vector<N> {
+            Vector(array::from_fn(|i| {
+                Float::mul_add(control[i], duration, state[i])
+            }))
+        }
+    }
+}
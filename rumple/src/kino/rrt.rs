@@ -0,0 +1,316 @@
+use alloc::vec::Vec;
+
+use crate::{
+    metric::Metric,
+    nn::{NearestEntry, NearestNeighborsMap},
+    sample::Sample,
+    time::Timeout,
+    valid::{DynamicValidate, Validate},
+};
+
+use super::{ControlSample, Plan, Propagate};
+
+#[derive(Clone, Debug)]
+/// A kinodynamic rapidly-exploring random tree.
+///
+/// Instead of interpolating toward sampled states, this tree is expanded by sampling a control and
+/// a duration, then propagating the nearest node forward under the system dynamics.
+///
+/// # Generic parameters
+///
+/// - `C` should be the configuration of a robot.
+/// - `U` is the control type.
+/// - `D` is the duration type.
+/// - `NN` should be the nearest neighbors data structure, which can use `C` as a key and implement
+///   [`NearestNeighborsMap`].
+/// - `P` is the state propagator; it must implement [`Propagate`].
+/// - `V` should be a state validator; it must implement [`Validate`] and [`DynamicValidate`].
+///
+/// # Citation
+///
+/// ```bibtex
+/// @article{lavalle2001randomized,
+///   title={Randomized kinodynamic planning},
+///   author={LaValle, Steven M and Kuffner Jr, James J},
+///   journal={The international journal of robotics research},
+///   volume={20},
+///   number={5},
+///   pages={378--400},
+///   year={2001},
+///   publisher={Sage Publications}
+/// }
+/// ```
+pub struct Rrt<'a, C, U, D, NN, P, V> {
+    /// buffer of saved configurations
+    /// configurations[0] is the root
+    configurations: Vec<C>,
+    /// ids for each configuration
+    /// `parent_ids[0]` is ignorable
+    parent_ids: Vec<usize>,
+    /// The control and duration used to reach each node from its parent.
+    /// `controls[i]` leads to `configurations[i + 1]`, since the root has no incoming edge.
+    controls: Vec<(U, D)>,
+    /// The nearest neighbors lookup.
+    nn: NN,
+    /// The state propagator.
+    propagator: &'a P,
+    /// The state validator.
+    valid: &'a V,
+}
+
+#[expect(clippy::too_many_arguments)]
+/// Plan from a start configuration to a goal using a kinodynamic [`Rrt`].
+///
+/// # Parameters
+///
+/// - `start`: The start configuration.
+/// - `nn`: The nearest-neighbor structure.
+/// - `propagator`: The state propagator.
+/// - `valid`: The state validator.
+/// - `space_sampler`: A sampler for states in the configuration space.
+/// - `control_sampler`: A sampler for controls and the durations over which they are held.
+/// - `goal`: The goal state.
+/// - `goal_metric`: The metric for determining whether a state has reached the goal.
+/// - `goal_radius`: A state is considered to have reached the goal if its distance to `goal` under
+///   `goal_metric` is no more than `goal_radius`.
+/// - `timeout`: The timeout condition. The planning algorithm will continue until `timeout` is
+///   over.
+/// - `target_goal_distn`: A sampler which returns `true` with some probability; every time it
+///   returns `true`, the RRT grows toward the goal instead of to fill the space.
+/// - `rng`: The source of randomness.
+pub fn rrt<C, U, D, NN, P, V, SP, CS, M, TC, TG, RNG>(
+    start: C,
+    nn: NN,
+    propagator: &P,
+    valid: &V,
+    space_sampler: &SP,
+    control_sampler: &CS,
+    goal: &C,
+    goal_metric: &M,
+    goal_radius: &M::Distance,
+    timeout: &mut TC,
+    target_goal_distn: &TG,
+    rng: &mut RNG,
+) -> Option<Plan<C, U, D>>
+where
+    C: Clone,
+    U: Clone,
+    D: Clone,
+    NN: NearestNeighborsMap<C, usize>,
+    P: Propagate<C, U, D>,
+    V: Validate<C> + DynamicValidate<P, C, U, D>,
+    SP: Sample<C, RNG>,
+    CS: ControlSample<P, C, U, D, RNG>,
+    M: Metric<C>,
+    TC: Timeout,
+    TG: Sample<bool, RNG>,
+{
+    let mut rrt = Rrt::new(start, nn, propagator, valid);
+    rrt.grow_toward(
+        space_sampler,
+        control_sampler,
+        goal,
+        goal_metric,
+        goal_radius,
+        timeout,
+        target_goal_distn,
+        rng,
+    )
+}
+
+impl<'a, C, U, D, NN, P, V> Rrt<'a, C, U, D, NN, P, V> {
+    /// Construct a new kinodynamic RRT rooted at `root`, using `nn` as its nearest-neighbor
+    /// structure, `propagator` to simulate the dynamics, and `valid` as its state validator.
+    pub fn new(root: C, mut nn: NN, propagator: &'a P, valid: &'a V) -> Self
+    where
+        NN: NearestNeighborsMap<C, usize>,
+        C: Clone,
+    {
+        nn.insert(root.clone(), 0);
+        Self {
+            configurations: vec![root],
+            parent_ids: vec![usize::MAX],
+            controls: Vec::new(),
+            nn,
+            propagator,
+            valid,
+        }
+    }
+
+    #[expect(clippy::too_many_arguments, clippy::missing_panics_doc)]
+    /// Grow this RRT toward the provided goal `goal`.
+    ///
+    /// # Parameters
+    ///
+    /// - `space_sampler`: A sampler for states in the configuration space.
+    /// - `control_sampler`: A sampler for controls and the durations over which they are held.
+    /// - `goal`: The goal state.
+    /// - `goal_metric`: The metric for determining whether a state has reached the goal.
+    /// - `goal_radius`: A state is considered to have reached the goal if its distance to `goal`
+    ///   under `goal_metric` is no more than `goal_radius`.
+    /// - `timeout`: The timeout condition. The planning algorithm will continue until `timeout` is
+    ///   over.
+    /// - `target_goal_distn`: A sampler which returns `true` with some probability; every time it
+    ///   returns `true`, the RRT grows toward the goal instead of to fill the space.
+    /// - `rng`: The source of randomness.
+    pub fn grow_toward<SP, CS, M, TC, TG, RNG>(
+        &mut self,
+        space_sampler: &SP,
+        control_sampler: &CS,
+        goal: &C,
+        goal_metric: &M,
+        goal_radius: &M::Distance,
+        timeout: &mut TC,
+        target_goal_distn: &TG,
+        rng: &mut RNG,
+    ) -> Option<Plan<C, U, D>>
+    where
+        C: Clone,
+        U: Clone,
+        D: Clone,
+        NN: NearestNeighborsMap<C, usize>,
+        P: Propagate<C, U, D>,
+        V: Validate<C> + DynamicValidate<P, C, U, D>,
+        SP: Sample<C, RNG>,
+        CS: ControlSample<P, C, U, D, RNG>,
+        M: Metric<C>,
+        TC: Timeout,
+        TG: Sample<bool, RNG>,
+    {
+        if !self.valid.is_valid_configuration(&self.configurations[0]) {
+            return None; // invalid configuration
+        }
+        let mut soln = None;
+        while !timeout.is_over() {
+            timeout.update_sample_count(1);
+            let target = if target_goal_distn.sample(rng) {
+                goal.clone()
+            } else {
+                space_sampler.sample(rng)
+            };
+            let &start_id = self
+                .nn
+                .nearest(&target)
+                .expect("NN must always have elements")
+                .value();
+            let start_cfg = &self.configurations[start_id];
+            let (control, duration) =
+                control_sampler.sample_control(self.propagator, start_cfg, &target, rng);
+            let end_cfg = self
+                .propagator
+                .propagate(start_cfg, &control, duration.clone());
+            if !self.valid.is_valid_configuration(&end_cfg)
+                || !self.valid.is_valid_transition(
+                    self.propagator,
+                    start_cfg,
+                    &control,
+                    duration.clone(),
+                    &end_cfg,
+                )
+            {
+                continue;
+            }
+            timeout.update_node_count(1);
+            let new_id = self.configurations.len();
+            let reached = goal_metric.distance(&end_cfg, goal) <= *goal_radius;
+            self.configurations.push(end_cfg.clone());
+            self.parent_ids.push(start_id);
+            self.controls.push((control, duration));
+            debug_assert_eq!(
+                self.configurations.len(),
+                self.parent_ids.len(),
+                "number of configurations and parents must be equal"
+            );
+            self.nn.insert(end_cfg, new_id);
+            if reached {
+                timeout.notify_solved();
+                soln = Some(new_id);
+            }
+        }
+
+        soln.map(|id| self.plan_to(id))
+    }
+
+    /// Extract the plan from the root to the node with ID `id`.
+    fn plan_to(&self, mut id: usize) -> Plan<C, U, D>
+    where
+        C: Clone,
+        U: Clone,
+        D: Clone,
+    {
+        let mut states = Vec::new();
+        let mut controls = Vec::new();
+        while id != 0 {
+            states.push(self.configurations[id].clone());
+            controls.push(self.controls[id - 1].clone());
+            id = self.parent_ids[id];
+        }
+        states.push(self.configurations[0].clone());
+        states.reverse();
+        controls.reverse();
+        Plan { states, controls }
+    }
+
+    #[must_use]
+    /// Get the number of total nodes in this tree.
+    pub const fn num_nodes(&self) -> usize {
+        self.configurations.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        kino::{tests::Integrator, DurationSteps, Independent},
+        metric::{Euclidean, SquaredEuclidean},
+        nn::KdTreeMap,
+        sample::Rectangle,
+        space::Vector,
+        time::Solved,
+        valid::AlwaysValid,
+    };
+    use rand::{distributions::Bernoulli, SeedableRng};
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn integrator2d() {
+        let goal = Vector::new([1.0, 1.0]);
+        let goal_radius = 0.05;
+        let plan = rrt(
+            Vector::new([0.0, 0.0]),
+            KdTreeMap::new(SquaredEuclidean),
+            &Integrator,
+            &AlwaysValid,
+            &Rectangle {
+                min: Vector::new([0.0; 2]),
+                max: Vector::new([1.0; 2]),
+            },
+            &Independent {
+                control: Rectangle {
+                    min: Vector::new([-1.0; 2]),
+                    max: Vector::new([1.0; 2]),
+                },
+                duration: DurationSteps {
+                    step: 0.05,
+                    min_steps: 1,
+                    max_steps: 1,
+                },
+            },
+            &goal,
+            &Euclidean,
+            &goal_radius,
+            &mut Solved::new(),
+            &Bernoulli::new(0.05).unwrap(),
+            &mut ChaCha20Rng::seed_from_u64(2707),
+        )
+        .unwrap();
+
+        assert_eq!(plan.states.len(), plan.controls.len() + 1);
+        assert_eq!(plan.states[0], Vector::new([0.0, 0.0]));
+        assert!(Euclidean.distance(plan.states.last().unwrap(), &goal) <= goal_radius);
+        for (w, (u, d)) in plan.states.windows(2).zip(&plan.controls) {
+            assert_eq!(Integrator.propagate(&w[0], u, *d), w[1]);
+        }
+    }
+}
@@ -0,0 +1,506 @@
+use alloc::vec::Vec;
+use core::ops::Add;
+
+use num_traits::Zero;
+
+use crate::{
+    metric::Metric,
+    nn::{NearestEntry, NearestNeighborsMap, RangeNearestNeighborsMap, RemoveNearestNeighborsMap},
+    sample::Sample,
+    time::Timeout,
+    valid::{DynamicValidate, Validate},
+};
+
+use super::{ControlSample, Plan, Propagate};
+
+#[derive(Clone, Debug)]
+/// A Stable Sparse RRT: an asymptotically near-optimal kinodynamic planner.
+///
+/// SST maintains a sparse set of _witness_ states which cover the state space. Each witness is
+/// represented by the lowest-cost node that has been found near it, and any node that is dominated
+/// by a better representative is deactivated. Inactive leaves are pruned from the tree, so the tree
+/// remains sparse even over long planning horizons.
+///
+/// The cost of a node is the total duration of the controls used to reach it from the root.
+///
+/// # Generic parameters
+///
+/// - `C` should be the configuration of a robot.
+/// - `U` is the control type.
+/// - `D` is the duration type.
+/// - `NN` is the nearest-neighbor structure for active nodes in the tree. It must implement
+///   [`RangeNearestNeighborsMap`] in order to select the best nearby node for expansion, and
+///   [`RemoveNearestNeighborsMap`] so that nodes can be removed from it once they are inactive.
+/// - `W` is the nearest-neighbor structure for witnesses.
+/// - `P` is the state propagator; it must implement [`Propagate`].
+/// - `V` should be a state validator; it must implement [`Validate`] and [`DynamicValidate`].
+/// - `M` is the metric between states. It should agree with the metric used by `NN` and `W`.
+///
+/// # Citation
+///
+/// ```bibtex
+/// @article{li2016asymptotically,
+///   title={Asymptotically optimal sampling-based kinodynamic planning},
+///   author={Li, Yanbo and Littlefield, Zakary and Bekris, Kostas E},
+///   journal={The International Journal of Robotics Research},
+///   volume={35},
+///   number={5},
+///   pages={528--564},
+///   year={2016},
+///   publisher={SAGE Publications}
+/// }
+/// ```
+pub struct Sst<'a, C, U, D, NN, W, P, V, M> {
+    /// buffer of saved configurations
+    /// configurations[0] is the root
+    configurations: Vec<C>,
+    /// ids for each configuration
+    /// `parent_ids[0]` is ignorable
+    parent_ids: Vec<usize>,
+    /// The control and duration used to reach each node from its parent.
+    /// `controls[i]` leads to `configurations[i + 1]`, since the root has no incoming edge.
+    controls: Vec<(U, D)>,
+    /// The cost-to-come of each node from the root.
+    costs: Vec<D>,
+    /// The number of unpruned children of each node.
+    n_children: Vec<usize>,
+    /// The status of each node.
+    status: Vec<Status>,
+    /// The nearest-neighbor lookup for active nodes.
+    /// Nodes are removed from the lookup as soon as they become inactive.
+    nn: NN,
+    /// The configuration of each witness.
+    witnesses: Vec<C>,
+    /// The ID of the node representing each witness, if any.
+    witness_reps: Vec<Option<usize>>,
+    /// The nearest-neighbor lookup for witnesses.
+    witness_nn: W,
+    /// The state propagator.
+    propagator: &'a P,
+    /// The state validator.
+    valid: &'a V,
+    /// The metric between states.
+    metric: M,
+    /// The IDs of nodes which have been pruned from the tree.
+    /// Their storage is reused by new nodes, so that the buffers only grow with the live tree.
+    free: Vec<usize>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The status of a node in an [`Sst`].
+enum Status {
+    /// The node is the representative of its witness and may be expanded.
+    Active,
+    /// The node has been dominated, but still has descendants.
+    Inactive,
+    /// The node has been removed from the tree.
+    Pruned,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The radii which control the sparsity of an [`Sst`].
+pub struct SstRadius<R> {
+    /// The radius around a sample in which the lowest-cost active node is selected for expansion.
+    pub best_near: R,
+    /// The radius of the region covered by each witness.
+    pub witness: R,
+}
+
+impl<'a, C, U, D, NN, W, P, V, M> Sst<'a, C, U, D, NN, W, P, V, M> {
+    /// Construct a new SST rooted at `root`.
+    ///
+    /// `nn` and `witness_nn` must both be empty; they will be used to look up nodes and witnesses
+    /// respectively. `propagator` simulates the dynamics, `valid` is the state validator, and
+    /// `metric` is the distance between states.
+    pub fn new(
+        root: C,
+        mut nn: NN,
+        mut witness_nn: W,
+        propagator: &'a P,
+        valid: &'a V,
+        metric: M,
+    ) -> Self
+    where
+        NN: NearestNeighborsMap<C, usize>,
+        W: NearestNeighborsMap<C, usize>,
+        C: Clone,
+        D: Zero,
+    {
+        nn.insert(root.clone(), 0);
+        witness_nn.insert(root.clone(), 0);
+        Self {
+            configurations: vec![root.clone()],
+            parent_ids: vec![usize::MAX],
+            controls: Vec::new(),
+            costs: vec![D::zero()],
+            n_children: vec![0],
+            status: vec![Status::Active],
+            nn,
+            witnesses: vec![root],
+            witness_reps: vec![Some(0)],
+            witness_nn,
+            propagator,
+            valid,
+            metric,
+            free: Vec::new(),
+        }
+    }
+
+    #[expect(clippy::too_many_arguments, clippy::missing_panics_doc)]
+    /// Grow this SST toward the provided goal `goal`, returning the lowest-cost plan to the goal
+    /// once `timeout` is over.
+    ///
+    /// # Parameters
+    ///
+    /// - `space_sampler`: A sampler for states in the configuration space.
+    /// - `control_sampler`: A sampler for controls and the durations over which they are held.
+    /// - `radius`: The best-near and witness radii.
+    /// - `goal`: The goal state.
+    /// - `goal_radius`: A state is considered to have reached the goal if its distance to `goal` is
+    ///   no more than `goal_radius`.
+    /// - `timeout`: The timeout condition. The planning algorithm will continue until `timeout` is
+    ///   over.
+    /// - `target_goal_distn`: A sampler which returns `true` with some probability; every time it
+    ///   returns `true`, the SST grows toward the goal instead of to fill the space.
+    /// - `rng`: The source of randomness.
+    pub fn grow_toward<SP, CS, R, TC, TG, RNG>(
+        &mut self,
+        space_sampler: &SP,
+        control_sampler: &CS,
+        radius: &SstRadius<R>,
+        goal: &C,
+        goal_radius: &R,
+        timeout: &mut TC,
+        target_goal_distn: &TG,
+        rng: &mut RNG,
+    ) -> Option<Plan<C, U, D>>
+    where
+        C: Clone,
+        U: Clone,
+        D: Clone + Add<Output = D> + PartialOrd,
+        NN: RangeNearestNeighborsMap<C, usize, Distance = R> + RemoveNearestNeighborsMap<C, usize>,
+        W: NearestNeighborsMap<C, usize>,
+        P: Propagate<C, U, D>,
+        V: Validate<C> + DynamicValidate<P, C, U, D>,
+        M: Metric<C, Distance = R>,
+        R: Clone + PartialOrd,
+        SP: Sample<C, RNG>,
+        CS: ControlSample<P, C, U, D, RNG>,
+        TC: Timeout,
+        TG: Sample<bool, RNG>,
+    {
+        if !self.valid.is_valid_configuration(&self.configurations[0]) {
+            return None; // invalid configuration
+        }
+        let mut best: Option<(D, Plan<C, U, D>)> = None;
+        while !timeout.is_over() {
+            timeout.update_sample_count(1);
+            let target = if target_goal_distn.sample(rng) {
+                goal.clone()
+            } else {
+                space_sampler.sample(rng)
+            };
+            let Some(start_id) = self.best_near(&target, radius.best_near.clone()) else {
+                continue;
+            };
+            let start_cfg = &self.configurations[start_id];
+            let (control, duration) =
+                control_sampler.sample_control(self.propagator, start_cfg, &target, rng);
+            let end_cfg = self
+                .propagator
+                .propagate(start_cfg, &control, duration.clone());
+            if !self.valid.is_valid_configuration(&end_cfg)
+                || !self.valid.is_valid_transition(
+                    self.propagator,
+                    start_cfg,
+                    &control,
+                    duration.clone(),
+                    &end_cfg,
+                )
+            {
+                continue;
+            }
+            let cost = self.costs[start_id].clone() + duration.clone();
+
+            // only add the new node if it is the best node in its witness region
+            let &nearest_witness = self
+                .witness_nn
+                .nearest(&end_cfg)
+                .expect("witness NN must always have elements")
+                .value();
+            let witness_id = if self
+                .metric
+                .distance(&end_cfg, &self.witnesses[nearest_witness])
+                > radius.witness
+            {
+                let id = self.witnesses.len();
+                self.witnesses.push(end_cfg.clone());
+                self.witness_reps.push(None);
+                self.witness_nn.insert(end_cfg.clone(), id);
+                id
+            } else {
+                nearest_witness
+            };
+            let peer = self.witness_reps[witness_id];
+            if peer.is_some_and(|p| self.costs[p] <= cost) {
+                continue;
+            }
+
+            timeout.update_node_count(1);
+            let reached = self.metric.distance(&end_cfg, goal) <= *goal_radius;
+            let new_id =
+                self.add_node(end_cfg.clone(), start_id, (control, duration), cost.clone());
+            self.nn.insert(end_cfg, new_id);
+            self.witness_reps[witness_id] = Some(new_id);
+
+            if let Some(peer) = peer {
+                self.status[peer] = Status::Inactive;
+                self.nn.remove(&self.configurations[peer], &peer);
+                self.prune(peer);
+            }
+
+            if reached && best.as_ref().is_none_or(|(c, _)| cost < *c) {
+                timeout.notify_solved();
+                best = Some((cost, self.plan_to(new_id)));
+            }
+        }
+
+        best.map(|(_, plan)| plan)
+    }
+
+    /// Find the lowest-cost active node within `radius` of `target`.
+    /// If there is no such node, fall back to the nearest active node.
+    fn best_near<R>(&self, target: &C, radius: R) -> Option<usize>
+    where
+        NN: RangeNearestNeighborsMap<C, usize, Distance = R>,
+        D: PartialOrd,
+    {
+        self.nn
+            .nearest_within_r(target, radius)
+            .map(|e| *e.value())
+            .reduce(|best, id| {
+                if self.costs[id] < self.costs[best] {
+                    id
+                } else {
+                    best
+                }
+            })
+            .or_else(|| self.nn.nearest(target).map(|e| *e.value()))
+    }
+
+    /// Add a new active node with configuration `cfg`, reached from `parent` by `control`, and
+    /// return its ID.
+    /// The ID of a pruned node is reused if there is one.
+    fn add_node(&mut self, cfg: C, parent: usize, control: (U, D), cost: D) -> usize {
+        self.n_children[parent] += 1;
+        if let Some(id) = self.free.pop() {
+            self.configurations[id] = cfg;
+            self.parent_ids[id] = parent;
+            self.controls[id - 1] = control;
+            self.costs[id] = cost;
+            self.n_children[id] = 0;
+            self.status[id] = Status::Active;
+            return id;
+        }
+        self.configurations.push(cfg);
+        self.parent_ids.push(parent);
+        self.controls.push(control);
+        self.costs.push(cost);
+        self.n_children.push(0);
+        self.status.push(Status::Active);
+        debug_assert_eq!(
+            self.configurations.len(),
+            self.parent_ids.len(),
+            "number of configurations and parents must be equal"
+        );
+        self.configurations.len() - 1
+    }
+
+    /// Remove the inactive node `id` from the tree if it is a leaf, along with any of its
+    /// ancestors which become inactive leaves as a result.
+    fn prune(&mut self, mut id: usize) {
+        while id != 0 && self.n_children[id] == 0 && self.status[id] == Status::Inactive {
+            self.status[id] = Status::Pruned;
+            self.free.push(id);
+            id = self.parent_ids[id];
+            self.n_children[id] -= 1;
+        }
+    }
+
+    /// Extract the plan from the root to the node with ID `id`.
+    fn plan_to(&self, mut id: usize) -> Plan<C, U, D>
+    where
+        C: Clone,
+        U: Clone,
+        D: Clone,
+    {
+        let mut states = Vec::new();
+        let mut controls = Vec::new();
+        while id != 0 {
+            states.push(self.configurations[id].clone());
+            controls.push(self.controls[id - 1].clone());
+            id = self.parent_ids[id];
+        }
+        states.push(self.configurations[0].clone());
+        states.reverse();
+        controls.reverse();
+        Plan { states, controls }
+    }
+
+    #[must_use]
+    /// Get the number of nodes in this tree which have not been pruned.
+    pub const fn num_nodes(&self) -> usize {
+        self.configurations.len() - self.free.len()
+    }
+
+    #[must_use]
+    /// Get the number of active nodes in this tree.
+    pub fn num_active(&self) -> usize {
+        self.status.iter().filter(|&&s| s == Status::Active).count()
+    }
+
+    #[must_use]
+    /// Get the number of witnesses in this tree.
+    pub const fn num_witnesses(&self) -> usize {
+        self.witnesses.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        kino::{tests::Integrator, DurationRange, Independent},
+        metric::SquaredEuclidean,
+        nn::KdTreeMap,
+        sample::Rectangle,
+        space::Vector,
+        time::{Any, LimitNodes, LimitSamples},
+        valid::AlwaysValid,
+    };
+    use rand::{distributions::Bernoulli, SeedableRng};
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn integrator2d() {
+        let goal = Vector::new([1.0, 1.0]);
+        let goal_radius = 0.0025;
+        let mut sst = Sst::new(
+            Vector::new([0.0, 0.0]),
+            KdTreeMap::new(SquaredEuclidean),
+            KdTreeMap::new(SquaredEuclidean),
+            &Integrator,
+            &AlwaysValid,
+            SquaredEuclidean,
+        );
+        let plan = sst
+            .grow_toward(
+                &Rectangle {
+                    min: Vector::new([0.0; 2]),
+                    max: Vector::new([1.0; 2]),
+                },
+                &Independent {
+                    control: Rectangle {
+                        min: Vector::new([-1.0; 2]),
+                        max: Vector::new([1.0; 2]),
+                    },
+                    duration: DurationRange {
+                        min: 0.01,
+                        max: 0.1,
+                    },
+                },
+                &SstRadius {
+                    best_near: 0.01,
+                    witness: 0.0025,
+                },
+                &goal,
+                &goal_radius,
+                &mut LimitSamples::new(20_000),
+                &Bernoulli::new(0.05).unwrap(),
+                &mut ChaCha20Rng::seed_from_u64(2707),
+            )
+            .unwrap();
+
+        assert_eq!(plan.states.len(), plan.controls.len() + 1);
+        assert_eq!(plan.states[0], Vector::new([0.0, 0.0]));
+        assert!(SquaredEuclidean.distance(plan.states.last().unwrap(), &goal) <= goal_radius);
+        for (w, (u, d)) in plan.states.windows(2).zip(&plan.controls) {
+            assert_eq!(Integrator.propagate(&w[0], u, *d), w[1]);
+        }
+        // each witness has at most one active representative
+        assert!(sst.num_active() <= sst.num_witnesses());
+        // only active nodes may be selected for expansion
+        assert_eq!(
+            sst.nn
+                .nearest_within_r(&Vector::new([0.5, 0.5]), 10.0)
+                .count(),
+            sst.num_active()
+        );
+        let cost: f64 = plan.controls.iter().map(|(_, d)| d).sum();
+
+        #[cfg(feature = "std")]
+        println!(
+            "{} nodes, {} witnesses, cost {cost}",
+            sst.num_nodes(),
+            sst.num_witnesses()
+        );
+        // the straight-line path at full speed takes 1 unit of time
+        assert!(cost < 1.5, "plan cost {cost} is too high");
+    }
+
+    #[test]
+    fn reuse_pruned() {
+        let mut sst = Sst::new(
+            Vector::new([0.0, 0.0]),
+            KdTreeMap::new(SquaredEuclidean),
+            KdTreeMap::new(SquaredEuclidean),
+            &Integrator,
+            &AlwaysValid,
+            SquaredEuclidean,
+        );
+        let mut timeout = Any((LimitSamples::new(20_000), LimitNodes::new(usize::MAX)));
+        sst.grow_toward(
+            &Rectangle {
+                min: Vector::new([0.0; 2]),
+                max: Vector::new([1.0; 2]),
+            },
+            &Independent {
+                control: Rectangle {
+                    min: Vector::new([-1.0; 2]),
+                    max: Vector::new([1.0; 2]),
+                },
+                duration: DurationRange {
+                    min: 0.01,
+                    max: 0.1,
+                },
+            },
+            &SstRadius {
+                best_near: 0.01,
+                witness: 0.0025,
+            },
+            &Vector::new([2.0, 2.0]),
+            &0.0,
+            &mut timeout,
+            &Bernoulli::new(0.0).unwrap(),
+            &mut ChaCha20Rng::seed_from_u64(2707),
+        );
+
+        // storage holds the live tree and the free list, not every node ever created
+        assert_eq!(sst.configurations.len(), sst.num_nodes() + sst.free.len());
+        #[cfg(feature = "std")]
+        println!(
+            "{} nodes created, {} stored",
+            timeout.0 .1.n_nodes(),
+            sst.configurations.len()
+        );
+        assert!(sst.configurations.len() < timeout.0 .1.n_nodes());
+        assert!(sst.free.iter().all(|&id| sst.status[id] == Status::Pruned));
+        assert!(sst
+            .status
+            .iter()
+            .enumerate()
+            .skip(1)
+            .filter(|&(_, &s)| s != Status::Pruned)
+            .all(|(id, _)| sst.status[sst.parent_ids[id]] != Status::Pruned));
+    }
+}
@@ -3,6 +3,7 @@
 //! zero-cost API.
 
 #![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(feature = "simd", feature(portable_simd))]
 #![warn(clippy::pedantic, clippy::nursery)]
 #![warn(clippy::allow_attributes, reason = "prefer expect over allow")]
 // #![warn(missing_docs)]
@@ -11,6 +12,7 @@
 extern crate alloc;
 
 pub mod geo;
+pub mod goal;
 pub mod kino;
 pub mod metric;
 pub mod nn;
@@ -4,7 +4,7 @@ use core::array;
 
 use crate::{
     nn::DistanceAabb,
-    space::{Angle, Vector},
+    space::{Angle, Orient, Vector},
 };
 use num_traits::{Float, FloatConst, Zero};
 
@@ -25,6 +25,18 @@ pub struct SquaredEuclidean;
 /// The Euclidean distance metric, i.e. the length of the line segment connecting two points.
 pub struct Euclidean;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// A metric on vectors which weights the difference along each axis.
+///
+/// With [`SquaredEuclidean`], this is the weighted sum of the squared differences along each
+/// axis; with [`Euclidean`], it is the square root of that sum.
+pub struct AxisWeighted<M, T, const N: usize> {
+    /// The underlying metric, either [`SquaredEuclidean`] or [`Euclidean`].
+    pub metric: M,
+    /// The weight of the squared difference along each axis.
+    pub weights: [T; N],
+}
+
 impl SquaredEuclidean {
     /// Computer the distance between two vectors without requiring that the result be strictly
     /// ordered.
@@ -96,6 +108,22 @@ where
     }
 }
 
+impl<T, const N: usize> DistanceAabb<Vector<N, T>> for Euclidean
+where
+    T: Float,
+{
+    fn distance_to_aabb(
+        &self,
+        c: &Vector<N, T>,
+        aabb_lo: &Vector<N, T>,
+        aabb_hi: &Vector<N, T>,
+    ) -> Self::Distance {
+        SquaredEuclidean
+            .distance_to_aabb(c, aabb_lo, aabb_hi)
+            .sqrt()
+    }
+}
+
 impl<T> Metric<Angle<T>> for SquaredEuclidean
 where
     T: Float + FloatConst,
@@ -140,6 +168,126 @@ where
     }
 }
 
+impl<T> Metric<Orient<T>> for Euclidean
+where
+    T: Float,
+{
+    type Distance = T;
+
+    /// Compute the geodesic distance between orientations: the angle of the smallest rotation
+    /// between them.
+    fn distance(&self, c1: &Orient<T>, c2: &Orient<T>) -> Self::Distance {
+        c1.angle_to(c2)
+    }
+}
+
+impl<T> Metric<Orient<T>> for SquaredEuclidean
+where
+    T: Float,
+{
+    type Distance = T;
+
+    fn distance(&self, c1: &Orient<T>, c2: &Orient<T>) -> Self::Distance {
+        let d = Euclidean.distance(c1, c2);
+        d * d
+    }
+}
+
+impl<T> DistanceAabb<Orient<T>> for Euclidean
+where
+    T: Float,
+{
+    fn distance_to_aabb(
+        &self,
+        c: &Orient<T>,
+        aabb_lo: &Orient<T>,
+        aabb_hi: &Orient<T>,
+    ) -> Self::Distance {
+        // For unit quaternions p and q with p . q >= 0, |p - q|^2 = 2 - 2 (p . q).
+        // Since q and -q represent the same orientation, the chordal distance from either of them
+        // to the box bounds the angle to any orientation inside the box.
+        let q = [c.x(), c.y(), c.z(), c.w()];
+        let lo = [aabb_lo.x(), aabb_lo.y(), aabb_lo.z(), aabb_lo.w()];
+        let hi = [aabb_hi.x(), aabb_hi.y(), aabb_hi.z(), aabb_hi.w()];
+        let mut pos_sq = T::zero();
+        let mut neg_sq = T::zero();
+        for i in 0..4 {
+            let d = q[i] - Float::clamp(q[i], lo[i], hi[i]);
+            pos_sq = pos_sq + d * d;
+            let d = -q[i] - Float::clamp(-q[i], lo[i], hi[i]);
+            neg_sq = neg_sq + d * d;
+        }
+        let two = T::one() + T::one();
+        let cos_half = (T::one() - pos_sq.min(neg_sq) / two).max(T::zero());
+        two * cos_half.acos()
+    }
+}
+
+impl<T> DistanceAabb<Orient<T>> for SquaredEuclidean
+where
+    T: Float,
+{
+    fn distance_to_aabb(
+        &self,
+        c: &Orient<T>,
+        aabb_lo: &Orient<T>,
+        aabb_hi: &Orient<T>,
+    ) -> Self::Distance {
+        Euclidean.distance_to_aabb(c, aabb_lo, aabb_hi).powi(2)
+    }
+}
+
+impl<T, const N: usize> Metric<Vector<N, T>> for AxisWeighted<SquaredEuclidean, T, N>
+where
+    T: Float,
+{
+    type Distance = T;
+
+    fn distance(&self, c1: &Vector<N, T>, c2: &Vector<N, T>) -> Self::Distance {
+        let mut total = T::zero();
+        for ((&a, &b), &w) in c1.iter().zip(c2.iter()).zip(&self.weights) {
+            total = total + w * (a - b) * (a - b);
+        }
+        total
+    }
+}
+
+impl<T, const N: usize> Metric<Vector<N, T>> for AxisWeighted<Euclidean, T, N>
+where
+    T: Float,
+{
+    type Distance = T;
+
+    fn distance(&self, c1: &Vector<N, T>, c2: &Vector<N, T>) -> Self::Distance {
+        AxisWeighted {
+            metric: SquaredEuclidean,
+            weights: self.weights,
+        }
+        .distance(c1, c2)
+        .sqrt()
+    }
+}
+
+impl<M, T, const N: usize> DistanceAabb<Vector<N, T>> for AxisWeighted<M, T, N>
+where
+    Self: Metric<Vector<N, T>>,
+    T: Float,
+{
+    fn distance_to_aabb(
+        &self,
+        c: &Vector<N, T>,
+        aabb_lo: &Vector<N, T>,
+        aabb_hi: &Vector<N, T>,
+    ) -> Self::Distance {
+        self.distance(
+            c,
+            &Vector(array::from_fn(|i| {
+                Float::clamp(c[i], aabb_lo[i], aabb_hi[i])
+            })),
+        )
+    }
+}
+
 impl Default for SquaredEuclidean {
     fn default() -> Self {
         Self
@@ -0,0 +1,224 @@
+use core::ops::Mul;
+
+use crate::metric::Metric;
+
+use super::{
+    kdt::{KNearest, RangeNearest},
+    BorrowedEntry, DistanceAabb, KNearestNeighborsMap, KdKey, KdTreeMap, NearestNeighborsMap,
+    RangeNearestNeighborsMap,
+};
+
+#[derive(Clone, Debug)]
+/// A nearest-neighbor map which performs (1 + ε)-approximate nearest-neighbor search over a
+/// [`KdTreeMap`].
+///
+/// The neighbor returned by [`NearestNeighborsMap::nearest`] is at most `1 + epsilon` times as
+/// far from the query as the true nearest neighbor.
+/// Larger values of `epsilon` prune more of the tree, trading accuracy for speed; this matters
+/// most in high-dimensional spaces, where exact search must visit most of the tree.
+/// An `epsilon` of zero gives exact search.
+/// Note that `epsilon` is applied to distances as measured by the metric, so for
+/// [`SquaredEuclidean`](crate::metric::SquaredEuclidean) the bound is on the squared distance.
+///
+/// Range and _k_-nearest queries are exact.
+///
+/// # Citation
+///
+/// ```bibtex
+/// @article{arya1998optimal,
+///   title={An optimal algorithm for approximate nearest neighbor searching fixed dimensions},
+///   author={Arya, Sunil and Mount, David M and Netanyahu, Nathan S and Silverman, Ruth and
+///           Wu, Angela Y},
+///   journal={Journal of the ACM},
+///   volume={45},
+///   number={6},
+///   pages={891--923},
+///   year={1998},
+///   publisher={ACM New York, NY, USA}
+/// }
+/// ```
+pub struct ApproxKdTreeMap<K, V, M: Metric<K>> {
+    tree: KdTreeMap<K, V, M>,
+    epsilon: M::Distance,
+}
+
+impl<K, V, M: Metric<K>> ApproxKdTreeMap<K, V, M> {
+    /// Construct a new `ApproxKdTreeMap` using the provided metric and approximation factor.
+    pub const fn new(metric: M, epsilon: M::Distance) -> Self {
+        Self {
+            tree: KdTreeMap::new(metric),
+            epsilon,
+        }
+    }
+
+    /// Construct an `ApproxKdTreeMap` which searches `tree` with approximation factor `epsilon`.
+    pub const fn from_tree(tree: KdTreeMap<K, V, M>, epsilon: M::Distance) -> Self {
+        Self { tree, epsilon }
+    }
+
+    /// Get the approximation factor of this map.
+    pub const fn epsilon(&self) -> &M::Distance {
+        &self.epsilon
+    }
+
+    /// Set the approximation factor of this map.
+    pub fn set_epsilon(&mut self, epsilon: M::Distance) {
+        self.epsilon = epsilon;
+    }
+}
+
+impl<K, V, M> NearestNeighborsMap<K, V> for ApproxKdTreeMap<K, V, M>
+where
+    M: DistanceAabb<K>,
+    M::Distance: Copy + Mul<Output = M::Distance>,
+    K: KdKey,
+{
+    type Entry<'q>
+        = BorrowedEntry<'q, K, V>
+    where
+        Self: 'q,
+        K: 'q,
+        V: 'q;
+
+    fn insert(&mut self, key: K, value: V) {
+        self.tree.insert(key, value);
+    }
+
+    fn nearest<'q>(&'q self, key: &K) -> Option<Self::Entry<'q>> {
+        let epsilon = self.epsilon;
+        self.tree.nearest_inflated(key, &|d| d + d * epsilon)
+    }
+}
+
+impl<K, V, M> RangeNearestNeighborsMap<K, V> for ApproxKdTreeMap<K, V, M>
+where
+    M: DistanceAabb<K>,
+    M::Distance: Copy + Mul<Output = M::Distance>,
+    K: KdKey,
+{
+    type Distance = M::Distance;
+    type RangeNearest<'q>
+        = RangeNearest<'q, K, V, M>
+    where
+        K: 'q,
+        V: 'q,
+        M: 'q;
+
+    fn nearest_within_r<'q>(&'q self, key: &'q K, r: Self::Distance) -> Self::RangeNearest<'q> {
+        self.tree.nearest_within_r(key, r)
+    }
+}
+
+impl<K, V, M> KNearestNeighborsMap<K, V> for ApproxKdTreeMap<K, V, M>
+where
+    M: DistanceAabb<K>,
+    M::Distance: Copy + Mul<Output = M::Distance>,
+    K: KdKey,
+{
+    type KNearest<'q>
+        = KNearest<'q, K, V, M>
+    where
+        K: 'q,
+        V: 'q,
+        M: 'q;
+
+    fn k_nearest<'q>(&'q self, key: &K, k: usize) -> Self::KNearest<'q> {
+        self.tree.k_nearest(key, k)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use core::cell::Cell;
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    use super::*;
+    use crate::{
+        metric::Euclidean,
+        sample::{Rectangle, Sample},
+        space::Vector,
+    };
+
+    const DIM: usize = 14;
+
+    /// A Euclidean metric which counts how many distances it has computed.
+    struct Counting<'a>(&'a Cell<usize>);
+
+    impl Metric<Vector<DIM>> for Counting<'_> {
+        type Distance = f64;
+
+        fn distance(&self, c1: &Vector<DIM>, c2: &Vector<DIM>) -> Self::Distance {
+            self.0.set(self.0.get() + 1);
+            Euclidean.distance(c1, c2)
+        }
+    }
+
+    impl DistanceAabb<Vector<DIM>> for Counting<'_> {
+        fn distance_to_aabb(
+            &self,
+            c: &Vector<DIM>,
+            aabb_lo: &Vector<DIM>,
+            aabb_hi: &Vector<DIM>,
+        ) -> Self::Distance {
+            Euclidean.distance_to_aabb(c, aabb_lo, aabb_hi)
+        }
+    }
+
+    /// The number of queries made by each call to [`recall`].
+    const N_QUERIES: u32 = 200;
+
+    /// Measure the recall of `ApproxKdTreeMap` with approximation factor `epsilon` against exact
+    /// search, as the number of queries out of [`N_QUERIES`] whose exact nearest neighbor was
+    /// found, as well as the mean number of distance evaluations per query.
+    fn recall(epsilon: f64) -> (u32, f64) {
+        const N_POINTS: usize = 5_000;
+        let region = Rectangle {
+            min: Vector::new([-1.0; DIM]),
+            max: Vector::new([1.0; DIM]),
+        };
+        let mut rng = ChaCha20Rng::seed_from_u64(2707);
+        let points: Vec<Vector<DIM>> = (0..N_POINTS).map(|_| region.sample(&mut rng)).collect();
+
+        let n_evals = Cell::new(0);
+        let mut map = ApproxKdTreeMap::new(Counting(&n_evals), epsilon);
+        for (i, &pt) in points.iter().enumerate() {
+            map.insert(pt, i);
+        }
+
+        let mut n_hits = 0u32;
+        n_evals.set(0);
+        for _ in 0..N_QUERIES {
+            let q = region.sample(&mut rng);
+            let exact = points
+                .iter()
+                .map(|p| Euclidean.distance(p, &q))
+                .min_by(f64::total_cmp)
+                .unwrap();
+            let approx = Euclidean.distance(map.nearest(&q).unwrap().key, &q);
+            assert!(approx <= exact * (1.0 + epsilon) + 1e-9);
+            if approx <= exact {
+                n_hits += 1;
+            }
+        }
+        #[expect(clippy::cast_precision_loss)]
+        (n_hits, n_evals.get() as f64 / f64::from(N_QUERIES))
+    }
+
+    #[test]
+    fn recall_tradeoff() {
+        let (exact_hits, exact_evals) = recall(0.0);
+        assert_eq!(exact_hits, N_QUERIES);
+
+        // a small approximation loses little accuracy but skips most of the tree
+        let (approx_hits, approx_evals) = recall(0.5);
+        assert!(approx_hits >= N_QUERIES * 9 / 10);
+        assert!(approx_evals < exact_evals / 2.0);
+
+        let (rough_hits, rough_evals) = recall(2.0);
+        assert!(rough_hits >= N_QUERIES / 2);
+        assert!(rough_evals < approx_evals / 2.0);
+    }
+}
@@ -0,0 +1,329 @@
+use alloc::vec::Vec;
+use core::{array, cmp::Ordering, marker::PhantomData};
+#[cfg(feature = "simd")]
+use core::{
+    ops::{AddAssign, Mul, Sub},
+    simd::{Simd, SimdElement},
+};
+
+use num_traits::Float;
+
+use crate::{
+    metric::{Euclidean, SquaredEuclidean},
+    space::Vector,
+};
+
+use super::{BorrowedEntry, KNearestNeighborsMap, NearestNeighborsMap, RangeNearestNeighborsMap};
+
+/// The number of keys whose distances are computed at once.
+const LANES: usize = 8;
+
+#[derive(Clone, Debug)]
+/// A nearest-neighbor map which compares a query against every key it contains.
+///
+/// Keys are stored in a structure-of-arrays layout so that distances to several keys can be
+/// computed at a time, with SIMD instructions if the `simd` feature is enabled.
+/// For maps of up to a few hundred keys, this is often faster than a tree, since it has no
+/// branching or pointer-chasing.
+///
+/// `BruteForceMap` supports the [`SquaredEuclidean`] and [`Euclidean`] metrics.
+pub struct BruteForceMap<T, const N: usize, V, M> {
+    /// `coords[i]` contains the `i`th coordinate of every key.
+    /// Each array is padded with infinity to a multiple of `LANES` in length.
+    coords: [Vec<T>; N],
+    keys: Vec<Vector<N, T>>,
+    values: Vec<V>,
+    metric: PhantomData<M>,
+}
+
+/// A coordinate type for keys in a [`BruteForceMap`].
+///
+/// This is implemented for every [`Float`], or with the `simd` feature, for every [`Float`] which
+/// can be stored in a SIMD vector.
+pub trait BruteForceScalar: Float {
+    /// Compute the squared-Euclidean distance from `key` to each of the `LANES` keys starting at
+    /// index `lo` of the coordinate arrays `coords`.
+    fn sq_dists_from<const N: usize>(
+        coords: &[Vec<Self>; N],
+        lo: usize,
+        key: &[Self; N],
+    ) -> [Self; LANES];
+}
+
+#[cfg(not(feature = "simd"))]
+impl<T: Float> BruteForceScalar for T {
+    fn sq_dists_from<const N: usize>(coords: &[Vec<T>; N], lo: usize, key: &[T; N]) -> [T; LANES] {
+        let mut total = [T::zero(); LANES];
+        for (axis, &x) in coords.iter().zip(key) {
+            for (t, &a) in total.iter_mut().zip(&axis[lo..lo + LANES]) {
+                *t = *t + (a - x) * (a - x);
+            }
+        }
+        total
+    }
+}
+
+#[cfg(feature = "simd")]
+impl<T> BruteForceScalar for T
+where
+    T: Float + SimdElement,
+    Simd<T, LANES>: AddAssign + Sub<Output = Simd<T, LANES>> + Mul<Output = Simd<T, LANES>>,
+{
+    fn sq_dists_from<const N: usize>(coords: &[Vec<T>; N], lo: usize, key: &[T; N]) -> [T; LANES] {
+        let mut total = Simd::splat(T::zero());
+        for (axis, &x) in coords.iter().zip(key) {
+            let diff = Simd::from_slice(&axis[lo..lo + LANES]) - Simd::splat(x);
+            total += diff * diff;
+        }
+        total.to_array()
+    }
+}
+
+/// An iterator over entries found by a query on a [`BruteForceMap`].
+pub struct BruteForceNearest<'a, K, V>(Vec<BorrowedEntry<'a, K, V>>);
+
+impl<'a, K, V> Iterator for BruteForceNearest<'a, K, V> {
+    type Item = BorrowedEntry<'a, K, V>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len(), Some(self.0.len()))
+    }
+}
+
+impl<T, const N: usize, V, M> BruteForceMap<T, N, V, M> {
+    /// Construct a new, empty `BruteForceMap`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            coords: array::from_fn(|_| Vec::new()),
+            keys: Vec::new(),
+            values: Vec::new(),
+            metric: PhantomData,
+        }
+    }
+
+    /// Get the number of entries in this map.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Determine whether this map is empty.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+impl<T, const N: usize, V, M> BruteForceMap<T, N, V, M>
+where
+    T: BruteForceScalar,
+{
+    /// Add a key to the coordinate arrays, padding them if necessary.
+    fn push_coords(&mut self, key: &Vector<N, T>) {
+        let id = self.keys.len();
+        for (axis, &x) in self.coords.iter_mut().zip(key.iter()) {
+            if id.is_multiple_of(LANES) {
+                axis.extend([T::infinity(); LANES]);
+            }
+            axis[id] = x;
+        }
+    }
+
+    /// Compute the squared-Euclidean distance from `key` to every key in the map, `LANES` keys at
+    /// a time.
+    /// Lanes past the end of the map have an infinite distance.
+    fn sq_dists<'a>(&'a self, key: &'a Vector<N, T>) -> impl Iterator<Item = [T; LANES]> + 'a {
+        (0..self.keys.len().div_ceil(LANES))
+            .map(move |chunk| T::sq_dists_from(&self.coords, chunk * LANES, &key.0))
+    }
+
+    /// Get the ID of the nearest key to `key`.
+    fn nearest_id(&self, key: &Vector<N, T>) -> Option<usize> {
+        self.sq_dists(key)
+            .flatten()
+            .take(self.keys.len())
+            .enumerate()
+            .reduce(|best, (id, d)| if d < best.1 { (id, d) } else { best })
+            .map(|(id, _)| id)
+    }
+
+    /// Get all entries whose squared distance to `key` is at most `r_sq`.
+    fn within_sq(&self, key: &Vector<N, T>, r_sq: T) -> Vec<BorrowedEntry<'_, Vector<N, T>, V>> {
+        self.sq_dists(key)
+            .flatten()
+            .take(self.keys.len())
+            .enumerate()
+            .filter(|&(_, d)| d <= r_sq)
+            .map(|(id, _)| self.entry(id))
+            .collect()
+    }
+
+    /// Get the `k` nearest entries to `key`, sorted from farthest to nearest.
+    fn k_nearest_rev(
+        &self,
+        key: &Vector<N, T>,
+        k: usize,
+    ) -> Vec<BorrowedEntry<'_, Vector<N, T>, V>> {
+        let mut candidates: Vec<(T, usize)> = self
+            .sq_dists(key)
+            .flatten()
+            .zip(0..self.keys.len())
+            .collect();
+        let cmp = |a: &(T, usize), b: &(T, usize)| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal);
+        if k < candidates.len() {
+            candidates.select_nth_unstable_by(k, cmp);
+            candidates.truncate(k);
+        }
+        candidates.sort_unstable_by(|a, b| cmp(b, a));
+        candidates
+            .into_iter()
+            .map(|(_, id)| self.entry(id))
+            .collect()
+    }
+
+    fn entry(&self, id: usize) -> BorrowedEntry<'_, Vector<N, T>, V> {
+        BorrowedEntry {
+            key: &self.keys[id],
+            value: &self.values[id],
+        }
+    }
+}
+
+/// Implement the nearest-neighbor traits for a [`BruteForceMap`] using metric `$m`, where `$r_sq`
+/// converts a range radius to a squared-Euclidean one.
+macro_rules! brute_impl {
+    ($m: ty, $r_sq: expr) => {
+        impl<T, const N: usize, V> NearestNeighborsMap<Vector<N, T>, V>
+            for BruteForceMap<T, N, V, $m>
+        where
+            T: BruteForceScalar,
+        {
+            type Entry<'q>
+                = BorrowedEntry<'q, Vector<N, T>, V>
+            where
+                Self: 'q,
+                V: 'q;
+
+            fn insert(&mut self, key: Vector<N, T>, value: V) {
+                self.push_coords(&key);
+                self.keys.push(key);
+                self.values.push(value);
+            }
+
+            fn nearest<'q>(&'q self, key: &Vector<N, T>) -> Option<Self::Entry<'q>> {
+                self.nearest_id(key).map(|id| self.entry(id))
+            }
+        }
+
+        impl<T, const N: usize, V> RangeNearestNeighborsMap<Vector<N, T>, V>
+            for BruteForceMap<T, N, V, $m>
+        where
+            T: BruteForceScalar,
+        {
+            type Distance = T;
+            type RangeNearest<'q>
+                = BruteForceNearest<'q, Vector<N, T>, V>
+            where
+                Self: 'q,
+                V: 'q;
+
+            fn nearest_within_r<'q>(
+                &'q self,
+                key: &'q Vector<N, T>,
+                r: Self::Distance,
+            ) -> Self::RangeNearest<'q> {
+                let r_sq: fn(T) -> T = $r_sq;
+                BruteForceNearest(self.within_sq(key, r_sq(r)))
+            }
+        }
+
+        impl<T, const N: usize, V> KNearestNeighborsMap<Vector<N, T>, V>
+            for BruteForceMap<T, N, V, $m>
+        where
+            T: BruteForceScalar,
+        {
+            type KNearest<'q>
+                = BruteForceNearest<'q, Vector<N, T>, V>
+            where
+                Self: 'q,
+                V: 'q;
+
+            fn k_nearest<'q>(&'q self, key: &Vector<N, T>, k: usize) -> Self::KNearest<'q> {
+                BruteForceNearest(self.k_nearest_rev(key, k))
+            }
+        }
+    };
+}
+
+brute_impl!(SquaredEuclidean, |r| r);
+brute_impl!(Euclidean, |r| r * r);
+
+impl<T, const N: usize, V, M> Default for BruteForceMap<T, N, V, M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    use super::*;
+    use crate::{
+        metric::Metric,
+        sample::{Rectangle, Sample},
+    };
+
+    #[test]
+    fn get_empty() {
+        let map = BruteForceMap::<f32, 3, (), SquaredEuclidean>::new();
+        assert_eq!(map.nearest(&Vector::new([0.0; 3])), None);
+        assert_eq!(map.nearest_within_r(&Vector::new([0.0; 3]), 1.0).count(), 0);
+        assert_eq!(map.k_nearest(&Vector::new([0.0; 3]), 3).count(), 0);
+    }
+
+    #[test]
+    fn randomized_3d() {
+        let region = Rectangle {
+            min: Vector::new([-10.0; 3]),
+            max: Vector::new([10.0; 3]),
+        };
+
+        let mut rng = ChaCha20Rng::seed_from_u64(2707);
+
+        let mut points: Vec<Vector<3>> = Vec::new();
+        let mut map = BruteForceMap::<_, 3, _, Euclidean>::new();
+        for i in 0..300 {
+            let pt = region.sample(&mut rng);
+            points.push(pt);
+            map.insert(pt, i);
+            assert_eq!(map.len(), points.len());
+            let q: Vector<3> = region.sample(&mut rng);
+
+            let mut bf_dists: Vec<f64> = points.iter().map(|p| Euclidean.distance(p, &q)).collect();
+            bf_dists.sort_unstable_by(f64::total_cmp);
+
+            let nearest = map.nearest(&q).unwrap();
+            assert_eq!(points[*nearest.value], *nearest.key);
+            assert!((Euclidean.distance(nearest.key, &q) - bf_dists[0]).abs() < 1e-9);
+
+            let n_within = bf_dists.iter().filter(|&&d| d <= 5.0).count();
+            assert_eq!(map.nearest_within_r(&q, 5.0).count(), n_within);
+
+            let k = i % 11;
+            let map_dists: Vec<f64> = map
+                .k_nearest(&q, k)
+                .map(|e| Euclidean.distance(e.key, &q))
+                .collect();
+            assert_eq!(map_dists.len(), k.min(points.len()));
+            for (a, b) in map_dists.iter().zip(&bf_dists) {
+                assert!((a - b).abs() < 1e-9);
+            }
+        }
+    }
+}
@@ -0,0 +1,508 @@
+use alloc::vec::Vec;
+use core::{cmp::Ordering, marker::PhantomData, mem, ops::Add};
+
+use num_traits::Zero;
+
+use crate::metric::Metric;
+
+use super::{BorrowedEntry, NearestNeighborsMap, RangeNearestNeighborsMap};
+
+#[derive(Clone, Debug)]
+/// A nearest-neighbor map backed by a Geometric Near-neighbor Access Tree (GNAT).
+///
+/// Unlike [`KdTreeMap`](super::KdTreeMap), a GNAT only needs to be able to measure the distance
+/// between two keys, so it can index any space with a [`Metric`], including ones without a
+/// meaningful notion of axes or bounding boxes.
+/// However, it relies on the triangle inequality to prune its search, so `M` must be a true
+/// metric: [`SquaredEuclidean`](crate::metric::SquaredEuclidean) and asymmetric distances (such
+/// as [`Dubins`](crate::space::Dubins)) may cause it to miss neighbors.
+///
+/// # Citation
+///
+/// ```bibtex
+/// @inproceedings{brin1995near,
+///   title={Near Neighbor Search in Large Metric Spaces},
+///   author={Brin, Sergey},
+///   booktitle={Proceedings of the 21st International Conference on Very Large Data Bases},
+///   pages={574--584},
+///   year={1995}
+/// }
+/// ```
+pub struct GnatMap<K, V, M: Metric<K>> {
+    keys: Vec<K>,
+    values: Vec<V>,
+    root: Node<M::Distance>,
+    metric: M,
+    /// The number of children created when a leaf is split.
+    degree: usize,
+    /// The greatest number of points a leaf may hold before it is split.
+    leaf_size: usize,
+}
+
+#[derive(Clone, Debug)]
+/// A node in a GNAT.
+/// A node is a leaf if and only if it has no children.
+struct Node<D> {
+    /// The IDs of the points stored directly in this node.
+    /// Always empty for non-leaf nodes.
+    data: Vec<usize>,
+    /// The children of this node, each of which is identified by a pivot point.
+    children: Vec<Child<D>>,
+    /// The greatest number of points this leaf may hold before it is split.
+    /// This is raised above the tree's leaf size when a split fails because all of the leaf's
+    /// points coincide, so that the split is not retried on every insertion.
+    capacity: usize,
+}
+
+#[derive(Clone, Debug)]
+struct Child<D> {
+    /// The ID of the pivot point of this child.
+    pivot: usize,
+    /// `ranges[j]` contains the least and greatest distance from this child's pivot to any point
+    /// in the subtree of the `j`th sibling, including that sibling's pivot.
+    ranges: Vec<(D, D)>,
+    node: Node<D>,
+}
+
+impl<D> Node<D> {
+    const fn new(capacity: usize) -> Self {
+        Self {
+            data: Vec::new(),
+            children: Vec::new(),
+            capacity,
+        }
+    }
+}
+
+impl<K, V, M: Metric<K>> GnatMap<K, V, M> {
+    /// Construct a new `GnatMap` using the provided metric, with a default degree of 8 and leaves
+    /// of up to 50 points.
+    pub const fn new(metric: M) -> Self {
+        Self::with_degree(metric, 8, 50)
+    }
+
+    /// Construct a new `GnatMap` using the provided metric, whose nodes have `degree` children
+    /// and whose leaves contain at most `leaf_size` points.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `degree` is less than 2 or if `leaf_size` is less than
+    /// `degree`.
+    pub const fn with_degree(metric: M, degree: usize, leaf_size: usize) -> Self {
+        assert!(degree >= 2, "GNAT degree must be at least 2");
+        assert!(
+            leaf_size >= degree,
+            "GNAT leaves must be able to hold a full split"
+        );
+        Self {
+            keys: Vec::new(),
+            values: Vec::new(),
+            root: Node::new(leaf_size),
+            metric,
+            degree,
+            leaf_size,
+        }
+    }
+}
+
+/// Widen `range` so that it contains `d`.
+fn extend<D: PartialOrd + Copy>(range: &mut (D, D), d: D) {
+    if d < range.0 {
+        range.0 = d;
+    }
+    if range.1 < d {
+        range.1 = d;
+    }
+}
+
+/// Determine whether a subtree whose distances from a pivot lie within `range` may contain a point
+/// within `r` of a query which is `d` away from that pivot.
+fn may_contain<D: PartialOrd + Copy + Add<Output = D>>(range: &(D, D), d: D, r: Option<D>) -> bool {
+    r.is_none_or(|r| range.1 + r >= d && range.0 <= d + r)
+}
+
+impl<K, V, M> GnatMap<K, V, M>
+where
+    M: Metric<K>,
+    M::Distance: Copy,
+{
+    /// Insert the point with ID `id` into the subtree rooted at `node`.
+    fn insert_help(&self, node: &mut Node<M::Distance>, id: usize) {
+        if node.children.is_empty() {
+            node.data.push(id);
+            if node.data.len() > node.capacity {
+                self.split(node);
+            }
+            return;
+        }
+
+        let key = &self.keys[id];
+        let dists: Vec<M::Distance> = node
+            .children
+            .iter()
+            .map(|c| self.metric.distance(&self.keys[c.pivot], key))
+            .collect();
+        let nearest = argmin(&dists);
+        for (child, &d) in node.children.iter_mut().zip(&dists) {
+            extend(&mut child.ranges[nearest], d);
+        }
+        self.insert_help(&mut node.children[nearest].node, id);
+    }
+
+    /// Split a leaf into children, using its points which are farthest apart as pivots.
+    fn split(&self, node: &mut Node<M::Distance>) {
+        let data = &node.data;
+        // greedily pick each new pivot to be as far as possible from all previous ones
+        let mut pivots = Vec::with_capacity(self.degree);
+        pivots.push(0);
+        let mut min_dists: Vec<M::Distance> = data
+            .iter()
+            .map(|&id| self.metric.distance(&self.keys[data[0]], &self.keys[id]))
+            .collect();
+        while pivots.len() < self.degree {
+            let farthest = argmax(&min_dists);
+            if min_dists[farthest].is_zero() {
+                // all remaining points coincide with a pivot
+                break;
+            }
+            pivots.push(farthest);
+            for (d, &id) in min_dists.iter_mut().zip(data) {
+                let d_new = self
+                    .metric
+                    .distance(&self.keys[data[farthest]], &self.keys[id]);
+                if d_new < *d {
+                    *d = d_new;
+                }
+            }
+        }
+        if pivots.len() < 2 {
+            // splitting would not separate any points, so wait until the leaf has doubled in size
+            // before trying again
+            node.capacity = 2 * data.len();
+            return;
+        }
+
+        let mut children: Vec<Child<M::Distance>> = pivots
+            .iter()
+            .map(|&i| {
+                let pivot = data[i];
+                Child {
+                    pivot,
+                    ranges: pivots
+                        .iter()
+                        .map(|&j| {
+                            let d = self.metric.distance(&self.keys[pivot], &self.keys[data[j]]);
+                            (d, d)
+                        })
+                        .collect(),
+                    node: Node::new(self.leaf_size),
+                }
+            })
+            .collect();
+
+        for (i, &id) in data.iter().enumerate() {
+            if pivots.contains(&i) {
+                continue;
+            }
+            let dists: Vec<M::Distance> = children
+                .iter()
+                .map(|c| self.metric.distance(&self.keys[c.pivot], &self.keys[id]))
+                .collect();
+            let nearest = argmin(&dists);
+            for (child, &d) in children.iter_mut().zip(&dists) {
+                extend(&mut child.ranges[nearest], d);
+            }
+            children[nearest].node.data.push(id);
+        }
+
+        node.data = Vec::new();
+        for child in &mut children {
+            if child.node.data.len() > child.node.capacity {
+                self.split(&mut child.node);
+            }
+        }
+        node.children = children;
+    }
+
+    /// Search the subtree rooted at `node` for the nearest point to `key`.
+    /// `best` contains the distance to and ID of the best point found so far.
+    fn nearest_help(
+        &self,
+        node: &Node<M::Distance>,
+        key: &K,
+        best: &mut Option<(M::Distance, usize)>,
+    ) {
+        let consider = |d: M::Distance, id: usize, best: &mut Option<(M::Distance, usize)>| {
+            if best.is_none_or(|(r, _)| d < r) {
+                *best = Some((d, id));
+            }
+        };
+
+        for &id in &node.data {
+            consider(self.metric.distance(&self.keys[id], key), id, best);
+        }
+        if node.children.is_empty() {
+            return;
+        }
+
+        let dists: Vec<M::Distance> = node
+            .children
+            .iter()
+            .map(|c| self.metric.distance(&self.keys[c.pivot], key))
+            .collect();
+        for (c, &d) in node.children.iter().zip(&dists) {
+            consider(d, c.pivot, best);
+        }
+
+        // visit children nearest-first, so that the search radius shrinks as fast as possible
+        let mut order: Vec<usize> = (0..node.children.len()).collect();
+        order.sort_by(|&i, &j| dists[i].partial_cmp(&dists[j]).unwrap_or(Ordering::Equal));
+        for j in order {
+            let r = best.map(|(r, _)| r);
+            if node
+                .children
+                .iter()
+                .zip(&dists)
+                .all(|(c, &d)| may_contain(&c.ranges[j], d, r))
+            {
+                self.nearest_help(&node.children[j].node, key, best);
+            }
+        }
+    }
+
+    /// Collect every point in the subtree rooted at `node` within `r` of `key` into `result`.
+    fn nearest_r_help<'q>(
+        &'q self,
+        node: &Node<M::Distance>,
+        key: &K,
+        r: M::Distance,
+        result: &mut Vec<BorrowedEntry<'q, K, V>>,
+    ) {
+        let mut consider = |id: usize, d: M::Distance| {
+            if d <= r {
+                result.push(BorrowedEntry {
+                    key: &self.keys[id],
+                    value: &self.values[id],
+                });
+            }
+        };
+
+        for &id in &node.data {
+            consider(id, self.metric.distance(&self.keys[id], key));
+        }
+        if node.children.is_empty() {
+            return;
+        }
+
+        let dists: Vec<M::Distance> = node
+            .children
+            .iter()
+            .map(|c| self.metric.distance(&self.keys[c.pivot], key))
+            .collect();
+        for (c, &d) in node.children.iter().zip(&dists) {
+            consider(c.pivot, d);
+        }
+
+        for j in 0..node.children.len() {
+            if node
+                .children
+                .iter()
+                .zip(&dists)
+                .all(|(c, &d)| may_contain(&c.ranges[j], d, Some(r)))
+            {
+                self.nearest_r_help(&node.children[j].node, key, r, result);
+            }
+        }
+    }
+}
+
+/// Get the index of the least element of `xs`.
+fn argmin<D: PartialOrd>(xs: &[D]) -> usize {
+    (1..xs.len()).fold(0, |best, i| if xs[i] < xs[best] { i } else { best })
+}
+
+/// Get the index of the greatest element of `xs`.
+fn argmax<D: PartialOrd>(xs: &[D]) -> usize {
+    (1..xs.len()).fold(0, |best, i| if xs[best] < xs[i] { i } else { best })
+}
+
+impl<K, V, M> NearestNeighborsMap<K, V> for GnatMap<K, V, M>
+where
+    M: Metric<K>,
+    M::Distance: Copy,
+{
+    type Entry<'q>
+        = BorrowedEntry<'q, K, V>
+    where
+        Self: 'q,
+        K: 'q,
+        V: 'q;
+
+    fn insert(&mut self, key: K, value: V) {
+        let id = self.keys.len();
+        self.keys.push(key);
+        self.values.push(value);
+        let mut root = mem::replace(&mut self.root, Node::new(0));
+        self.insert_help(&mut root, id);
+        self.root = root;
+    }
+
+    fn nearest<'q>(&'q self, key: &K) -> Option<Self::Entry<'q>> {
+        let mut best = None;
+        self.nearest_help(&self.root, key, &mut best);
+        best.map(|(_, id)| BorrowedEntry {
+            key: &self.keys[id],
+            value: &self.values[id],
+        })
+    }
+}
+
+/// An iterator over all points with a given radius of a query point in a [`GnatMap`].
+pub struct GnatRangeNearest<'a, K, V, M: Metric<K>>(
+    Vec<BorrowedEntry<'a, K, V>>,
+    PhantomData<&'a GnatMap<K, V, M>>,
+);
+
+impl<'a, K, V, M: Metric<K>> Iterator for GnatRangeNearest<'a, K, V, M> {
+    type Item = BorrowedEntry<'a, K, V>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len(), Some(self.0.len()))
+    }
+}
+
+impl<K, V, M> RangeNearestNeighborsMap<K, V> for GnatMap<K, V, M>
+where
+    M: Metric<K>,
+    M::Distance: Copy,
+{
+    type Distance = M::Distance;
+    type RangeNearest<'q>
+        = GnatRangeNearest<'q, K, V, M>
+    where
+        K: 'q,
+        V: 'q,
+        M: 'q;
+
+    fn nearest_within_r<'q>(&'q self, key: &'q K, r: Self::Distance) -> Self::RangeNearest<'q> {
+        let mut result = Vec::new();
+        self.nearest_r_help(&self.root, key, r, &mut result);
+        GnatRangeNearest(result, PhantomData)
+    }
+}
+
+impl<K, V, M> Default for GnatMap<K, V, M>
+where
+    M: Metric<K> + Default,
+{
+    fn default() -> Self {
+        Self::new(M::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    use super::*;
+    use crate::{
+        metric::Euclidean,
+        sample::{Rectangle, Sample},
+        space::{Pose2d, Vector, WeightedPoseDistance},
+    };
+
+    #[test]
+    fn get_empty() {
+        let gnat = GnatMap::<Vector<2>, (), _>::new(Euclidean);
+        assert_eq!(gnat.nearest(&Vector::new([0.0; 2])), None);
+        assert_eq!(
+            gnat.nearest_within_r(&Vector::new([0.0; 2]), 1.0).count(),
+            0
+        );
+    }
+
+    #[test]
+    fn duplicates() {
+        let mut gnat = GnatMap::with_degree(Euclidean, 2, 2);
+        for i in 0..100 {
+            gnat.insert(Vector::new([1.0, 1.0]), i);
+        }
+        assert_eq!(
+            gnat.nearest_within_r(&Vector::new([0.0; 2]), 1.5).count(),
+            100
+        );
+    }
+
+    /// A Euclidean metric which counts how many times it has been evaluated.
+    struct Counting(Cell<usize>);
+
+    impl Metric<Vector<2>> for Counting {
+        type Distance = f64;
+        fn distance(&self, c1: &Vector<2>, c2: &Vector<2>) -> f64 {
+            self.0.set(self.0.get() + 1);
+            Euclidean.distance(c1, c2)
+        }
+    }
+
+    #[test]
+    fn duplicates_not_resplit() {
+        const N: usize = 10_000;
+        let mut gnat = GnatMap::with_degree(Counting(Cell::new(0)), 2, 2);
+        for i in 0..N {
+            gnat.insert(Vector::new([1.0, 1.0]), i);
+        }
+        // retrying a failed split on every insertion would take quadratically many evaluations
+        assert!(gnat.metric.0.get() < 10 * N);
+        assert_eq!(
+            gnat.nearest_within_r(&Vector::new([0.0; 2]), 1.5).count(),
+            N
+        );
+    }
+
+    #[test]
+    fn randomized_pose2d() {
+        let region = Rectangle {
+            min: Vector::new([-5.0; 2]),
+            max: Vector::new([5.0; 2]),
+        };
+        let metric = WeightedPoseDistance {
+            position_metric: Euclidean,
+            position_weight: 1.0,
+            angle_metric: Euclidean,
+            angle_weight: 0.5,
+        };
+
+        let mut rng = ChaCha20Rng::seed_from_u64(2707);
+
+        let mut points: Vec<Pose2d<f64>> = Vec::new();
+        let mut gnat = GnatMap::with_degree(metric, 4, 8);
+        for i in 0..1000 {
+            let pt = region.sample(&mut rng);
+            points.push(pt);
+            gnat.insert(pt, i);
+            let q: Pose2d<f64> = region.sample(&mut rng);
+
+            let bf_nearest = points
+                .iter()
+                .map(|p| metric.distance(p, &q))
+                .min_by(f64::total_cmp);
+            let gnat_nearest = gnat.nearest(&q).map(|e| metric.distance(e.key, &q));
+            assert_eq!(bf_nearest, gnat_nearest);
+
+            let mut bf_range: Vec<usize> = (0..points.len())
+                .filter(|&j| metric.distance(&points[j], &q) <= 1.5)
+                .collect();
+            let mut gnat_range: Vec<usize> =
+                gnat.nearest_within_r(&q, 1.5).map(|e| *e.value).collect();
+            bf_range.sort_unstable();
+            gnat_range.sort_unstable();
+            assert_eq!(bf_range, gnat_range);
+        }
+    }
+}
@@ -1,10 +1,13 @@
-use alloc::vec::Vec;
-use core::{cmp::Ordering, fmt::Debug, marker::PhantomData, num::NonZeroUsize};
+use alloc::{collections::BinaryHeap, vec::Vec};
+use core::{cmp::Ordering, fmt::Debug, marker::PhantomData, mem, num::NonZeroUsize};
 use num_traits::Zero;
 
 use crate::{
     metric::Metric,
-    nn::{NearestNeighborsMap, RangeNearestNeighborsMap},
+    nn::{
+        KNearestNeighborsMap, NearestNeighborsMap, RangeNearestNeighborsMap,
+        RemoveNearestNeighborsMap,
+    },
 };
 
 use super::BorrowedEntry;
@@ -12,13 +15,19 @@ use super::BorrowedEntry;
 #[derive(Clone, Debug, PartialEq, Eq)]
 /// A nearest-neighbor map backed by a _k_-d tree.
 ///
-/// This implementation is not particularly efficient, but it has support for spaces of weird
-/// topologies (such as [`crate::space::Angle`]).
+/// This implementation has support for spaces of weird topologies (such as
+/// [`crate::space::Angle`]).
+/// Trees may be built all at once with [`KdTreeMap::build`], and are rebalanced automatically
+/// whenever insertions make them too deep, as in a scapegoat tree.
+/// Built and rebalanced subtrees are laid out in depth-first order, so that each node's lesser
+/// child directly follows it in memory, while inserted nodes are linked to from their parents.
 pub struct KdTreeMap<K, V, M> {
-    /// `nodes[0]` is the root, but no guarantees on the others.
+    /// `nodes[0]` is the root, and every node's ID is less than its children's IDs.
     /// if this is empty, the tree is empty.
     nodes: Vec<Node<K>>,
     values: Vec<V>,
+    /// The number of nodes which have been removed but not yet cleaned up.
+    n_removed: usize,
     metric: M,
 }
 
@@ -49,6 +58,9 @@ pub trait DistanceAabb<C>: Metric<C> {
 struct Node<K> {
     key: K,
     children: [Option<NonZeroUsize>; 2],
+    /// Whether this node has been removed.
+    /// Removed nodes still partition space for their children, but are never returned by queries.
+    removed: bool,
 }
 
 impl<K, V, M> KdTreeMap<K, V, M> {
@@ -57,11 +69,174 @@ impl<K, V, M> KdTreeMap<K, V, M> {
         Self {
             nodes: Vec::new(),
             values: Vec::new(),
+            n_removed: 0,
             metric,
         }
     }
 }
 
+impl<K, V, M> KdTreeMap<K, V, M>
+where
+    K: KdKey,
+{
+    /// Construct a balanced `KdTreeMap` containing every key-value pair in `entries`, using the
+    /// provided metric.
+    ///
+    /// Each node splits its subtree at the median along its axis, and nodes are laid out in
+    /// depth-first order, so that every node's lesser child directly follows it in memory.
+    pub fn build(metric: M, entries: impl IntoIterator<Item = (K, V)>) -> Self {
+        let mut map = Self::new(metric);
+        for (key, value) in entries {
+            map.nodes.push(Node {
+                key,
+                children: [None; 2],
+                removed: false,
+            });
+            map.values.push(value);
+        }
+        map.rebuild_subtree(&(0..map.nodes.len()).collect::<Vec<_>>(), 0);
+        map
+    }
+
+    /// Rebalance the tree after inserting the node `new_id`, whose depth was too great.
+    ///
+    /// This finds the lowest ancestor of the new node whose subtree is unbalanced and rebuilds
+    /// that subtree, as in a scapegoat tree.
+    fn rebalance_from(&mut self, new_id: usize) {
+        // collect the path from the root to the new node
+        let key = &self.nodes[new_id].key;
+        let mut path = Vec::new();
+        let mut node_id = 0;
+        let mut k = 0;
+        while node_id != new_id {
+            path.push(node_id);
+            let side: usize = self.nodes[node_id].key.compare(key, k).is_le().into();
+            node_id = self.nodes[node_id].children[side]
+                .expect("new node must be reachable from the root")
+                .get();
+            k = (k + 1) % K::dimension();
+        }
+
+        let mut child_id = new_id;
+        let mut child_size = 1;
+        for (depth, &anc_id) in path.iter().enumerate().rev() {
+            let anc = &self.nodes[anc_id];
+            let sibling = anc
+                .children
+                .into_iter()
+                .flatten()
+                .find(|c| c.get() != child_id);
+            let size = 1 + child_size + sibling.map_or(0, |c| self.subtree(c.get()).len());
+            if 10 * child_size > 7 * size {
+                let mut slots = self.subtree(anc_id);
+                slots.sort_unstable();
+                self.rebuild_subtree(&slots, depth % K::dimension());
+                return;
+            }
+            child_id = anc_id;
+            child_size = size;
+        }
+    }
+
+    /// Get the IDs of every node in the subtree rooted at `root`.
+    fn subtree(&self, root: usize) -> Vec<usize> {
+        let mut ids = vec![root];
+        let mut i = 0;
+        while let Some(&id) = ids.get(i) {
+            ids.extend(
+                self.nodes[id]
+                    .children
+                    .into_iter()
+                    .flatten()
+                    .map(NonZeroUsize::get),
+            );
+            i += 1;
+        }
+        ids
+    }
+
+    /// Rebuild the subtree occupying the node IDs `slots` into a balanced tree whose root splits
+    /// along axis `k`.
+    ///
+    /// `slots` must be sorted, and its first element must be the root of the subtree.
+    /// The rebuilt subtree occupies the same IDs, in depth-first order.
+    fn rebuild_subtree(&mut self, slots: &[usize], k: usize) {
+        let mut order = slots.to_vec();
+        let mut children = vec![[None; 2]; slots.len()];
+        self.balance(&mut order, k, &mut children, 0);
+
+        // `order[i]` is the node which must move to `slots[i]`.
+        // Find the index of each node's destination, then apply that permutation by swapping.
+        let mut dest = vec![0; slots.len()];
+        for (i, id) in order.iter().enumerate() {
+            dest[slots.binary_search(id).unwrap()] = i;
+        }
+        for i in 0..slots.len() {
+            while dest[i] != i {
+                let j = dest[i];
+                self.nodes.swap(slots[i], slots[j]);
+                self.values.swap(slots[i], slots[j]);
+                dest.swap(i, j);
+            }
+        }
+
+        for (&slot, c) in slots.iter().zip(children) {
+            self.nodes[slot].children = c.map(|c| c.map(|c| NonZeroUsize::new(slots[c]).unwrap()));
+        }
+    }
+
+    /// Reorder `ids` into the depth-first order of a balanced tree whose root splits along axis
+    /// `k`, and record the position of the children of each node in `children`.
+    /// `offset` is the position of `ids[0]` in the whole ordering.
+    fn balance(
+        &self,
+        ids: &mut [usize],
+        k: usize,
+        children: &mut [[Option<usize>; 2]],
+        offset: usize,
+    ) {
+        if ids.is_empty() {
+            return;
+        }
+        let cmp = |a: &usize, b: &usize| self.nodes[*a].key.compare(&self.nodes[*b].key, k);
+        let mid = ids.len() / 2;
+        ids.select_nth_unstable_by(mid, cmp);
+        // move the median to the front, followed by the lesser half
+        // keys equal to the median along `k` may end up on either side
+        ids[..=mid].rotate_right(1);
+
+        children[0] = [
+            (mid > 0).then_some(offset + 1),
+            (mid + 1 < ids.len()).then_some(offset + 1 + mid),
+        ];
+        let new_k = (k + 1) % K::dimension();
+        let (lo, hi) = ids[1..].split_at_mut(mid);
+        let (lo_children, hi_children) = children[1..].split_at_mut(mid);
+        self.balance(lo, new_k, lo_children, offset + 1);
+        self.balance(hi, new_k, hi_children, offset + 1 + mid);
+    }
+}
+
+/// Get the greatest depth a tree with `n` nodes may reach before it must be rebalanced.
+///
+/// A subtree which is deeper than `log_{1/α}(n)` must contain a node with one child holding more
+/// than a fraction `α` of its descendants, which can then be rebuilt.
+/// We use `α = 0.7`, which keeps queries fast without rebuilding subtrees too often.
+const fn max_depth(n: usize) -> usize {
+    // log2(1 / 0.7) is a little over 0.5, so this slightly overestimates the bound
+    (n.ilog2() as usize + 1) * 2
+}
+
+impl<K, V, M> FromIterator<(K, V)> for KdTreeMap<K, V, M>
+where
+    K: KdKey,
+    M: Default,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self::build(M::default(), iter)
+    }
+}
+
 impl<K, V, M> NearestNeighborsMap<K, V> for KdTreeMap<K, V, M>
 where
     M: DistanceAabb<K>,
@@ -81,6 +256,7 @@ where
             self.nodes.push(Node {
                 key,
                 children: [None; 2],
+                removed: false,
             });
             self.values.push(value);
             return;
@@ -88,6 +264,8 @@ where
 
         let mut parent = &mut self.nodes[0];
         let mut k = 0;
+        // the depth of the new node, counting the root as depth 1
+        let mut depth = 2;
         let mut side: usize;
         loop {
             side = parent.key.compare(&key, k).is_le().into();
@@ -96,46 +274,85 @@ where
             };
             parent = &mut self.nodes[node_id.get()];
             k = (k + 1) % K::dimension();
+            depth += 1;
         }
         debug_assert!(parent.children[side].is_none());
         parent.children[side] = Some(NonZeroUsize::new(new_node_id).unwrap());
         self.nodes.push(Node {
             key,
             children: [None, None],
+            removed: false,
         });
         self.values.push(value);
+
+        if depth > max_depth(self.nodes.len()) {
+            self.rebalance_from(new_node_id);
+        }
     }
 
     fn nearest<'q>(&'q self, key: &K) -> Option<Self::Entry<'q>> {
-        let root = self.nodes.first()?;
-        let mut radius = self.metric.distance(&root.key, key);
-        if radius.is_zero() {
-            return Some(BorrowedEntry {
-                key: &root.key,
-                value: &self.values[0],
-            });
-        }
-        let best_node = self
-            .nearest_help(0, key, K::lower_bound(), K::upper_bound(), &mut radius, 0)
-            .map_or(0, NonZeroUsize::get);
-        Some(BorrowedEntry {
-            key: &self.nodes[best_node].key,
-            value: &self.values[best_node],
-        })
+        self.nearest_inflated(key, &|d| d)
     }
 }
 
-// TODO make this a resuming iterator
 /// An iterator over all points with a given radius of a query point in a [`KdTreeMap`].
-pub struct RangeNearest<'a, K, V, M>(
-    Vec<BorrowedEntry<'a, K, V>>,
-    PhantomData<&'a KdTreeMap<K, V, M>>,
-);
+///
+/// The tree is searched lazily as the iterator advances, so stopping early (for instance, with
+/// [`Iterator::take`]) skips the rest of the search.
+/// The only allocation is the traversal stack, whose size is proportional to the depth of the
+/// tree.
+pub struct RangeNearest<'a, K, V, M: Metric<K>> {
+    map: &'a KdTreeMap<K, V, M>,
+    point: &'a K,
+    radius: M::Distance,
+    /// The subtrees which remain to be searched.
+    /// Each frame contains the ID of the subtree's root, the lower and upper corners of its
+    /// bounding region, and the axis along which its root splits.
+    stack: Vec<(usize, K, K, usize)>,
+}
 
-impl<'a, K, V, M> Iterator for RangeNearest<'a, K, V, M> {
+impl<'a, K, V, M> Iterator for RangeNearest<'a, K, V, M>
+where
+    M: DistanceAabb<K>,
+    K: KdKey,
+{
     type Item = BorrowedEntry<'a, K, V>;
     fn next(&mut self) -> Option<Self::Item> {
-        self.0.pop()
+        let map = self.map;
+        while let Some((node_id, mut reg_lo, mut reg_hi, k)) = self.stack.pop() {
+            let node = &map.nodes[node_id];
+            let is_left = self.point.compare(&node.key, k).is_lt();
+            let [near_child, far_child] = if is_left {
+                [node.children[0], node.children[1]]
+            } else {
+                [node.children[1], node.children[0]]
+            };
+
+            // push the far child first so that the near child is searched first
+            let new_k = (k + 1) % K::dimension();
+            let near_region = near_child.map(|_| (reg_lo.clone(), reg_hi.clone()));
+            if let Some(c) = far_child {
+                if is_left {
+                    reg_lo.assign(&node.key, k);
+                } else {
+                    reg_hi.assign(&node.key, k);
+                }
+                if map.metric.distance_to_aabb(self.point, &reg_lo, &reg_hi) <= self.radius {
+                    self.stack.push((c.get(), reg_lo, reg_hi, new_k));
+                }
+            }
+            if let (Some(c), Some((lo, hi))) = (near_child, near_region) {
+                self.stack.push((c.get(), lo, hi, new_k));
+            }
+
+            if !node.removed && map.metric.distance(self.point, &node.key) <= self.radius {
+                return Some(BorrowedEntry {
+                    key: &node.key,
+                    value: &map.values[node_id],
+                });
+            }
+        }
+        None
     }
 }
 
@@ -153,19 +370,139 @@ where
         M: 'q;
 
     fn nearest_within_r<'q>(&'q self, key: &'q K, r: Self::Distance) -> Self::RangeNearest<'q> {
-        let mut result = Vec::new();
+        let mut stack = Vec::new();
         if !self.nodes.is_empty() {
-            self.nearest_r_help(
-                key,
-                &mut result,
-                &r,
-                0,
-                K::lower_bound(),
-                K::upper_bound(),
-                0,
-            );
+            stack.push((0, K::lower_bound(), K::upper_bound(), 0));
+        }
+        RangeNearest {
+            map: self,
+            point: key,
+            radius: r,
+            stack,
         }
-        RangeNearest(result, PhantomData)
+    }
+}
+
+/// An iterator over the _k_ nearest points to a query point in a [`KdTreeMap`], from nearest to
+/// farthest.
+pub struct KNearest<'a, K, V, M>(
+    /// Sorted from farthest to nearest, so that popping yields the nearest first.
+    Vec<BorrowedEntry<'a, K, V>>,
+    PhantomData<&'a KdTreeMap<K, V, M>>,
+);
+
+impl<'a, K, V, M> Iterator for KNearest<'a, K, V, M> {
+    type Item = BorrowedEntry<'a, K, V>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len(), Some(self.0.len()))
+    }
+}
+
+/// A candidate neighbor while searching for the _k_ nearest neighbors, ordered by its distance.
+struct Candidate<D> {
+    dist: D,
+    node_id: usize,
+}
+
+impl<D: PartialOrd> PartialEq for Candidate<D> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl<D: PartialOrd> Eq for Candidate<D> {}
+
+impl<D: PartialOrd> PartialOrd for Candidate<D> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<D: PartialOrd> Ord for Candidate<D> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.dist
+            .partial_cmp(&other.dist)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+impl<K, V, M> KNearestNeighborsMap<K, V> for KdTreeMap<K, V, M>
+where
+    M: DistanceAabb<K>,
+    K: KdKey,
+{
+    type KNearest<'q>
+        = KNearest<'q, K, V, M>
+    where
+        K: 'q,
+        V: 'q,
+        M: 'q;
+
+    fn k_nearest<'q>(&'q self, key: &K, k: usize) -> Self::KNearest<'q> {
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        if !self.nodes.is_empty() && k > 0 {
+            self.k_nearest_help(key, &mut heap, k, 0, K::lower_bound(), K::upper_bound(), 0);
+        }
+        // `into_sorted_vec` is ascending, so reverse it to pop the nearest first
+        KNearest(
+            heap.into_sorted_vec()
+                .into_iter()
+                .rev()
+                .map(|c| BorrowedEntry {
+                    key: &self.nodes[c.node_id].key,
+                    value: &self.values[c.node_id],
+                })
+                .collect(),
+            PhantomData,
+        )
+    }
+}
+
+impl<K, V, M> RemoveNearestNeighborsMap<K, V> for KdTreeMap<K, V, M>
+where
+    M: DistanceAabb<K>,
+    K: KdKey + PartialEq,
+    V: PartialEq,
+{
+    /// Removed entries are marked as tombstones and left in the tree until more than half of all
+    /// nodes are removed, at which point the tree is rebuilt from the remaining entries.
+    fn remove(&mut self, key: &K, value: &V) -> bool {
+        if self.nodes.is_empty() {
+            return false;
+        }
+        // keys equal to a node along its axis may be in either of its subtrees
+        let mut stack = vec![(0, 0)];
+        let mut found = None;
+        while let Some((node_id, k)) = stack.pop() {
+            let node = &self.nodes[node_id];
+            if !node.removed && &node.key == key && &self.values[node_id] == value {
+                found = Some(node_id);
+                break;
+            }
+            let ord = node.key.compare(key, k);
+            let new_k = (k + 1) % K::dimension();
+            for (side, child) in node.children.iter().enumerate() {
+                if let Some(c) = child {
+                    if ord.is_eq() || usize::from(ord.is_lt()) == side {
+                        stack.push((c.get(), new_k));
+                    }
+                }
+            }
+        }
+        let Some(node_id) = found else {
+            return false;
+        };
+
+        self.nodes[node_id].removed = true;
+        self.n_removed += 1;
+        if self.n_removed * 2 > self.nodes.len() {
+            self.rebuild();
+        }
+        true
     }
 }
 
@@ -174,14 +511,78 @@ where
     M: DistanceAabb<K>,
     K: KdKey,
 {
+    /// Rebuild the tree from scratch, discarding all removed nodes.
+    fn rebuild(&mut self) {
+        let nodes = mem::take(&mut self.nodes);
+        let values = mem::take(&mut self.values);
+        self.n_removed = 0;
+        for (node, value) in nodes.into_iter().zip(values) {
+            if !node.removed {
+                self.nodes.push(Node {
+                    key: node.key,
+                    children: [None; 2],
+                    removed: false,
+                });
+                self.values.push(value);
+            }
+        }
+        self.rebuild_subtree(&(0..self.nodes.len()).collect::<Vec<_>>(), 0);
+    }
+
+    /// Search for the nearest neighbor to `key`, pruning every region whose distance to `key`,
+    /// after being passed through `inflate`, is no less than that of the best neighbor so far.
+    ///
+    /// If `inflate` is the identity, the search is exact.
+    pub(super) fn nearest_inflated(
+        &self,
+        key: &K,
+        inflate: &impl Fn(M::Distance) -> M::Distance,
+    ) -> Option<BorrowedEntry<'_, K, V>> {
+        let root = self.nodes.first()?;
+        let mut radius = None;
+        let best_node = if root.removed {
+            None
+        } else {
+            let root_dist = self.metric.distance(&root.key, key);
+            if root_dist.is_zero() {
+                return Some(BorrowedEntry {
+                    key: &root.key,
+                    value: &self.values[0],
+                });
+            }
+            radius = Some(root_dist);
+            Some(0)
+        };
+        let best_node = self
+            .nearest_help(
+                0,
+                key,
+                K::lower_bound(),
+                K::upper_bound(),
+                &mut radius,
+                0,
+                inflate,
+            )
+            .map(NonZeroUsize::get)
+            .or(best_node)?;
+        Some(BorrowedEntry {
+            key: &self.nodes[best_node].key,
+            value: &self.values[best_node],
+        })
+    }
+
+    /// Search for the nearest neighbor to `key` among the descendants of `node_id`.
+    /// `radius` is the distance to the best neighbor found so far, or `None` if there is none yet.
+    #[expect(clippy::too_many_arguments)]
     fn nearest_help(
         &self,
         node_id: usize,
         key: &K,
         mut reg_lo: K,
         mut reg_hi: K,
-        radius: &mut <M as Metric<K>>::Distance,
+        radius: &mut Option<<M as Metric<K>>::Distance>,
         k: usize,
+        inflate: &impl Fn(M::Distance) -> M::Distance,
     ) -> Option<NonZeroUsize> {
         let node = &self.nodes[node_id];
         let mut best_result = None;
@@ -191,13 +592,16 @@ where
 
         if let Some(child_id) = children[0] {
             let child = &self.nodes[child_id.get()];
-            let cdist = self.metric.distance(&child.key, key);
-            if cdist <= *radius {
-                *radius = cdist;
-                best_result = Some(child_id);
-                if radius.is_zero() {
-                    // exact match to query
-                    return best_result;
+            if !child.removed {
+                let cdist = self.metric.distance(&child.key, key);
+                if radius.as_ref().is_none_or(|r| cdist <= *r) {
+                    best_result = Some(child_id);
+                    if cdist.is_zero() {
+                        // exact match to query
+                        *radius = Some(cdist);
+                        return best_result;
+                    }
+                    *radius = Some(cdist);
                 }
             }
 
@@ -209,18 +613,22 @@ where
                     reg_hi.clone(),
                     radius,
                     (k + 1) % K::dimension(),
+                    inflate,
                 )
                 .or(best_result);
         }
         if let Some(child_id) = children[1] {
             let child = &self.nodes[child_id.get()];
-            let cdist = self.metric.distance(&child.key, key);
-            if cdist <= *radius {
-                *radius = cdist;
-                best_result = Some(child_id);
-                if radius.is_zero() {
-                    // exact match to query
-                    return best_result;
+            if !child.removed {
+                let cdist = self.metric.distance(&child.key, key);
+                if radius.as_ref().is_none_or(|r| cdist <= *r) {
+                    best_result = Some(child_id);
+                    if cdist.is_zero() {
+                        // exact match to query
+                        *radius = Some(cdist);
+                        return best_result;
+                    }
+                    *radius = Some(cdist);
                 }
             }
 
@@ -229,7 +637,10 @@ where
             } else {
                 reg_lo.assign(&node.key, k);
             }
-            if self.metric.distance_to_aabb(key, &reg_lo, &reg_hi) < *radius {
+            if radius
+                .as_ref()
+                .is_none_or(|r| inflate(self.metric.distance_to_aabb(key, &reg_lo, &reg_hi)) < *r)
+            {
                 best_result = self
                     .nearest_help(
                         child_id.get(),
@@ -238,6 +649,7 @@ where
                         reg_hi,
                         radius,
                         (k + 1) % K::dimension(),
+                        inflate,
                     )
                     .or(best_result);
             }
@@ -246,23 +658,30 @@ where
         best_result
     }
 
+    /// Search for the `n` nearest neighbors to `point`, keeping the best candidates found so far
+    /// in `heap`.
+    /// The farthest of the candidates is at the top of the heap, so that it can be evicted once a
+    /// closer point is found.
     #[expect(clippy::too_many_arguments)]
-    fn nearest_r_help<'q>(
-        &'q self,
+    fn k_nearest_help(
+        &self,
         point: &K,
-        buf: &mut Vec<BorrowedEntry<'q, K, V>>,
-        radius: &<M as Metric<K>>::Distance,
+        heap: &mut BinaryHeap<Candidate<<M as Metric<K>>::Distance>>,
+        n: usize,
         node_id: usize,
         mut reg_lo: K,
         mut reg_hi: K,
         k: usize,
     ) {
         let node = &self.nodes[node_id];
-        if &self.metric.distance(point, &node.key) <= radius {
-            buf.push(BorrowedEntry {
-                key: &node.key,
-                value: &self.values[node_id],
-            });
+        if !node.removed {
+            let dist = self.metric.distance(point, &node.key);
+            if heap.len() < n {
+                heap.push(Candidate { dist, node_id });
+            } else if heap.peek().is_some_and(|worst| dist < worst.dist) {
+                heap.pop();
+                heap.push(Candidate { dist, node_id });
+            }
         }
 
         let is_left = point.compare(&node.key, k).is_lt();
@@ -274,10 +693,10 @@ where
 
         let new_k = (k + 1) % K::dimension();
         if let Some(c) = near_child {
-            self.nearest_r_help(
+            self.k_nearest_help(
                 point,
-                buf,
-                radius,
+                heap,
+                n,
                 c.get(),
                 reg_lo.clone(),
                 reg_hi.clone(),
@@ -291,8 +710,12 @@ where
             } else {
                 reg_hi.assign(&node.key, k);
             }
-            if &self.metric.distance_to_aabb(point, &reg_lo, &reg_hi) <= radius {
-                self.nearest_r_help(point, buf, radius, c.get(), reg_lo, reg_hi, new_k);
+            if heap.len() < n
+                || heap.peek().is_some_and(|worst| {
+                    self.metric.distance_to_aabb(point, &reg_lo, &reg_hi) < worst.dist
+                })
+            {
+                self.k_nearest_help(point, heap, n, c.get(), reg_lo, reg_hi, new_k);
             }
         }
     }
@@ -309,14 +732,15 @@ where
 
 #[cfg(test)]
 mod tests {
-    use rand::SeedableRng;
+    use core::sync::atomic::{self, AtomicUsize};
+    use rand::{Rng, SeedableRng};
     use rand_chacha::ChaCha20Rng;
 
     use super::*;
     use crate::{
-        metric::SquaredEuclidean,
+        metric::{Euclidean, SquaredEuclidean},
         sample::{Rectangle, Sample},
-        space::{Pose2d, Vector, WeightedPoseDistance},
+        space::{Pose2d, Pose3d, Vector, WeightedPoseDistance},
         valid::AlwaysValid,
     };
 
@@ -444,6 +868,184 @@ mod tests {
         }
     }
 
+    /// Get the depth of the subtree rooted at `node_id`.
+    fn depth<K, V, M>(t: &KdTreeMap<K, V, M>, node_id: usize) -> usize {
+        1 + t.nodes[node_id]
+            .children
+            .iter()
+            .flatten()
+            .map(|c| depth(t, c.get()))
+            .max()
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn build_balanced() {
+        // points on a line are the worst case for incremental insertion
+        let t: KdTreeMap<_, _, SquaredEuclidean> = (0..1000u32)
+            .map(|i| (Vector::new([f64::from(i), 0.0]), i))
+            .collect();
+        assert_eq!(depth(&t, 0), 10);
+        for i in 0..1000u32 {
+            let q = Vector::new([f64::from(i) + 0.3, 1.0]);
+            assert_eq!(*t.nearest(&q).unwrap().value, i);
+        }
+        for (id, node) in t.nodes.iter().enumerate() {
+            assert!(node.children.iter().flatten().all(|c| c.get() > id));
+        }
+    }
+
+    #[test]
+    fn sorted_inserts_rebalance() {
+        let mut t = KdTreeMap::new(SquaredEuclidean);
+        for i in 0..2000u32 {
+            t.insert(Vector::new([f64::from(i), f64::from(i % 7)]), i);
+            assert!(depth(&t, 0) <= max_depth(t.nodes.len()));
+        }
+        let mut rng = ChaCha20Rng::seed_from_u64(2707);
+        for _ in 0..1000 {
+            let q = Vector::new([rng.gen_range(-10.0..2010.0), rng.gen_range(-1.0..8.0)]);
+            let bf = (0..2000u32)
+                .map(|i| {
+                    SquaredEuclidean.distance(&Vector::new([f64::from(i), f64::from(i % 7)]), &q)
+                })
+                .min_by(f64::total_cmp);
+            let kdt = t.nearest(&q).map(|e| SquaredEuclidean.distance(e.key, &q));
+            assert_eq!(bf, kdt);
+        }
+        // removal must still find every entry after rebalancing
+        for i in 0..2000u32 {
+            assert!(t.remove(&Vector::new([f64::from(i), f64::from(i % 7)]), &i));
+        }
+        assert_eq!(t.nearest(&Vector::new([0.0; 2])), None);
+    }
+
+    /// The number of times [`Counted::compare`] has been called.
+    static N_COMPARES: AtomicUsize = AtomicUsize::new(0);
+
+    /// A key which counts how many times it has been compared.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Counted(Vector<3, f32>);
+
+    impl KdKey for Counted {
+        fn dimension() -> usize {
+            3
+        }
+
+        fn compare(&self, rhs: &Self, k: usize) -> Ordering {
+            N_COMPARES.fetch_add(1, atomic::Ordering::Relaxed);
+            self.0.compare(&rhs.0, k)
+        }
+
+        fn assign(&mut self, src: &Self, k: usize) {
+            self.0.assign(&src.0, k);
+        }
+
+        fn lower_bound() -> Self {
+            Self(Vector::lower_bound())
+        }
+
+        fn upper_bound() -> Self {
+            Self(Vector::upper_bound())
+        }
+    }
+
+    impl Metric<Counted> for SquaredEuclidean {
+        type Distance = f32;
+
+        fn distance(&self, c1: &Counted, c2: &Counted) -> Self::Distance {
+            self.distance(&c1.0, &c2.0)
+        }
+    }
+
+    impl DistanceAabb<Counted> for SquaredEuclidean {
+        fn distance_to_aabb(&self, c: &Counted, aabb_lo: &Counted, aabb_hi: &Counted) -> f32 {
+            self.distance_to_aabb(&c.0, &aabb_lo.0, &aabb_hi.0)
+        }
+    }
+
+    #[test]
+    fn random_walk_inserts() {
+        // each point takes a short step from a random earlier point, as in an RRT
+        const N: usize = 20_000;
+        let step = Rectangle {
+            min: Vector::new([-0.05; 3]),
+            max: Vector::new([0.05; 3]),
+        };
+        let mut rng = ChaCha20Rng::seed_from_u64(2707);
+        let mut points = vec![Vector::new([0.0f32; 3])];
+        while points.len() < N {
+            let Vector(base) = points[rng.gen_range(0..points.len())];
+            let Vector(d) = step.sample(&mut rng);
+            points.push(Vector::new([
+                base[0] + d[0],
+                base[1] + d[1],
+                base[2] + d[2],
+            ]));
+        }
+
+        let mut t = KdTreeMap::new(SquaredEuclidean);
+        N_COMPARES.store(0, atomic::Ordering::Relaxed);
+        for (i, &p) in points.iter().enumerate() {
+            t.insert(Counted(p), i);
+        }
+        let n_compares = N_COMPARES.load(atomic::Ordering::Relaxed);
+        // rebuilding subtrees must not dominate the cost of insertion
+        let bound = 4 * N * N.ilog2() as usize;
+        assert!(
+            n_compares < bound,
+            "{n_compares} comparisons, expected < {bound}"
+        );
+        assert!(depth(&t, 0) <= max_depth(N));
+
+        for _ in 0..200 {
+            let q: Vector<3, f32> = step.sample(&mut rng);
+            let bf = points
+                .iter()
+                .map(|p| SquaredEuclidean.distance(p, &q))
+                .min_by(f32::total_cmp);
+            let kdt = t
+                .nearest(&Counted(q))
+                .map(|e| SquaredEuclidean.distance(&e.key.0, &q));
+            assert_eq!(bf, kdt);
+        }
+    }
+
+    #[test]
+    fn range_stops_early() {
+        let region = Rectangle {
+            min: Vector::new([-10.0; 3]),
+            max: Vector::new([10.0; 3]),
+        };
+
+        let mut rng = ChaCha20Rng::seed_from_u64(2707);
+
+        let mut kdt = KdTreeMap::new(SquaredEuclidean);
+        for i in 0..500 {
+            let pt: Vector<3, f32> = region.sample(&mut rng);
+            kdt.insert(pt, i);
+        }
+        let q = Vector::new([0.0; 3]);
+        let mut iter = kdt.nearest_within_r(&q, 25.0);
+        let first: Vec<_> = iter.by_ref().take(3).collect();
+        assert_eq!(first.len(), 3);
+        let rest: Vec<_> = iter.collect();
+        assert!(!rest.is_empty());
+        for e in first.iter().chain(&rest) {
+            assert!(SquaredEuclidean.distance(e.key, &q) <= 25.0);
+        }
+        // resuming after stopping early must not repeat any entries
+        assert!(first.iter().all(|e| !rest.contains(e)));
+        assert_eq!(
+            first.len() + rest.len(),
+            kdt.values
+                .iter()
+                .zip(&kdt.nodes)
+                .filter(|(_, n)| SquaredEuclidean.distance(&n.key, &q) <= 25.0)
+                .count()
+        );
+    }
+
     #[test]
     fn pose2d() {
         let region = Rectangle {
@@ -478,7 +1080,7 @@ mod tests {
             let mut bf_rn: Vec<&Pose2d<f32>> = bf
                 .poses
                 .iter()
-                .filter(|p| m.distance(p, &q) <= 2.0)
+                .filter(|p| m.distance(*p, &q) <= 2.0)
                 .collect();
             let mut kdt_rn: Vec<&Pose2d<f32>> =
                 kdt.nearest_within_r(&q, 2.0).map(|e| e.key).collect();
@@ -488,4 +1090,151 @@ mod tests {
             assert_eq!(bf_rn, kdt_rn);
         }
     }
+
+    #[test]
+    fn pose3d() {
+        let region = Rectangle {
+            min: Vector::new([-10.0; 3]),
+            max: Vector::new([10.0; 3]),
+        };
+
+        let mut rng = ChaCha20Rng::seed_from_u64(2707);
+
+        let m = WeightedPoseDistance {
+            position_metric: Euclidean,
+            position_weight: 1.0,
+            angle_metric: Euclidean,
+            angle_weight: 1.0,
+        };
+        let mut bf = BruteForce {
+            poses: Vec::new(),
+            values: Vec::new(),
+            metric: m,
+        };
+        let mut kdt = KdTreeMap::new(m);
+        for _ in 0..2_000 {
+            let pt: Pose3d<f64> = region.sample(&mut rng);
+            bf.insert(pt, ());
+            kdt.insert(pt, ());
+            let q = region.sample(&mut rng);
+            let bf_nearest = bf.nearest(&q).unwrap().key;
+            let kdt_nearest = kdt.nearest(&q).unwrap().key;
+
+            assert_eq!(bf_nearest, kdt_nearest);
+        }
+    }
+
+    #[test]
+    fn k_nearest_3d() {
+        let region = Rectangle {
+            min: Vector::new([-10.0; 3]),
+            max: Vector::new([10.0; 3]),
+        };
+
+        let mut rng = ChaCha20Rng::seed_from_u64(2707);
+
+        let mut points = Vec::new();
+        let mut kdt = KdTreeMap::new(SquaredEuclidean);
+        assert_eq!(kdt.k_nearest(&Vector::new([0.0; 3]), 5).count(), 0);
+        for i in 0..500 {
+            let pt: Vector<3, f32> = region.sample(&mut rng);
+            points.push(pt);
+            kdt.insert(pt, i);
+            let q: Vector<3, f32> = region.sample(&mut rng);
+            let k = i % 7;
+
+            let mut bf_dists: Vec<f32> = points
+                .iter()
+                .map(|p| SquaredEuclidean.distance(p, &q))
+                .collect();
+            bf_dists.sort_unstable_by(f32::total_cmp);
+            bf_dists.truncate(k);
+            let kdt_dists: Vec<f32> = kdt
+                .k_nearest(&q, k)
+                .map(|e| SquaredEuclidean.distance(e.key, &q))
+                .collect();
+
+            assert_eq!(bf_dists, kdt_dists);
+        }
+    }
+
+    #[test]
+    fn remove_and_update() {
+        let region = Rectangle {
+            min: Vector::new([-10.0; 3]),
+            max: Vector::new([10.0; 3]),
+        };
+
+        let mut rng = ChaCha20Rng::seed_from_u64(2707);
+
+        // live entries, indexed by value
+        let mut points: Vec<Option<Vector<3, f32>>> = Vec::new();
+        let mut kdt = KdTreeMap::new(SquaredEuclidean);
+        assert!(!kdt.remove(&Vector::new([0.0; 3]), &0));
+        for i in 0..1000 {
+            match rng.gen_range(0..4) {
+                0 if !points.is_empty() => {
+                    let v = rng.gen_range(0..points.len());
+                    let removed = points[v].take();
+                    let key = removed.unwrap_or(Vector::new([0.0; 3]));
+                    assert_eq!(kdt.remove(&key, &v), removed.is_some());
+                }
+                1 if !points.is_empty() => {
+                    let v = rng.gen_range(0..points.len());
+                    let new_key: Vector<3, f32> = region.sample(&mut rng);
+                    let old = points[v].inspect(|_| points[v] = Some(new_key));
+                    let key = old.unwrap_or(Vector::new([0.0; 3]));
+                    assert_eq!(kdt.update_key(&key, v, new_key), old.is_some());
+                }
+                _ => {
+                    let pt: Vector<3, f32> = region.sample(&mut rng);
+                    kdt.insert(pt, points.len());
+                    points.push(Some(pt));
+                }
+            }
+
+            let q: Vector<3, f32> = region.sample(&mut rng);
+            let live = || {
+                points
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(v, p)| Some((v, (*p)?)))
+            };
+
+            let bf_nearest = live()
+                .map(|(_, p)| SquaredEuclidean.distance(&p, &q))
+                .min_by(f32::total_cmp);
+            let kdt_nearest = kdt.nearest(&q);
+            assert_eq!(
+                bf_nearest,
+                kdt_nearest.map(|e| SquaredEuclidean.distance(e.key, &q))
+            );
+            if let Some(e) = kdt_nearest {
+                assert_eq!(points[*e.value], Some(*e.key));
+            }
+
+            let mut bf_range: Vec<usize> = live()
+                .filter(|(_, p)| SquaredEuclidean.distance(p, &q) <= 16.0)
+                .map(|(v, _)| v)
+                .collect();
+            let mut kdt_range: Vec<usize> =
+                kdt.nearest_within_r(&q, 16.0).map(|e| *e.value).collect();
+            bf_range.sort_unstable();
+            kdt_range.sort_unstable();
+            assert_eq!(bf_range, kdt_range);
+
+            let k = i % 7;
+            let mut bf_dists: Vec<f32> = live()
+                .map(|(_, p)| SquaredEuclidean.distance(&p, &q))
+                .collect();
+            bf_dists.sort_unstable_by(f32::total_cmp);
+            bf_dists.truncate(k);
+            let kdt_dists: Vec<f32> = kdt
+                .k_nearest(&q, k)
+                .inspect(|e| assert_eq!(points[*e.value], Some(*e.key)))
+                .map(|e| SquaredEuclidean.distance(e.key, &q))
+                .collect();
+            assert_eq!(bf_dists, kdt_dists);
+        }
+    }
 }
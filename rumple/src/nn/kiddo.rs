@@ -1,4 +1,5 @@
-use core::{marker::PhantomData, ops::AddAssign};
+use alloc::vec::Vec;
+use core::{array, ops::AddAssign};
 
 use kiddo::{
     float::kdtree::{Axis, KdTree},
@@ -6,99 +7,393 @@ use kiddo::{
 };
 use num_traits::float::Float;
 
-use crate::{nn::NearestNeighborsMap, space::Vector};
+use crate::{
+    metric::{AxisWeighted, Euclidean, SquaredEuclidean},
+    nn::NearestNeighborsMap,
+    space::{Pose2d, Vector},
+};
 
-use super::{NearestEntry, RangeNearestNeighborsMap};
+use super::{
+    BorrowedEntry, KNearestNeighborsMap, RangeNearestNeighborsMap, RemoveNearestNeighborsMap,
+};
 
 #[derive(Clone, Debug)]
 /// A _k_-d tree map using [`kiddo::KdTree`] as its backing implementation.
 ///
-/// Unlike [`KdTreeMap`](super::KdTreeMap)
-pub struct KiddoMap<T: Default + Copy, const N: usize, M> {
+/// Unlike [`KdTreeMap`](super::KdTreeMap), keys are not stored directly in the tree.
+/// Instead, each key is embedded as a point in `N`-dimensional Euclidean space (see
+/// [`KiddoKey`]), and the metric `M` is evaluated on those points (see [`KiddoMetric`]).
+/// By default, keys are [`Vector`]s and values are `usize`.
+pub struct KiddoMap<T: Default + Copy, const N: usize, M, K = Vector<N, T>, V = usize> {
+    /// The tree of embedded keys, whose items are indices into `keys` and `values`.
     tree: KdTree<T, usize, N, 32, u32>,
-    metric: PhantomData<M>,
+    keys: Vec<K>,
+    values: Vec<V>,
+    metric: M,
+    /// The factor by which each axis of an embedded key is scaled before insertion into the tree.
+    scale: [T; N],
+}
+
+/// A key which can be stored in a [`KiddoMap`] by embedding it in `N`-dimensional Euclidean
+/// space.
+pub trait KiddoKey<T, const N: usize> {
+    /// Compute the point in Euclidean space which represents this key.
+    fn embed(&self) -> [T; N];
+}
+
+/// A metric which a [`KiddoMap`] can evaluate using kiddo's squared-Euclidean distance function
+/// on scaled embeddings of its keys.
+pub trait KiddoMetric<T, const N: usize> {
+    /// Get the factor by which each axis of an embedded key must be scaled so that the
+    /// squared-Euclidean distance between scaled points is ordered the same as this metric.
+    fn scale(&self) -> [T; N];
+
+    /// Convert a distance under this metric into a squared-Euclidean distance between scaled
+    /// points.
+    fn to_squared(&self, d: T) -> T;
+}
+
+impl<T: Copy, const N: usize> KiddoKey<T, N> for Vector<N, T> {
+    fn embed(&self) -> [T; N] {
+        self.0
+    }
+}
+
+/// Poses are embedded as `(x, y, cos θ, sin θ)`, so that angles on either side of the
+/// wraparound are close together.
+/// As a result, angular distance is measured as the chord `2 sin(Δθ / 2)` rather than the arc
+/// `Δθ`.
+impl<T: Float> KiddoKey<T, 4> for Pose2d<T> {
+    fn embed(&self) -> [T; 4] {
+        let (s, c) = self.angle.get().sin_cos();
+        [self.position[0], self.position[1], c, s]
+    }
 }
 
-pub struct KiddoEntry<V> {
-    value: V,
+impl<T: Float, const N: usize> KiddoMetric<T, N> for SquaredEuclidean {
+    fn scale(&self) -> [T; N] {
+        [T::one(); N]
+    }
+
+    fn to_squared(&self, d: T) -> T {
+        d
+    }
 }
 
-impl<V> NearestEntry<V> for KiddoEntry<V> {
-    fn value(&self) -> &V {
-        &self.value
+impl<T: Float, const N: usize> KiddoMetric<T, N> for Euclidean {
+    fn scale(&self) -> [T; N] {
+        [T::one(); N]
+    }
+
+    fn to_squared(&self, d: T) -> T {
+        d * d
     }
 }
 
-impl<T: Default + Copy, const N: usize, M> KiddoMap<T, N, M> {
+impl<M, T, const N: usize> KiddoMetric<T, N> for AxisWeighted<M, T, N>
+where
+    M: KiddoMetric<T, N>,
+    T: Float,
+{
+    fn scale(&self) -> [T; N] {
+        let inner = self.metric.scale();
+        array::from_fn(|i| inner[i] * self.weights[i].sqrt())
+    }
+
+    fn to_squared(&self, d: T) -> T {
+        self.metric.to_squared(d)
+    }
+}
+
+impl<T, const N: usize, M, K, V> KiddoMap<T, N, M, K, V>
+where
+    T: Default + Copy + Axis,
+    M: KiddoMetric<T, N>,
+{
     #[must_use]
+    /// Construct a new, empty `KiddoMap` using the default value of its metric.
     pub fn new() -> Self
     where
-        T: Axis,
+        M: Default,
     {
+        Self::with_metric(M::default())
+    }
+
+    #[must_use]
+    /// Construct a new, empty `KiddoMap` using the provided metric.
+    pub fn with_metric(metric: M) -> Self {
         Self {
             tree: KdTree::new(),
-            metric: PhantomData,
+            keys: Vec::new(),
+            values: Vec::new(),
+            scale: metric.scale(),
+            metric,
         }
     }
 }
 
-impl<T, const N: usize> NearestNeighborsMap<Vector<N, T>, usize>
-    for KiddoMap<T, N, crate::metric::SquaredEuclidean>
+impl<T, const N: usize, M, K, V> KiddoMap<T, N, M, K, V>
+where
+    T: Float + Default + Axis,
+    K: KiddoKey<T, N>,
+{
+    /// Compute the point at which `key` is stored in the tree.
+    fn embed(&self, key: &K) -> [T; N] {
+        let point = key.embed();
+        array::from_fn(|i| point[i] * self.scale[i])
+    }
+
+    fn entry(&self, id: usize) -> BorrowedEntry<'_, K, V> {
+        BorrowedEntry {
+            key: &self.keys[id],
+            value: &self.values[id],
+        }
+    }
+}
+
+impl<T, const N: usize, M, K, V> NearestNeighborsMap<K, V> for KiddoMap<T, N, M, K, V>
 where
     T: Float + Default + AddAssign + Send + Sync + Axis,
+    K: KiddoKey<T, N>,
 {
     type Entry<'q>
-        = KiddoEntry<usize>
+        = BorrowedEntry<'q, K, V>
     where
-        T: 'q;
-    fn insert(&mut self, key: Vector<N, T>, value: usize) {
-        self.tree.add(&key, value);
+        Self: 'q;
+
+    fn insert(&mut self, key: K, value: V) {
+        self.tree.add(&self.embed(&key), self.keys.len());
+        self.keys.push(key);
+        self.values.push(value);
     }
 
-    fn nearest<'q>(&'q self, key: &Vector<N, T>) -> Option<Self::Entry<'q>> {
-        (self.tree.size() > 0).then(|| {
-            let item = self.tree.nearest_one::<kiddo::SquaredEuclidean>(key).item;
-            KiddoEntry { value: item }
+    fn nearest<'q>(&'q self, key: &K) -> Option<Self::Entry<'q>> {
+        (!self.keys.is_empty()).then(|| {
+            let id = self
+                .tree
+                .nearest_one::<kiddo::SquaredEuclidean>(&self.embed(key))
+                .item;
+            self.entry(id)
         })
     }
 }
 
-pub struct KiddoNearest<'a, T: Default + Copy, const N: usize, M> {
+/// An iterator over entries found by a query on a [`KiddoMap`].
+pub struct KiddoNearest<'a, T: Default + Copy, const N: usize, M, K, V> {
+    map: &'a KiddoMap<T, N, M, K, V>,
     iter: Vec<NearestNeighbour<T, usize>>,
-    _phantom: PhantomData<(&'a KiddoMap<T, N, M>, M)>,
 }
 
-impl<T, const N: usize> RangeNearestNeighborsMap<Vector<N, T>, usize>
-    for KiddoMap<T, N, crate::metric::SquaredEuclidean>
+impl<T, const N: usize, M, K, V> RangeNearestNeighborsMap<K, V> for KiddoMap<T, N, M, K, V>
 where
     T: Float + Default + AddAssign + Send + Sync + Axis,
+    M: KiddoMetric<T, N>,
+    K: KiddoKey<T, N>,
 {
     type Distance = T;
     type RangeNearest<'q>
-        = KiddoNearest<'q, T, N, crate::metric::SquaredEuclidean>
+        = KiddoNearest<'q, T, N, M, K, V>
     where
         Self: 'q;
-    fn nearest_within_r<'q>(
-        &'q self,
-        key: &'q Vector<N, T>,
-        r: Self::Distance,
-    ) -> Self::RangeNearest<'q> {
+
+    fn nearest_within_r<'q>(&'q self, key: &'q K, r: Self::Distance) -> Self::RangeNearest<'q> {
         KiddoNearest {
-            iter: self.tree.within_unsorted::<kiddo::SquaredEuclidean>(key, r),
-            _phantom: PhantomData,
+            map: self,
+            iter: self.tree.within_unsorted::<kiddo::SquaredEuclidean>(
+                &self.embed(key),
+                self.metric.to_squared(r),
+            ),
         }
     }
 }
 
-impl<T: Default + Copy, const N: usize, M> Iterator for KiddoNearest<'_, T, N, M> {
-    type Item = KiddoEntry<usize>;
+impl<T, const N: usize, M, K, V> KNearestNeighborsMap<K, V> for KiddoMap<T, N, M, K, V>
+where
+    T: Float + Default + AddAssign + Send + Sync + Axis,
+    K: KiddoKey<T, N>,
+{
+    type KNearest<'q>
+        = KiddoNearest<'q, T, N, M, K, V>
+    where
+        Self: 'q;
+
+    fn k_nearest<'q>(&'q self, key: &K, k: usize) -> Self::KNearest<'q> {
+        let mut iter = if k == 0 {
+            Vec::new()
+        } else {
+            self.tree
+                .nearest_n::<kiddo::SquaredEuclidean>(&self.embed(key), k)
+        };
+        // `nearest_n` sorts from nearest to farthest, but `KiddoNearest` pops from the back
+        iter.reverse();
+        KiddoNearest { map: self, iter }
+    }
+}
+
+impl<T, const N: usize, M, K, V> RemoveNearestNeighborsMap<K, V> for KiddoMap<T, N, M, K, V>
+where
+    T: Float + Default + AddAssign + Send + Sync + Axis,
+    K: KiddoKey<T, N> + PartialEq,
+    V: PartialEq,
+{
+    fn remove(&mut self, key: &K, value: &V) -> bool {
+        let point = self.embed(key);
+        let Some(id) = self
+            .tree
+            .within_unsorted::<kiddo::SquaredEuclidean>(&point, <T as Float>::epsilon())
+            .into_iter()
+            .map(|nbr| nbr.item)
+            .find(|&id| &self.keys[id] == key && &self.values[id] == value)
+        else {
+            return false;
+        };
+
+        self.tree.remove(&point, id);
+        let last = self.keys.len() - 1;
+        if id != last {
+            // `swap_remove` will move the last entry to `id`, so its index in the tree must move
+            // too
+            let last_point = self.embed(&self.keys[last]);
+            self.tree.remove(&last_point, last);
+            self.tree.add(&last_point, id);
+        }
+        self.keys.swap_remove(id);
+        self.values.swap_remove(id);
+        true
+    }
+}
+
+impl<'a, T: Default + Copy, const N: usize, M, K, V> Iterator for KiddoNearest<'a, T, N, M, K, V> {
+    type Item = BorrowedEntry<'a, K, V>;
     fn next(&mut self) -> Option<Self::Item> {
-        self.iter.pop().map(|nbr| KiddoEntry { value: nbr.item })
+        self.iter.pop().map(|nbr| BorrowedEntry {
+            key: &self.map.keys[nbr.item],
+            value: &self.map.values[nbr.item],
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.iter.len(), Some(self.iter.len()))
     }
 }
 
-impl<T: Default + Copy + Axis, const N: usize, M> Default for KiddoMap<T, N, M> {
+impl<T, const N: usize, M, K, V> Default for KiddoMap<T, N, M, K, V>
+where
+    T: Default + Copy + Axis,
+    M: KiddoMetric<T, N> + Default,
+{
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    use super::*;
+    use crate::{
+        metric::Metric,
+        sample::{Rectangle, Sample},
+        space::Angle,
+    };
+
+    #[test]
+    fn pose2d_wraparound() {
+        let mut map = KiddoMap::<f64, 4, Euclidean, Pose2d, &str>::new();
+        let pose = |x, theta| Pose2d {
+            position: Vector([x, 0.0]),
+            angle: Angle::new(theta),
+        };
+        map.insert(pose(0.0, 0.05), "just above zero");
+        map.insert(pose(0.0, 3.0), "half turn");
+        let nearest = map.nearest(&pose(0.0, 6.25)).unwrap();
+        assert_eq!(*nearest.value, "just above zero");
+        assert_eq!(map.nearest_within_r(&pose(0.0, 6.25), 0.5).count(), 1);
+    }
+
+    #[test]
+    fn weighted_matches_brute_force() {
+        let region = Rectangle {
+            min: Vector::new([-10.0; 3]),
+            max: Vector::new([10.0; 3]),
+        };
+        let metric = AxisWeighted {
+            metric: Euclidean,
+            weights: [1.0, 4.0, 0.25],
+        };
+
+        let mut rng = ChaCha20Rng::seed_from_u64(2707);
+
+        let mut points: Vec<Vector<3>> = Vec::new();
+        let mut map = KiddoMap::with_metric(metric);
+        for i in 0..300 {
+            let pt = region.sample(&mut rng);
+            points.push(pt);
+            map.insert(pt, i);
+            let q: Vector<3> = region.sample(&mut rng);
+
+            let bf_nearest = points
+                .iter()
+                .map(|p| metric.distance(p, &q))
+                .min_by(f64::total_cmp);
+            let map_nearest = map.nearest(&q).map(|e| metric.distance(e.key, &q));
+            assert_eq!(bf_nearest, map_nearest);
+
+            let n_within = points
+                .iter()
+                .filter(|p| metric.distance(p, &q) < 4.0)
+                .count();
+            assert_eq!(map.nearest_within_r(&q, 4.0).count(), n_within);
+        }
+
+        // remove every other point and make sure none of them are found again
+        for (i, pt) in points.iter().enumerate().step_by(2) {
+            assert!(map.remove(pt, &i));
+            assert!(!map.remove(pt, &i));
+        }
+        for (i, pt) in points.iter().enumerate() {
+            let e = map.nearest(pt).unwrap();
+            assert_eq!(*e.value % 2, 1);
+            if i % 2 == 1 {
+                assert_eq!(*e.value, i);
+            }
+        }
+    }
+
+    #[test]
+    fn k_nearest_matches_brute_force() {
+        let region = Rectangle {
+            min: Vector::new([-10.0; 3]),
+            max: Vector::new([10.0; 3]),
+        };
+        let metric = AxisWeighted {
+            metric: Euclidean,
+            weights: [1.0, 4.0, 0.25],
+        };
+
+        let mut rng = ChaCha20Rng::seed_from_u64(2707);
+
+        let mut points: Vec<Vector<3>> = Vec::new();
+        let mut map = KiddoMap::with_metric(metric);
+        assert_eq!(map.k_nearest(&Vector::new([0.0; 3]), 5).count(), 0);
+        for i in 0..500 {
+            let pt = region.sample(&mut rng);
+            points.push(pt);
+            map.insert(pt, i);
+            let q: Vector<3> = region.sample(&mut rng);
+            let k = i % 7;
+
+            let mut bf_dists: Vec<f64> = points.iter().map(|p| metric.distance(p, &q)).collect();
+            bf_dists.sort_unstable_by(f64::total_cmp);
+            bf_dists.truncate(k);
+            let map_dists: Vec<f64> = map
+                .k_nearest(&q, k)
+                .map(|e| metric.distance(e.key, &q))
+                .collect();
+
+            assert_eq!(bf_dists, map_dists);
+        }
+    }
+}
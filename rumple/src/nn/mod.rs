@@ -1,21 +1,34 @@
 //! Nearest-neighbor search.
 
+mod approx;
+mod brute;
+mod gnat;
 mod kdt;
 #[cfg(feature = "kiddo")]
 mod kiddo;
+#[cfg(feature = "std")]
+mod sharded;
 
-pub use kdt::{DistanceAabb, KdKey, KdTreeMap, RangeNearest};
+pub use approx::ApproxKdTreeMap;
+pub use brute::{BruteForceMap, BruteForceNearest, BruteForceScalar};
+pub use gnat::{GnatMap, GnatRangeNearest};
+pub use kdt::{DistanceAabb, KNearest, KdKey, KdTreeMap, RangeNearest};
 #[cfg(feature = "kiddo")]
-pub use kiddo::{KiddoMap, KiddoNearest};
-pub trait NearestEntry<V> {
+pub use kiddo::{KiddoKey, KiddoMap, KiddoMetric, KiddoNearest};
+#[cfg(feature = "std")]
+pub use sharded::{ShardedMap, ShardedNearest};
+
+pub trait NearestEntry<K, V> {
+    fn key(&self) -> &K;
     fn value(&self) -> &V;
 }
 
 /// A key-value map which is capable of nearest-neighbor search.
 pub trait NearestNeighborsMap<K, V> {
-    type Entry<'q>: NearestEntry<V> + 'q
+    type Entry<'q>: NearestEntry<K, V> + 'q
     where
         Self: 'q,
+        K: 'q,
         V: 'q;
 
     /// Insert a key into the map.
@@ -40,6 +53,41 @@ pub trait RangeNearestNeighborsMap<K, V>: NearestNeighborsMap<K, V> {
     fn nearest_within_r<'q>(&'q self, key: &'q K, r: Self::Distance) -> Self::RangeNearest<'q>;
 }
 
+/// A key-value map which is capable of _k_-nearest-neighbor search.
+pub trait KNearestNeighborsMap<K, V>: NearestNeighborsMap<K, V> {
+    /// An iterator over the nearest configurations to a query, from nearest to farthest.
+    type KNearest<'q>: Iterator<Item = Self::Entry<'q>>
+    where
+        V: 'q,
+        K: 'q,
+        Self: 'q;
+
+    /// Get an iterator over the `k` nearest elements of `self` to `key`, sorted by increasing
+    /// distance.
+    /// If `self` contains fewer than `k` elements, all of them are returned.
+    fn k_nearest<'q>(&'q self, key: &K, k: usize) -> Self::KNearest<'q>;
+}
+
+/// A key-value map which supports removing entries and moving them to new keys.
+pub trait RemoveNearestNeighborsMap<K, V>: NearestNeighborsMap<K, V> {
+    /// Remove one entry with key `key` and value `value` from the map.
+    /// Returns `true` if such an entry was found and removed.
+    ///
+    /// Once removed, an entry will never be returned by any query on the map.
+    fn remove(&mut self, key: &K, value: &V) -> bool;
+
+    /// Move the entry with key `key` and value `value` so that its key is `new_key`.
+    /// Returns `true` if the entry was found and moved.
+    /// If no such entry exists, `value` is not inserted.
+    fn update_key(&mut self, key: &K, value: V, new_key: K) -> bool {
+        let found = self.remove(key, &value);
+        if found {
+            self.insert(new_key, value);
+        }
+        found
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct BorrowedEntry<'a, K, V> {
     pub key: &'a K,
@@ -54,8 +102,37 @@ impl<K, V> Clone for BorrowedEntry<'_, K, V> {
 
 impl<K, V> Copy for BorrowedEntry<'_, K, V> {}
 
-impl<K, V> NearestEntry<V> for BorrowedEntry<'_, K, V> {
+impl<K, V> NearestEntry<K, V> for BorrowedEntry<'_, K, V> {
+    fn key(&self) -> &K {
+        self.key
+    }
+
     fn value(&self) -> &V {
         self.value
     }
 }
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct OwnedEntry<K, V> {
+    pub key: K,
+    pub value: V,
+}
+
+impl<K, V> NearestEntry<K, V> for OwnedEntry<K, V> {
+    fn key(&self) -> &K {
+        &self.key
+    }
+
+    fn value(&self) -> &V {
+        &self.value
+    }
+}
+
+impl<K: Clone, V: Clone> From<BorrowedEntry<'_, K, V>> for OwnedEntry<K, V> {
+    fn from(e: BorrowedEntry<'_, K, V>) -> Self {
+        Self {
+            key: e.key.clone(),
+            value: e.value.clone(),
+        }
+    }
+}
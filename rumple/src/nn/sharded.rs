@@ -0,0 +1,467 @@
+use alloc::vec::Vec;
+use core::{
+    cmp::Ordering,
+    marker::PhantomData,
+    sync::atomic::{self, AtomicUsize},
+};
+use std::sync::{RwLock, RwLockWriteGuard, TryLockError};
+
+use crate::metric::Metric;
+
+use super::{
+    KNearestNeighborsMap, NearestEntry, NearestNeighborsMap, OwnedEntry, RangeNearestNeighborsMap,
+    RemoveNearestNeighborsMap,
+};
+
+/// The message for a panic upon finding a poisoned shard.
+const POISONED: &str = "a shard was poisoned by a panic during an insertion or removal";
+
+/// A nearest-neighbor map which may be grown and queried from many threads at once.
+///
+/// Entries are spread across several shards of type `N`, each guarded by its own lock, and
+/// queries merge the results from every shard using the metric `M`.
+/// Since [`ShardedMap::insert_shared`] and every query take `&self`, a `ShardedMap` can be shared
+/// between the threads of a parallel planner: an entry is found by every query which begins after
+/// its insertion returns.
+/// Queries return entries by value, so keys `K` and values `V` must be [`Clone`].
+///
+/// # Panics
+///
+/// If a thread panics while inserting into or removing from a shard, every later operation on
+/// this map which touches that shard will panic.
+pub struct ShardedMap<K, V, N, M> {
+    shards: Vec<RwLock<N>>,
+    metric: M,
+    /// The shard at which the next insertion will begin looking for an unlocked shard.
+    next: AtomicUsize,
+    /// The entries are stored in the shards, but queries return them by value.
+    entries: PhantomData<fn() -> (K, V)>,
+}
+
+/// An iterator over entries found by a query on a [`ShardedMap`].
+pub struct ShardedNearest<K, V>(Vec<OwnedEntry<K, V>>);
+
+impl<K, V> Iterator for ShardedNearest<K, V> {
+    type Item = OwnedEntry<K, V>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len(), Some(self.0.len()))
+    }
+}
+
+/// Copy an entry borrowed from a shard so that it may outlive the lock on that shard.
+fn to_owned<K: Clone, V: Clone>(e: &impl NearestEntry<K, V>) -> OwnedEntry<K, V> {
+    OwnedEntry {
+        key: e.key().clone(),
+        value: e.value().clone(),
+    }
+}
+
+impl<K, V, N, M> ShardedMap<K, V, N, M> {
+    /// Construct a new `ShardedMap` with `n_shards` shards, each created by `make_shard`.
+    /// The results of queries on different shards are compared using `metric`, which should be
+    /// the same metric used by the shards.
+    ///
+    /// A good choice for `n_shards` is a small multiple of the number of threads which will
+    /// insert into the map.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `n_shards` is zero.
+    pub fn new(n_shards: usize, metric: M, mut make_shard: impl FnMut() -> N) -> Self {
+        assert!(n_shards > 0, "a sharded map must have at least one shard");
+        Self {
+            shards: (0..n_shards).map(|_| RwLock::new(make_shard())).collect(),
+            metric,
+            next: AtomicUsize::new(0),
+            entries: PhantomData,
+        }
+    }
+
+    /// Get the number of shards in this map.
+    #[must_use]
+    pub const fn n_shards(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Consume this map, returning its shards.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if any shard has been poisoned.
+    pub fn into_shards(self) -> Vec<N> {
+        self.shards
+            .into_iter()
+            .map(|shard| shard.into_inner().expect(POISONED))
+            .collect()
+    }
+
+    /// Lock a shard for writing.
+    /// Shards which are not currently locked are preferred, beginning at a different shard for
+    /// each call so that entries are spread evenly.
+    fn lock_any(&self) -> RwLockWriteGuard<'_, N> {
+        let start = self.next.fetch_add(1, atomic::Ordering::Relaxed);
+        let n = self.shards.len();
+        for i in 0..n {
+            match self.shards[(start + i) % n].try_write() {
+                Ok(guard) => return guard,
+                Err(TryLockError::Poisoned(_)) => panic!("{POISONED}"),
+                Err(TryLockError::WouldBlock) => (),
+            }
+        }
+
+        // every shard is busy, so wait on one of them
+        self.shards[start % n].write().expect(POISONED)
+    }
+
+    /// Apply `f` to each shard in turn while holding a read lock on it.
+    fn for_each_shard(&self, mut f: impl FnMut(&N)) {
+        for shard in &self.shards {
+            f(&shard.read().expect(POISONED));
+        }
+    }
+
+    /// Insert a key into the map without exclusive access to it.
+    /// This may be called from several threads at once.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the shard chosen for the insertion has been poisoned.
+    pub fn insert_shared(&self, key: K, value: V)
+    where
+        N: NearestNeighborsMap<K, V>,
+    {
+        self.lock_any().insert(key, value);
+    }
+
+    /// Remove one entry with key `key` and value `value` from the map without exclusive access to
+    /// it.
+    /// Returns `true` if such an entry was found and removed.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if any shard searched for the entry has been poisoned.
+    pub fn remove_shared(&self, key: &K, value: &V) -> bool
+    where
+        N: RemoveNearestNeighborsMap<K, V>,
+    {
+        self.shards
+            .iter()
+            .any(|shard| shard.write().expect(POISONED).remove(key, value))
+    }
+}
+
+impl<K, V, N, M> NearestNeighborsMap<K, V> for ShardedMap<K, V, N, M>
+where
+    N: NearestNeighborsMap<K, V>,
+    M: Metric<K>,
+    K: Clone,
+    V: Clone,
+{
+    type Entry<'q>
+        = OwnedEntry<K, V>
+    where
+        Self: 'q,
+        K: 'q,
+        V: 'q;
+
+    fn insert(&mut self, key: K, value: V) {
+        let next = self.next.get_mut();
+        let n = self.shards.len();
+        self.shards[*next % n]
+            .get_mut()
+            .expect(POISONED)
+            .insert(key, value);
+        *next = next.wrapping_add(1);
+    }
+
+    fn nearest<'q>(&'q self, key: &K) -> Option<Self::Entry<'q>> {
+        let mut best: Option<(M::Distance, OwnedEntry<K, V>)> = None;
+        self.for_each_shard(|shard| {
+            let Some(e) = shard.nearest(key) else {
+                return;
+            };
+            let d = self.metric.distance(e.key(), key);
+            if best.as_ref().is_none_or(|(best_d, _)| d < *best_d) {
+                best = Some((d, to_owned(&e)));
+            }
+        });
+        best.map(|(_, e)| e)
+    }
+}
+
+impl<K, V, N, M> RangeNearestNeighborsMap<K, V> for ShardedMap<K, V, N, M>
+where
+    N: RangeNearestNeighborsMap<K, V>,
+    N::Distance: Clone,
+    M: Metric<K>,
+    K: Clone,
+    V: Clone,
+{
+    type Distance = N::Distance;
+    type RangeNearest<'q>
+        = ShardedNearest<K, V>
+    where
+        Self: 'q,
+        K: 'q,
+        V: 'q;
+
+    fn nearest_within_r<'q>(&'q self, key: &'q K, r: Self::Distance) -> Self::RangeNearest<'q> {
+        let mut found = Vec::new();
+        self.for_each_shard(|shard| {
+            found.extend(shard.nearest_within_r(key, r.clone()).map(|e| to_owned(&e)));
+        });
+        ShardedNearest(found)
+    }
+}
+
+impl<K, V, N, M> KNearestNeighborsMap<K, V> for ShardedMap<K, V, N, M>
+where
+    N: KNearestNeighborsMap<K, V>,
+    M: Metric<K>,
+    K: Clone,
+    V: Clone,
+{
+    type KNearest<'q>
+        = ShardedNearest<K, V>
+    where
+        Self: 'q,
+        K: 'q,
+        V: 'q;
+
+    fn k_nearest<'q>(&'q self, key: &K, k: usize) -> Self::KNearest<'q> {
+        let mut candidates = Vec::new();
+        self.for_each_shard(|shard| {
+            candidates.extend(
+                shard
+                    .k_nearest(key, k)
+                    .map(|e| (self.metric.distance(e.key(), key), to_owned(&e))),
+            );
+        });
+
+        // each shard contributes up to `k` candidates, so keep only the best `k` of them all,
+        // sorted from farthest to nearest since `ShardedNearest` pops from the back
+        candidates.sort_unstable_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(Ordering::Equal));
+        let n_extra = candidates.len().saturating_sub(k);
+        ShardedNearest(candidates.drain(n_extra..).map(|(_, e)| e).collect())
+    }
+}
+
+impl<K, V, N, M> RemoveNearestNeighborsMap<K, V> for ShardedMap<K, V, N, M>
+where
+    N: RemoveNearestNeighborsMap<K, V>,
+    M: Metric<K>,
+    K: Clone,
+    V: Clone,
+{
+    fn remove(&mut self, key: &K, value: &V) -> bool {
+        self.shards
+            .iter_mut()
+            .any(|shard| shard.get_mut().expect(POISONED).remove(key, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+    use std::{sync::Mutex, thread};
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    use super::*;
+    use crate::{
+        metric::{Euclidean, SquaredEuclidean},
+        nn::{GnatMap, KdTreeMap},
+        sample::{Rectangle, Sample},
+        space::{Interpolate, Vector},
+    };
+
+    type Map = ShardedMap<
+        Vector<3>,
+        usize,
+        KdTreeMap<Vector<3>, usize, SquaredEuclidean>,
+        SquaredEuclidean,
+    >;
+
+    fn new_map() -> Map {
+        ShardedMap::new(4, SquaredEuclidean, || KdTreeMap::new(SquaredEuclidean))
+    }
+
+    #[test]
+    fn get_empty() {
+        let map = new_map();
+        let q = Vector::new([0.0; 3]);
+        assert_eq!(map.nearest(&q), None);
+        assert_eq!(map.nearest_within_r(&q, 1.0).count(), 0);
+        assert_eq!(map.k_nearest(&q, 3).count(), 0);
+    }
+
+    /// A Euclidean metric which borrows a counter of how many times it has been evaluated.
+    #[derive(Clone, Copy)]
+    struct Counting<'a>(&'a Cell<usize>);
+
+    impl Metric<Vector<2>> for Counting<'_> {
+        type Distance = f64;
+        fn distance(&self, c1: &Vector<2>, c2: &Vector<2>) -> f64 {
+            self.0.set(self.0.get() + 1);
+            Euclidean.distance(c1, c2)
+        }
+    }
+
+    #[test]
+    fn borrowed_metric() {
+        let n_evals = Cell::new(0);
+        let metric = Counting(&n_evals);
+        let mut map = ShardedMap::new(3, metric, || GnatMap::new(metric));
+        for i in 0..10u8 {
+            map.insert(Vector::new([f64::from(i), 0.0]), i);
+        }
+        assert_eq!(map.nearest(&Vector::new([4.2, 0.0])).unwrap().value, 4);
+        assert_eq!(
+            map.nearest_within_r(&Vector::new([4.2, 0.0]), 1.0).count(),
+            2
+        );
+        assert!(n_evals.get() > 0);
+    }
+
+    #[test]
+    fn poisoned_shard_panics() {
+        let map = new_map();
+        map.insert_shared(Vector::new([0.0; 3]), 0);
+        // simulate a writer panicking partway through modifying a shard
+        let writer = thread::scope(|s| {
+            s.spawn(|| {
+                let _guard = map.shards[1].write().unwrap();
+                panic!("writer failed");
+            })
+            .join()
+        });
+        assert!(writer.is_err());
+
+        let q = Vector::new([0.0; 3]);
+        let query = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| map.nearest(&q)));
+        assert!(query.is_err());
+    }
+
+    #[test]
+    fn parallel_rrt() {
+        const N_THREADS: usize = 4;
+        const N_PER_THREAD: usize = 500;
+        const STEP: f64 = 0.5;
+        let region = Rectangle {
+            min: Vector::new([-10.0; 3]),
+            max: Vector::new([10.0; 3]),
+        };
+
+        // Each worker queries the map for the nearest neighbor of a new sample, records the new
+        // node in shared storage whose indices serve as the map's values, and then publishes the
+        // node to the other workers by inserting it into the map.
+        // A node must be recorded before it is inserted, since other workers may find it as soon
+        // as it is in the map.
+        // Each node is stored with the ID of its parent, and the root is its own parent.
+        let root = Vector::new([0.0; 3]);
+        let nodes = Mutex::new(vec![(root, 0)]);
+        let map = new_map();
+        map.insert_shared(root, 0);
+        let mut rng = ChaCha20Rng::seed_from_u64(2707);
+        thread::scope(|s| {
+            for _ in 0..N_THREADS {
+                let (map, nodes, region) = (&map, &nodes, &region);
+                let mut rng = ChaCha20Rng::from_rng(&mut rng).unwrap();
+                s.spawn(move || {
+                    for _ in 0..N_PER_THREAD {
+                        let target: Vector<3> = region.sample(&mut rng);
+                        let parent = map.nearest(&target).unwrap();
+                        let new = parent
+                            .key
+                            .interpolate(&target, STEP)
+                            .next()
+                            .unwrap_or(target);
+                        let id = {
+                            let mut nodes = nodes.lock().unwrap();
+                            nodes.push((new, parent.value));
+                            nodes.len() - 1
+                        };
+                        map.insert_shared(new, id);
+                    }
+                });
+            }
+        });
+
+        let nodes = nodes.into_inner().unwrap();
+        assert_eq!(nodes.len(), 1 + N_THREADS * N_PER_THREAD);
+        for (id, &(q, parent)) in nodes.iter().enumerate().skip(1) {
+            // a parent must have been recorded before any of its children
+            assert!(parent < id);
+            assert!(Euclidean.distance(&nodes[parent].0, &q) <= STEP + 1e-9);
+            assert_eq!(map.nearest(&q).unwrap().key, q);
+        }
+    }
+
+    #[test]
+    fn parallel_insert() {
+        const N_THREADS: usize = 4;
+        const N_PER_THREAD: usize = 500;
+        let region = Rectangle {
+            min: Vector::new([-10.0; 3]),
+            max: Vector::new([10.0; 3]),
+        };
+        let mut rng = ChaCha20Rng::seed_from_u64(2707);
+        let points: Vec<Vector<3>> = (0..N_THREADS * N_PER_THREAD)
+            .map(|_| region.sample(&mut rng))
+            .collect();
+
+        let map = new_map();
+        thread::scope(|s| {
+            for (t, chunk) in points.chunks(N_PER_THREAD).enumerate() {
+                let map = &map;
+                s.spawn(move || {
+                    for (i, &pt) in chunk.iter().enumerate() {
+                        let id = t * N_PER_THREAD + i;
+                        map.insert_shared(pt, id);
+                        // an entry is visible to queries as soon as it has been inserted
+                        let e = map.nearest(&pt).unwrap();
+                        assert!(SquaredEuclidean.distance(&e.key, &pt) <= 0.0);
+                    }
+                });
+            }
+        });
+
+        for _ in 0..100 {
+            let q: Vector<3> = region.sample(&mut rng);
+            let mut bf_dists: Vec<f64> = points
+                .iter()
+                .map(|p| SquaredEuclidean.distance(p, &q))
+                .collect();
+            bf_dists.sort_unstable_by(f64::total_cmp);
+
+            let nearest = map.nearest(&q).unwrap();
+            assert_eq!(points[nearest.value], nearest.key);
+            assert!((SquaredEuclidean.distance(&nearest.key, &q) - bf_dists[0]).abs() < 1e-9);
+
+            let n_within = bf_dists.iter().filter(|&&d| d <= 9.0).count();
+            assert_eq!(map.nearest_within_r(&q, 9.0).count(), n_within);
+
+            let map_dists: Vec<f64> = map
+                .k_nearest(&q, 10)
+                .map(|e| SquaredEuclidean.distance(&e.key, &q))
+                .collect();
+            assert_eq!(map_dists, bf_dists[..10]);
+        }
+
+        assert!(map.remove_shared(&points[3], &3));
+        assert!(!map.remove_shared(&points[3], &3));
+        assert_ne!(map.nearest(&points[3]).unwrap().value, 3);
+        assert_eq!(
+            map.into_shards()
+                .iter()
+                .map(|shard| shard.nearest_within_r(&Vector::new([0.0; 3]), 1e9).count())
+                .sum::<usize>(),
+            points.len() - 1
+        );
+    }
+}
@@ -0,0 +1,350 @@
+//! Samplers which focus on the region that could improve on a known path.
+
+use core::{array, cell::Cell};
+
+use num_traits::{Float, FloatConst, NumCast};
+use rand::{distributions::uniform::SampleUniform, Rng};
+
+use crate::space::Vector;
+
+use super::{standard_normal, Rectangle, Sample};
+
+/// The number of attempts the sampler makes to find a sample within both `bounds` and the
+/// spheroid before falling back to a sample between the foci.
+const MAX_ATTEMPTS: usize = 1000;
+
+/// A sampler which draws only configurations that could improve on a known path.
+///
+/// Once a path of cost `c` between `start` and `goal` is known, only configurations `x` such that
+/// `|x - start| + |x - goal| ≤ c` (measured by Euclidean distance) can be part of a shorter path.
+/// These configurations form a prolate hyperspheroid with foci at `start` and `goal`, which this
+/// sampler samples uniformly.
+/// Samples outside of `bounds` are rejected.
+///
+/// Until a cost is provided with [`Informed::set_cost`], this sampler samples uniformly from
+/// `bounds`.
+/// Since the cost may be updated through a shared reference, a planner may shrink the sampled
+/// region as it finds better paths.
+///
+/// When the spheroid is larger than `bounds` (for instance, just after a poor first solution),
+/// this sampler instead samples `bounds` and rejects configurations which cannot improve the
+/// path.
+/// If neither approach finds a sample within a fixed number of attempts (as when the spheroid
+/// barely intersects `bounds`), it returns a configuration on the segment between `start` and
+/// `goal`, clamped into `bounds`.
+/// When `start` and `goal` are within `bounds`, the clamp has no effect and the configuration
+/// always satisfies the cost bound.
+///
+/// # Citation
+///
+/// ```bibtex
+/// @inproceedings{gammell2014informed,
+///   title={Informed RRT*: Optimal sampling-based path planning focused via direct sampling of an
+///          admissible ellipsoidal heuristic},
+///   author={Gammell, Jonathan D and Srinivasa, Siddhartha S and Barfoot, Timothy D},
+///   booktitle={2014 IEEE/RSJ International Conference on Intelligent Robots and Systems},
+///   pages={2997--3004},
+///   year={2014},
+///   organization={IEEE}
+/// }
+/// ```
+pub struct Informed<const N: usize, T = f64> {
+    bounds: Rectangle<Vector<N, T>>,
+    start: Vector<N, T>,
+    goal: Vector<N, T>,
+    /// The midpoint between the foci.
+    center: Vector<N, T>,
+    /// The unit vector from `start` to `goal`, or the first basis vector if they coincide.
+    axis: Vector<N, T>,
+    /// The distance between `start` and `goal`, which is the least possible cost.
+    min_cost: T,
+    /// The cost of the best known path.
+    cost: Cell<T>,
+}
+
+impl<const N: usize, T: Float> Informed<N, T> {
+    /// Construct a new informed sampler for paths from `start` to `goal` within `bounds`.
+    /// No path is initially known, so the sampler will sample all of `bounds` until
+    /// [`Informed::set_cost`] is called.
+    pub fn new(start: &Vector<N, T>, goal: &Vector<N, T>, bounds: Rectangle<Vector<N, T>>) -> Self {
+        let two = T::one() + T::one();
+        let diff: [T; N] = array::from_fn(|i| goal[i] - start[i]);
+        let min_cost = diff.iter().fold(T::zero(), |a, &x| a + x * x).sqrt();
+        let axis = if min_cost.is_zero() {
+            array::from_fn(|i| if i == 0 { T::one() } else { T::zero() })
+        } else {
+            diff.map(|x| x / min_cost)
+        };
+        Self {
+            bounds,
+            start: *start,
+            goal: *goal,
+            center: Vector::new(array::from_fn(|i| (start[i] + goal[i]) / two)),
+            axis: Vector::new(axis),
+            min_cost,
+            cost: Cell::new(T::infinity()),
+        }
+    }
+
+    /// Get the cost of the best known path, or infinity if no path is known.
+    pub const fn cost(&self) -> T {
+        self.cost.get()
+    }
+
+    /// Set the cost of the best known path.
+    /// Subsequent samples will only be drawn from configurations which could be part of a path
+    /// with cost at most `cost`.
+    ///
+    /// No path can be shorter than the distance between `start` and `goal`, so smaller costs are
+    /// raised to that distance.
+    pub fn set_cost(&self, cost: T) {
+        self.cost.set(cost.max(self.min_cost));
+    }
+
+    /// Get the cost of the shortest path from `start` to `goal` through `x`.
+    fn path_cost(&self, x: &Vector<N, T>) -> T {
+        let dist = |a: &Vector<N, T>| {
+            x.iter()
+                .zip(a.iter())
+                .fold(T::zero(), |acc, (&x, &a)| acc + (x - a) * (x - a))
+                .sqrt()
+        };
+        dist(&self.start) + dist(&self.goal)
+    }
+
+    /// Determine whether `x` lies within `bounds`.
+    fn in_bounds(&self, x: &Vector<N, T>) -> bool {
+        x.iter()
+            .enumerate()
+            .all(|(i, &x)| self.bounds.min[i] <= x && x <= self.bounds.max[i])
+    }
+}
+
+impl<const N: usize, T> Informed<N, T>
+where
+    T: Float + FloatConst + SampleUniform,
+{
+    /// Get the radius of the spheroid for `cost` perpendicular to the line between the foci.
+    fn transverse_radius(&self, cost: T) -> T {
+        let two = T::one() + T::one();
+        (cost * cost - self.min_cost * self.min_cost)
+            .max(T::zero())
+            .sqrt()
+            / two
+    }
+
+    /// Determine whether the spheroid for `cost` has a greater measure than `bounds`.
+    fn spheroid_is_larger(&self, cost: T) -> bool {
+        // the measure of the unit ball, computed by the recurrence V(n) = V(n - 2) * 2π / n
+        let two = T::one() + T::one();
+        let mut ball = [T::one(), two];
+        let mut n = T::one();
+        for _ in 1..N {
+            n = n + T::one();
+            ball = [ball[1], ball[0] * T::TAU() / n];
+        }
+        let radius = self.transverse_radius(cost);
+        let spheroid = (1..N).fold(ball[1] * cost / two, |v, _| v * radius);
+        let rectangle = (0..N).fold(T::one(), |v, i| {
+            v * (self.bounds.max[i] - self.bounds.min[i])
+        });
+        spheroid >= rectangle
+    }
+
+    /// Draw a sample uniformly from the spheroid for `cost`, which may not be within `bounds`.
+    fn sample_spheroid<RNG: Rng>(&self, cost: T, rng: &mut RNG) -> Vector<N, T> {
+        let two = T::one() + T::one();
+        let transverse_radius = self.transverse_radius(cost);
+        let dim = <T as NumCast>::from(N).expect("dimension must be representable");
+
+        // sample uniformly from the unit ball
+        let (dir, norm) = loop {
+            let dir: [T; N] = array::from_fn(|_| standard_normal(rng));
+            let norm = dir.iter().fold(T::zero(), |a, &x| a + x * x).sqrt();
+            if !norm.is_zero() {
+                break (dir, norm);
+            }
+        };
+        let r = rng.gen_range(T::zero()..T::one()).powf(dim.recip()) / norm;
+
+        // stretch the ball into a spheroid with its major axis along the first basis vector
+        let y: [T; N] = array::from_fn(|i| {
+            dir[i]
+                * r
+                * if i == 0 {
+                    cost / two
+                } else {
+                    transverse_radius
+                }
+        });
+
+        // reflect the first basis vector onto `axis` so that the spheroid is aligned with the foci
+        let v: [T; N] = array::from_fn(|i| {
+            if i == 0 {
+                T::one() - self.axis[i]
+            } else {
+                -self.axis[i]
+            }
+        });
+        let v_sq = v.iter().fold(T::zero(), |a, &x| a + x * x);
+        if v_sq <= T::epsilon() {
+            Vector::new(array::from_fn(|i| self.center[i] + y[i]))
+        } else {
+            let proj = two * v.iter().zip(&y).fold(T::zero(), |a, (&v, &y)| a + v * y) / v_sq;
+            Vector::new(array::from_fn(|i| self.center[i] + y[i] - proj * v[i]))
+        }
+    }
+}
+
+impl<const N: usize, T, RNG> Sample<Vector<N, T>, RNG> for Informed<N, T>
+where
+    T: Float + FloatConst + SampleUniform,
+    RNG: Rng,
+{
+    fn sample(&self, rng: &mut RNG) -> Vector<N, T> {
+        let cost = self.cost.get();
+        if cost.is_infinite() {
+            return self.bounds.sample(rng);
+        }
+
+        if self.spheroid_is_larger(cost) {
+            for _ in 0..MAX_ATTEMPTS {
+                let x = self.bounds.sample(rng);
+                if self.path_cost(&x) <= cost {
+                    return x;
+                }
+            }
+        } else {
+            for _ in 0..MAX_ATTEMPTS {
+                let x = self.sample_spheroid(cost, rng);
+                if self.in_bounds(&x) {
+                    return x;
+                }
+            }
+        }
+
+        // every point between the foci is on a shortest path
+        let t = rng.gen_range(T::zero()..=T::one());
+        Vector::new(array::from_fn(|i| {
+            (self.start[i] + t * (self.goal[i] - self.start[i]))
+                .max(self.bounds.min[i])
+                .min(self.bounds.max[i])
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    use super::*;
+    use crate::metric::{Euclidean, Metric};
+
+    #[test]
+    fn within_spheroid() {
+        let start = Vector::new([0.0, 0.0, 0.0]);
+        let goal = Vector::new([1.0, 2.0, -1.0]);
+        let bounds = Rectangle {
+            min: Vector::new([-1.0, -1.0, -2.0]),
+            max: Vector::new([2.0, 2.5, 1.0]),
+        };
+        let sampler = Informed::new(&start, &goal, bounds);
+        let mut rng = ChaCha20Rng::seed_from_u64(2707);
+
+        let min_cost = Euclidean.distance(&start, &goal);
+        let mut costs = Vec::new();
+        for cost in [
+            f64::INFINITY,
+            2.0 * min_cost,
+            1.2 * min_cost,
+            1.01 * min_cost,
+        ] {
+            sampler.set_cost(cost);
+            let mut max_cost: f64 = 0.0;
+            for _ in 0..1000 {
+                let x = sampler.sample(&mut rng);
+                assert!(sampler.in_bounds(&x));
+                let path_cost = Euclidean.distance(&start, &x) + Euclidean.distance(&x, &goal);
+                assert!(path_cost <= cost + 1e-9);
+                max_cost = max_cost.max(path_cost);
+            }
+            costs.push(max_cost);
+        }
+
+        // the samples should fill the spheroid, not just its center
+        assert!(costs[1] > 1.9 * min_cost);
+        assert!(costs[2] > 1.15 * min_cost);
+        assert!(costs[1] < costs[0]);
+    }
+
+    #[test]
+    fn uniform_in_spheroid() {
+        let start = Vector::new([1.0, 1.0]);
+        let goal = Vector::new([3.0, 3.0]);
+        let bounds = Rectangle {
+            min: Vector::new([-10.0; 2]),
+            max: Vector::new([10.0; 2]),
+        };
+        let sampler = Informed::new(&start, &goal, bounds);
+        sampler.set_cost(4.0);
+        let mut rng = ChaCha20Rng::seed_from_u64(2707);
+
+        // compare the number of samples nearer to the start and to the goal, and inside and
+        // outside of the spheroid with half the radii, which contains a quarter of its area
+        let (mut n_start, mut n_inner) = (0, 0);
+        for _ in 0..4000 {
+            let x = sampler.sample(&mut rng);
+            if Euclidean.distance(&x, &start) < Euclidean.distance(&x, &goal) {
+                n_start += 1;
+            }
+            let u = (x[0] - 2.0 + x[1] - 2.0) / 2f64.sqrt() / 2.0;
+            let v = (x[1] - x[0]) / 2f64.sqrt() / 2f64.sqrt();
+            if u * u + v * v <= 0.25 {
+                n_inner += 1;
+            }
+        }
+        assert!((1800..2200).contains(&n_start));
+        assert!((900..1100).contains(&n_inner));
+    }
+
+    #[test]
+    fn degenerate_costs() {
+        let start = Vector::new([0.0, 0.0, 0.0]);
+        let goal = Vector::new([1.0, 0.0, 0.0]);
+        let mut rng = ChaCha20Rng::seed_from_u64(2707);
+
+        // the spheroid is far larger than the bounds, so the bounds are sampled instead
+        let small = Rectangle {
+            min: Vector::new([0.0, -0.1, -0.1]),
+            max: Vector::new([1.0, 0.1, 0.1]),
+        };
+        let sampler = Informed::new(&start, &goal, small);
+        sampler.set_cost(100.0);
+        assert!(sampler.spheroid_is_larger(100.0));
+        for _ in 0..100 {
+            assert!(sampler.in_bounds(&sampler.sample(&mut rng)));
+        }
+
+        // costs below the distance between the foci are raised to it
+        sampler.set_cost(0.5);
+        assert!((sampler.cost() - 1.0).abs() < 1e-9);
+        for _ in 0..100 {
+            assert!(sampler.path_cost(&sampler.sample(&mut rng)) <= 1.0 + 1e-9);
+        }
+
+        // the spheroid does not intersect the bounds, so sampling must still terminate within
+        // the bounds
+        let far = Rectangle {
+            min: Vector::new([5.0; 3]),
+            max: Vector::new([6.0; 3]),
+        };
+        let sampler = Informed::new(&start, &goal, far);
+        sampler.set_cost(1.5);
+        for _ in 0..10 {
+            assert!(sampler.in_bounds(&sampler.sample(&mut rng)));
+        }
+    }
+}
@@ -0,0 +1,437 @@
+//! Deterministic low-dispersion samplers.
+
+use alloc::vec::Vec;
+use core::{
+    array,
+    cell::{Cell, RefCell},
+};
+
+use num_traits::{Float, FloatConst, NumCast};
+
+use crate::space::{Angle, Pose2d, Vector};
+
+use super::{Everywhere, Rectangle, Sample};
+
+/// The primes used as bases for each dimension of a [`Halton`] sequence.
+const PRIMES: [usize; 32] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+    101, 103, 107, 109, 113, 127, 131,
+];
+
+/// The primitive polynomials and initial direction numbers for each dimension of a [`Sobol`]
+/// sequence after the first, as `(s, a, m)`: `s` is the degree of the polynomial, `a` encodes its
+/// interior coefficients, and `m` holds the first `s` direction numbers.
+const SOBOL_PARAMS: [(usize, u32, [u32; 6]); 15] = [
+    (1, 0, [1, 0, 0, 0, 0, 0]),
+    (2, 1, [1, 3, 0, 0, 0, 0]),
+    (3, 1, [1, 3, 1, 0, 0, 0]),
+    (3, 2, [1, 1, 1, 0, 0, 0]),
+    (4, 1, [1, 1, 3, 3, 0, 0]),
+    (4, 4, [1, 3, 5, 13, 0, 0]),
+    (5, 2, [1, 1, 5, 5, 17, 0]),
+    (5, 4, [1, 1, 5, 5, 5, 0]),
+    (5, 7, [1, 1, 7, 11, 19, 0]),
+    (5, 11, [1, 1, 5, 1, 1, 0]),
+    (5, 13, [1, 1, 1, 3, 11, 0]),
+    (5, 14, [1, 3, 5, 5, 31, 0]),
+    (6, 1, [1, 3, 3, 9, 7, 49]),
+    (6, 13, [1, 1, 1, 15, 21, 21]),
+    (6, 16, [1, 3, 1, 13, 27, 49]),
+];
+
+/// The direction numbers `m` for each dimension of a [`Sobol`] sequence, such that the `k`th
+/// direction number of dimension `d` is `SOBOL_DIRECTIONS[d][k] / 2^(k + 1)`.
+const SOBOL_DIRECTIONS: [[u32; 32]; SOBOL_PARAMS.len() + 1] = sobol_directions();
+
+/// Compute the direction numbers for every dimension of a [`Sobol`] sequence from
+/// [`SOBOL_PARAMS`] using the recurrence given by each primitive polynomial.
+const fn sobol_directions() -> [[u32; 32]; SOBOL_PARAMS.len() + 1] {
+    let mut directions = [[1u32; 32]; SOBOL_PARAMS.len() + 1];
+    let mut dim = 1;
+    while dim < directions.len() {
+        let (s, a, init) = SOBOL_PARAMS[dim - 1];
+        let m = &mut directions[dim];
+        let mut idx = 0;
+        while idx < s {
+            m[idx] = init[idx];
+            idx += 1;
+        }
+        while idx < 32 {
+            let mut next = m[idx - s] ^ (m[idx - s] << s);
+            let mut bit = 1;
+            while bit < s {
+                if (a >> (s - 1 - bit)) & 1 == 1 {
+                    next ^= m[idx - bit] << bit;
+                }
+                bit += 1;
+            }
+            m[idx] = next;
+            idx += 1;
+        }
+        dim += 1;
+    }
+    directions
+}
+
+/// A region which can be sampled by mapping points from the unit hypercube onto it.
+///
+/// This is used by the deterministic samplers ([`Halton`], [`Sobol`], and [`Lattice`]) to turn
+/// their sequences of points in the unit hypercube into configurations of type `C`.
+pub trait MapUnitCube<C> {
+    /// The number of dimensions of the hypercube.
+    const DIM: usize;
+
+    /// Map a point in the unit hypercube, whose coordinate along axis `i` is `u(i)`, to a
+    /// configuration in this region.
+    fn map_unit_cube(&self, u: impl Fn(usize) -> f64) -> C;
+}
+
+/// Convert a coordinate in the unit interval to a `T`.
+fn cast<T: Float>(u: f64) -> T {
+    <T as NumCast>::from(u).expect("unit coordinate must be representable")
+}
+
+impl<const N: usize, T: Float> MapUnitCube<Vector<N, T>> for Rectangle<Vector<N, T>> {
+    const DIM: usize = N;
+
+    fn map_unit_cube(&self, u: impl Fn(usize) -> f64) -> Vector<N, T> {
+        Vector::new(array::from_fn(|i| {
+            self.min[i] + (self.max[i] - self.min[i]) * cast(u(i))
+        }))
+    }
+}
+
+impl<T: Float + FloatConst> MapUnitCube<Angle<T>> for Everywhere {
+    const DIM: usize = 1;
+
+    fn map_unit_cube(&self, u: impl Fn(usize) -> f64) -> Angle<T> {
+        let theta = cast::<T>(u(0)) * T::TAU();
+        // rounding may push the largest coordinates up to a full turn
+        Angle::new(if theta < T::TAU() { theta } else { T::zero() })
+    }
+}
+
+impl<T: Float + FloatConst> MapUnitCube<Pose2d<T>> for Rectangle<Vector<2, T>> {
+    const DIM: usize = 3;
+
+    fn map_unit_cube(&self, u: impl Fn(usize) -> f64) -> Pose2d<T> {
+        Pose2d {
+            position: self.map_unit_cube(&u),
+            angle: Everywhere.map_unit_cube(|_| u(2)),
+        }
+    }
+}
+
+/// Implement the constructors and index accessors for a deterministic sampler.
+macro_rules! sequence_impl {
+    ($s: ident $(, $field: ident: $init: expr)*) => {
+        impl<R> $s<R> {
+            /// Construct a new sampler over `region`, starting from the beginning of its
+            /// sequence.
+            pub const fn new(region: R) -> Self {
+                Self::with_index(region, 0)
+            }
+
+            /// Construct a new sampler over `region` whose next sample will be the `index`th
+            /// element of its sequence.
+            pub const fn with_index(region: R, index: usize) -> Self {
+                Self {
+                    region,
+                    index: Cell::new(index),
+                    $($field: $init,)*
+                }
+            }
+
+            /// Get the index in the sequence of the next sample.
+            pub const fn index(&self) -> usize {
+                self.index.get()
+            }
+
+            /// Get the index of the next sample and advance the sequence.
+            fn advance(&self) -> usize {
+                let i = self.index.get();
+                self.index.set(i + 1);
+                i
+            }
+        }
+    };
+}
+
+#[derive(Clone, Debug)]
+/// A deterministic sampler which draws from the Halton sequence over `region`.
+///
+/// Each sample advances the sampler's own index, so the random number generator passed to
+/// [`Sample::sample`] is ignored and repeated runs produce identical samples.
+///
+/// `region` may be a [`Rectangle`] of [`Vector`]s (sampling [`Vector`]s or [`Pose2d`]s) or
+/// [`Everywhere`] (sampling [`Angle`]s), or anything else implementing [`MapUnitCube`].
+/// Halton sequences of more than 32 dimensions are not supported.
+///
+/// # Citation
+///
+/// ```bibtex
+/// @article{halton1960efficiency,
+///   title={On the efficiency of certain quasi-random sequences of points in evaluating
+///          multi-dimensional integrals},
+///   author={Halton, John H},
+///   journal={Numerische Mathematik},
+///   volume={2},
+///   number={1},
+///   pages={84--90},
+///   year={1960},
+///   publisher={Springer}
+/// }
+/// ```
+pub struct Halton<R> {
+    /// The region to be sampled.
+    pub region: R,
+    index: Cell<usize>,
+}
+
+#[derive(Clone, Debug)]
+/// A deterministic sampler which draws from the Sobol sequence over `region`.
+///
+/// Each sample advances the sampler's own index, so the random number generator passed to
+/// [`Sample::sample`] is ignored and repeated runs produce identical samples.
+///
+/// `region` may be a [`Rectangle`] of [`Vector`]s (sampling [`Vector`]s or [`Pose2d`]s) or
+/// [`Everywhere`] (sampling [`Angle`]s), or anything else implementing [`MapUnitCube`].
+/// Sobol sequences of more than 16 dimensions are not supported.
+///
+/// # Citation
+///
+/// ```bibtex
+/// @article{joe2008constructing,
+///   title={Constructing Sobol sequences with better two-dimensional projections},
+///   author={Joe, Stephen and Kuo, Frances Y},
+///   journal={SIAM Journal on Scientific Computing},
+///   volume={30},
+///   number={5},
+///   pages={2635--2654},
+///   year={2008},
+///   publisher={SIAM}
+/// }
+/// ```
+pub struct Sobol<R> {
+    /// The region to be sampled.
+    pub region: R,
+    index: Cell<usize>,
+}
+
+#[derive(Clone, Debug)]
+/// A deterministic sampler which draws from a rank-1 Kronecker lattice over `region`.
+///
+/// The `i`th point of the lattice along axis `j` is the fractional part of `1/2 + i α_j`, where
+/// `α_j = φ^-(j+1)` and `φ` is the generalized golden ratio for the dimension of `region`.
+/// Unlike [`Halton`] and [`Sobol`], this sequence supports any number of dimensions.
+///
+/// Each sample advances the sampler's own index, so the random number generator passed to
+/// [`Sample::sample`] is ignored and repeated runs produce identical samples.
+///
+/// `region` may be a [`Rectangle`] of [`Vector`]s (sampling [`Vector`]s or [`Pose2d`]s) or
+/// [`Everywhere`] (sampling [`Angle`]s), or anything else implementing [`MapUnitCube`].
+pub struct Lattice<R> {
+    /// The region to be sampled.
+    pub region: R,
+    index: Cell<usize>,
+    /// The step `α_j` along each axis, computed on the first sample for the dimension of the
+    /// sampled configurations.
+    alpha: RefCell<Vec<f64>>,
+}
+
+sequence_impl!(Halton);
+sequence_impl!(Sobol);
+sequence_impl!(Lattice, alpha: RefCell::new(Vec::new()));
+
+/// Compute the radical inverse of `i` in base `base`, which reverses the digits of `i` about the
+/// radix point.
+#[expect(clippy::cast_precision_loss)]
+fn radical_inverse(mut i: usize, base: usize) -> f64 {
+    let inv_base = 1.0 / base as f64;
+    let mut scale = inv_base;
+    let mut x = 0.0;
+    while i > 0 {
+        x = Float::mul_add((i % base) as f64, scale, x);
+        i /= base;
+        scale *= inv_base;
+    }
+    x
+}
+
+/// Compute coordinate `axis` of the `i`th point of the Sobol sequence.
+fn sobol(i: usize, axis: usize) -> f64 {
+    let m = &SOBOL_DIRECTIONS[axis];
+    // the `k`th direction number is `m[k] / 2^(k + 1)`, stored as a 32-bit binary fraction
+    let x = (0..32)
+        .filter(|&k| (i >> k) & 1 == 1)
+        .fold(0u32, |x, k| x ^ (m[k] << (31 - k)));
+    <f64 as From<u32>>::from(x) / 4_294_967_296.0
+}
+
+/// Compute the generalized golden ratio for `dim` dimensions: the positive root of
+/// `x^(dim + 1) = x + 1`.
+#[expect(clippy::cast_precision_loss)]
+fn golden_ratio(dim: usize) -> f64 {
+    let exponent = 1.0 / (dim + 1) as f64;
+    (0..64).fold(2.0, |phi: f64, _| Float::powf(1.0 + phi, exponent))
+}
+
+impl<C, R, RNG> Sample<C, RNG> for Halton<R>
+where
+    R: MapUnitCube<C>,
+{
+    /// # Panics
+    ///
+    /// This function will panic if `R::DIM` is greater than 32.
+    fn sample(&self, _: &mut RNG) -> C {
+        assert!(R::DIM <= PRIMES.len(), "too many dimensions for Halton");
+        let i = self.advance();
+        self.region
+            .map_unit_cube(|axis| radical_inverse(i, PRIMES[axis]))
+    }
+}
+
+impl<C, R, RNG> Sample<C, RNG> for Sobol<R>
+where
+    R: MapUnitCube<C>,
+{
+    /// # Panics
+    ///
+    /// This function will panic if `R::DIM` is greater than 16.
+    fn sample(&self, _: &mut RNG) -> C {
+        assert!(
+            R::DIM <= SOBOL_DIRECTIONS.len(),
+            "too many dimensions for Sobol"
+        );
+        // visit points in Gray-code order, as is conventional
+        let i = self.advance();
+        let gray = i ^ (i >> 1);
+        self.region.map_unit_cube(|axis| sobol(gray, axis))
+    }
+}
+
+impl<C, R, RNG> Sample<C, RNG> for Lattice<R>
+where
+    R: MapUnitCube<C>,
+{
+    #[expect(clippy::cast_precision_loss)]
+    fn sample(&self, _: &mut RNG) -> C {
+        let mut alpha = self.alpha.borrow_mut();
+        if alpha.len() != R::DIM {
+            let phi = golden_ratio(R::DIM);
+            *alpha = (0..R::DIM)
+                .map(|axis| Float::powf(phi, -((axis + 1) as f64)))
+                .collect();
+        }
+        let i = self.advance() as f64;
+        self.region
+            .map_unit_cube(|axis| Float::fract(Float::mul_add(i, alpha[axis], 0.5)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    fn unit_square() -> Rectangle<Vector<2>> {
+        Rectangle {
+            min: Vector::new([0.0; 2]),
+            max: Vector::new([1.0; 2]),
+        }
+    }
+
+    /// Get the index of the interval of width `1 / n` which contains `x` in the unit interval.
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    fn bin(x: f64, n: usize) -> usize {
+        (x * n as f64) as usize
+    }
+
+    #[test]
+    fn halton_known_points() {
+        let sampler = Halton::with_index(unit_square(), 1);
+        let expected = [[0.5, 1.0 / 3.0], [0.25, 2.0 / 3.0], [0.75, 1.0 / 9.0]];
+        for pt in expected {
+            let c: Vector<2> = sampler.sample(&mut ());
+            assert!((c[0] - pt[0]).abs() < 1e-12);
+            assert!((c[1] - pt[1]).abs() < 1e-12);
+        }
+        assert_eq!(sampler.index(), 4);
+    }
+
+    #[test]
+    fn sobol_stratified() {
+        const LOG_N: u32 = 6;
+        let n = 1usize << LOG_N;
+        let region = Rectangle {
+            min: Vector::new([0.0; 16]),
+            max: Vector::new([1.0; 16]),
+        };
+        let sampler = Sobol::new(region);
+        let points: Vec<Vector<16>> = (0..n).map(|_| sampler.sample(&mut ())).collect();
+
+        // every prefix of 2^m points of each axis lands once in each interval of width 2^-m
+        for axis in 0..16 {
+            let mut bins = vec![false; n];
+            for pt in &points {
+                let bin = bin(pt[axis], n);
+                assert!(!bins[bin]);
+                bins[bin] = true;
+            }
+        }
+
+        // the first two axes form a (0, m, 2)-net: every box of area 2^-m contains one point
+        for log_w in 0..=LOG_N {
+            let (w, h) = (1usize << log_w, 1usize << (LOG_N - log_w));
+            let mut boxes = vec![false; n];
+            for pt in &points {
+                let id = bin(pt[0], w) * h + bin(pt[1], h);
+                assert!(!boxes[id]);
+                boxes[id] = true;
+            }
+        }
+    }
+
+    #[test]
+    fn lattice_evenly_spread() {
+        let sampler = Lattice::new(unit_square());
+        let mut counts = [[0; 10]; 10];
+        for _ in 0..1000 {
+            let c: Vector<2> = sampler.sample(&mut ());
+            counts[bin(c[0], 10)][bin(c[1], 10)] += 1;
+        }
+        for row in counts {
+            for count in row {
+                assert!((7..=13).contains(&count));
+            }
+        }
+    }
+
+    #[test]
+    fn poses_and_angles() {
+        let sampler = Halton::new(Rectangle {
+            min: Vector::new([-1.0; 2]),
+            max: Vector::new([1.0; 2]),
+        });
+        let mut total_angle = 0.0;
+        for _ in 0..100 {
+            let pose: Pose2d = sampler.sample(&mut ());
+            assert!(pose.position.iter().all(|x| (-1.0..=1.0).contains(x)));
+            total_angle += pose.angle.get();
+        }
+        // the angles should be spread evenly around the circle
+        assert!((total_angle / 100.0 - core::f64::consts::PI).abs() < 0.1);
+
+        let sampler = Sobol::new(Everywhere);
+        let angles: Vec<f64> = (0..4)
+            .map(|_| Sample::<Angle, ()>::sample(&sampler, &mut ()).get())
+            .collect();
+        let tau = core::f64::consts::TAU;
+        assert_eq!(angles, [0.0, tau / 2.0, tau * 3.0 / 4.0, tau / 4.0]);
+    }
+}
@@ -0,0 +1,172 @@
+//! Sampling distributions for states.
+
+mod informed;
+mod low_dispersion;
+mod narrow;
+
+pub use informed::Informed;
+pub use low_dispersion::{Halton, Lattice, MapUnitCube, Sobol};
+pub use narrow::{BridgeTest, Gaussian, ObstacleBased};
+
+use core::array;
+
+use crate::space::{Angle, DubinsPose, Orient, Pose2d, Pose3d, Product, ReedsSheppPose, Vector};
+use num_traits::{float::Float, FloatConst};
+use rand::{
+    distributions::{uniform::SampleUniform, Bernoulli, Distribution},
+    Rng,
+};
+
+/// A sampler for a configuration.
+///
+/// `C` is the type of the configuration, and `RNG` is a source of randomness.
+pub trait Sample<C, RNG> {
+    /// Sample a configuration, using `rng` as a source of randomness.
+    fn sample(&self, rng: &mut RNG) -> C;
+}
+
+impl<RNG: Rng> Sample<bool, RNG> for Bernoulli {
+    fn sample(&self, rng: &mut RNG) -> bool {
+        <Self as Distribution<bool>>::sample(self, rng)
+    }
+}
+
+/// Implement `Sample` for tuples of samplers, where each sampler `$s` samples the component `$c` at
+/// tuple index `$idx`.
+macro_rules! product_impl {
+    ($(($c: ident, $s: ident, $idx: tt)),+) => {
+        impl<RNG, $($c, $s),+> Sample<($($c,)+), RNG> for ($($s,)+)
+        where
+            $($s: Sample<$c, RNG>,)+
+        {
+            fn sample(&self, rng: &mut RNG) -> ($($c,)+) {
+                ($(self.$idx.sample(rng),)+)
+            }
+        }
+    };
+}
+
+product_impl!((A, SA, 0), (B, SB, 1));
+product_impl!((A, SA, 0), (B, SB, 1), (C, SC, 2));
+product_impl!((A, SA, 0), (B, SB, 1), (C, SC, 2), (D, SD, 3));
+
+impl<C, S, RNG> Sample<Product<C>, RNG> for Product<S>
+where
+    S: Sample<C, RNG>,
+{
+    fn sample(&self, rng: &mut RNG) -> Product<C> {
+        Product(self.0.sample(rng))
+    }
+}
+
+/// Draw a sample from the standard normal distribution using the Box-Muller transform.
+fn standard_normal<T, RNG>(rng: &mut RNG) -> T
+where
+    T: Float + FloatConst + SampleUniform,
+    RNG: Rng,
+{
+    // `u1` must be nonzero so that its logarithm is finite
+    let u1 = T::one() - rng.gen_range(T::zero()..T::one());
+    let u2 = rng.gen_range(T::zero()..T::one());
+    (-(T::one() + T::one()) * u1.ln()).sqrt() * (T::TAU() * u2).cos()
+}
+
+/// A rectangular prism of values of `T`.
+/// When used as [`Sample`], this rectangle inclusively samples all values.
+pub struct Rectangle<T> {
+    /// The lowest corner along all axes.
+    pub min: T,
+    /// The highest corner along all axes.
+    pub max: T,
+}
+
+/// A sampler for all possible values of a datum.
+pub struct Everywhere;
+
+impl<const N: usize, T, RNG: Rng> Sample<Vector<N, T>, RNG> for Rectangle<Vector<N, T>>
+where
+    T: Float + SampleUniform,
+{
+    fn sample(&self, rng: &mut RNG) -> Vector<N, T> {
+        Vector::new(array::from_fn(|i| rng.gen_range(self.min[i]..=self.max[i])))
+    }
+}
+
+impl<T, RNG: Rng> Sample<Angle<T>, RNG> for Everywhere
+where
+    T: Float + FloatConst + SampleUniform,
+{
+    fn sample(&self, rng: &mut RNG) -> Angle<T> {
+        unsafe { Angle::new_unchecked(rng.gen_range(T::zero()..T::TAU())) }
+    }
+}
+
+impl<T, RNG: Rng> Sample<Pose2d<T>, RNG> for Rectangle<Vector<2, T>>
+where
+    T: FloatConst + Float + SampleUniform,
+{
+    fn sample(&self, rng: &mut RNG) -> Pose2d<T> {
+        Pose2d {
+            position: self.sample(rng),
+            angle: Everywhere.sample(rng),
+        }
+    }
+}
+
+impl<T, RNG: Rng> Sample<DubinsPose<T>, RNG> for Rectangle<Vector<2, T>>
+where
+    T: FloatConst + Float + SampleUniform,
+{
+    fn sample(&self, rng: &mut RNG) -> DubinsPose<T> {
+        DubinsPose(self.sample(rng))
+    }
+}
+
+impl<T, RNG: Rng> Sample<ReedsSheppPose<T>, RNG> for Rectangle<Vector<2, T>>
+where
+    T: FloatConst + Float + SampleUniform,
+{
+    fn sample(&self, rng: &mut RNG) -> ReedsSheppPose<T> {
+        ReedsSheppPose(self.sample(rng))
+    }
+}
+
+impl<T, RNG: Rng> Sample<Orient<T>, RNG> for Everywhere
+where
+    T: Float + FloatConst + SampleUniform,
+{
+    /// Sample an orientation uniformly from _SO_(3).
+    ///
+    /// # Citation
+    ///
+    /// ```bibtex
+    /// @incollection{shoemake1992uniform,
+    ///   title={Uniform random rotations},
+    ///   author={Shoemake, Ken},
+    ///   booktitle={Graphics Gems III},
+    ///   pages={124--132},
+    ///   year={1992},
+    ///   publisher={Elsevier}
+    /// }
+    /// ```
+    fn sample(&self, rng: &mut RNG) -> Orient<T> {
+        let u1 = rng.gen_range(T::zero()..T::one());
+        let t2 = rng.gen_range(T::zero()..T::TAU());
+        let t3 = rng.gen_range(T::zero()..T::TAU());
+        let r1 = (T::one() - u1).sqrt();
+        let r2 = u1.sqrt();
+        Orient::new(r1 * t2.sin(), r1 * t2.cos(), r2 * t3.sin(), r2 * t3.cos())
+    }
+}
+
+impl<T, RNG: Rng> Sample<Pose3d<T>, RNG> for Rectangle<Vector<3, T>>
+where
+    T: FloatConst + Float + SampleUniform,
+{
+    fn sample(&self, rng: &mut RNG) -> Pose3d<T> {
+        Pose3d {
+            position: self.sample(rng),
+            orient: Everywhere.sample(rng),
+        }
+    }
+}
@@ -0,0 +1,350 @@
+//! Samplers which concentrate samples near obstacles and in narrow passages.
+
+use core::iter;
+
+use num_traits::{Float, FloatConst};
+use rand::{distributions::uniform::SampleUniform, Rng};
+
+use crate::{space::Interpolate, valid::Validate};
+
+use super::{standard_normal, Sample};
+
+/// The number of attempts a sampler makes to find a suitable configuration before falling back
+/// to a sample from its base distribution.
+const MAX_ATTEMPTS: usize = 1000;
+
+/// The number of bisection steps used to measure the length of a bridge.
+const BISECTION_STEPS: usize = 40;
+
+#[derive(Clone, Copy, Debug)]
+/// A sampler which draws configurations near the boundaries of obstacles.
+///
+/// Each attempt draws a configuration from `base`, then a second configuration at a distance
+/// drawn from a normal distribution with standard deviation `std_dev` in the direction of a third
+/// configuration from `base`.
+/// If exactly one of the first two configurations is valid, it is the sample.
+///
+/// If no sample is found after many attempts (for instance, because there are no obstacles),
+/// this sampler returns a sample from `base` instead.
+///
+/// # Citation
+///
+/// ```bibtex
+/// @inproceedings{boor1999gaussian,
+///   title={The Gaussian sampling strategy for probabilistic roadmap planners},
+///   author={Boor, Val{\'e}rie and Overmars, Mark H and Van Der Stappen, A Frank},
+///   booktitle={Proceedings 1999 IEEE International Conference on Robotics and Automation},
+///   volume={2},
+///   pages={1018--1023},
+///   year={1999},
+///   organization={IEEE}
+/// }
+/// ```
+pub struct Gaussian<S, V, T> {
+    /// The distribution from which configurations are initially drawn.
+    pub base: S,
+    /// The validity checker which determines where obstacles are.
+    pub valid: V,
+    /// The standard deviation of the distance between each pair of configurations.
+    pub std_dev: T,
+}
+
+#[derive(Clone, Copy, Debug)]
+/// A sampler which draws configurations inside narrow passages using the bridge test.
+///
+/// Each attempt draws an invalid configuration from `base`, then a second configuration at a
+/// distance drawn from a normal distribution with standard deviation `std_dev` in the direction
+/// of a third configuration from `base`.
+/// If the second configuration is also invalid but the midpoint between the two is valid, the
+/// midpoint is the sample.
+///
+/// If no sample is found after many attempts (for instance, because there are no narrow
+/// passages), this sampler returns a sample from `base` instead.
+///
+/// # Citation
+///
+/// ```bibtex
+/// @inproceedings{hsu2003bridge,
+///   title={The bridge test for sampling narrow passages with probabilistic roadmap planners},
+///   author={Hsu, David and Jiang, Tingting and Reif, John and Sun, Zheng},
+///   booktitle={2003 IEEE International Conference on Robotics and Automation},
+///   volume={3},
+///   pages={4420--4426},
+///   year={2003},
+///   organization={IEEE}
+/// }
+/// ```
+pub struct BridgeTest<S, V, T> {
+    /// The distribution from which configurations are initially drawn.
+    pub base: S,
+    /// The validity checker which determines where obstacles are.
+    pub valid: V,
+    /// The standard deviation of the length of each bridge.
+    pub std_dev: T,
+}
+
+#[derive(Clone, Copy, Debug)]
+/// A sampler which draws valid configurations on the surfaces of obstacles.
+///
+/// Each attempt draws an invalid configuration from `base`, then walks in steps of length `step`
+/// toward a second configuration from `base`.
+/// The first valid configuration along the walk is the sample, and so lies within `step` of an
+/// obstacle.
+///
+/// If no sample is found after many attempts (for instance, because every configuration is
+/// invalid), this sampler returns a sample from `base` instead.
+///
+/// # Citation
+///
+/// ```bibtex
+/// @inproceedings{amato1998obprm,
+///   title={OBPRM: An obstacle-based PRM for 3D workspaces},
+///   author={Amato, Nancy M and Bayazit, O Burchan and Dale, Lucia K and Jones, Christopher and
+///           Vallejo, Daniel},
+///   booktitle={Robotics: The Algorithmic Perspective},
+///   pages={155--168},
+///   year={1998},
+///   publisher={AK Peters}
+/// }
+/// ```
+pub struct ObstacleBased<S, V, T> {
+    /// The distribution from which configurations are initially drawn.
+    pub base: S,
+    /// The validity checker which determines where obstacles are.
+    pub valid: V,
+    /// The length of each step taken away from an obstacle.
+    pub step: T,
+}
+
+/// Draw a sample from the absolute value of a normal distribution with mean zero and standard
+/// deviation `std_dev`.
+fn half_normal<T, RNG>(std_dev: T, rng: &mut RNG) -> T
+where
+    T: Float + FloatConst + SampleUniform,
+    RNG: Rng,
+{
+    std_dev * standard_normal::<T, _>(rng).abs()
+}
+
+/// Get the configuration at distance `dist` from `start` in the direction of `end`, or `end` if
+/// it is closer than `dist`.
+fn toward<C>(start: &C, end: &C, dist: C::Distance) -> C
+where
+    C: Interpolate + Clone,
+{
+    start
+        .interpolate(end, dist)
+        .next()
+        .unwrap_or_else(|| end.clone())
+}
+
+/// Find the distance between `start` and `end`, which must be at most `max`, by bisecting on the
+/// radius at which interpolating between them takes no intermediate steps.
+fn separation<C, T>(start: &C, end: &C, max: T) -> T
+where
+    C: Interpolate<Distance = T>,
+    T: Float,
+{
+    let (mut lo, mut hi) = (T::zero(), max);
+    for _ in 0..BISECTION_STEPS {
+        let two = T::one() + T::one();
+        let mid = (lo + hi) / two;
+        if start.interpolate(end, mid).next().is_some() {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    hi
+}
+
+impl<C, RNG, S, V, T> Sample<C, RNG> for Gaussian<S, V, T>
+where
+    C: Interpolate<Distance = T> + Clone,
+    RNG: Rng,
+    S: Sample<C, RNG>,
+    V: Validate<C>,
+    T: Float + FloatConst + SampleUniform,
+{
+    fn sample(&self, rng: &mut RNG) -> C {
+        for _ in 0..MAX_ATTEMPTS {
+            let c1 = self.base.sample(rng);
+            let direction = self.base.sample(rng);
+            let c2 = toward(&c1, &direction, half_normal(self.std_dev, rng));
+            match (
+                self.valid.is_valid_configuration(&c1),
+                self.valid.is_valid_configuration(&c2),
+            ) {
+                (true, false) => return c1,
+                (false, true) => return c2,
+                _ => (),
+            }
+        }
+        self.base.sample(rng)
+    }
+}
+
+impl<C, RNG, S, V, T> Sample<C, RNG> for BridgeTest<S, V, T>
+where
+    C: Interpolate<Distance = T> + Clone,
+    RNG: Rng,
+    S: Sample<C, RNG>,
+    V: Validate<C>,
+    T: Float + FloatConst + SampleUniform,
+{
+    fn sample(&self, rng: &mut RNG) -> C {
+        for _ in 0..MAX_ATTEMPTS {
+            let c1 = self.base.sample(rng);
+            if self.valid.is_valid_configuration(&c1) {
+                continue;
+            }
+            let direction = self.base.sample(rng);
+            let length = half_normal(self.std_dev, rng);
+            let step = c1.interpolate(&direction, length).next();
+            let (c2, length) = step.map_or_else(
+                // the bridge is cut short at `direction`
+                || {
+                    let length = separation(&c1, &direction, length);
+                    (direction, length)
+                },
+                |c2| (c2, length),
+            );
+            if self.valid.is_valid_configuration(&c2) {
+                continue;
+            }
+            let midpoint = toward(&c1, &c2, length / (T::one() + T::one()));
+            if self.valid.is_valid_configuration(&midpoint) {
+                return midpoint;
+            }
+        }
+        self.base.sample(rng)
+    }
+}
+
+impl<C, RNG, S, V, T> Sample<C, RNG> for ObstacleBased<S, V, T>
+where
+    C: Interpolate<Distance = T> + Clone,
+    RNG: Rng,
+    S: Sample<C, RNG>,
+    V: Validate<C>,
+    T: Clone,
+{
+    fn sample(&self, rng: &mut RNG) -> C {
+        for _ in 0..MAX_ATTEMPTS {
+            let inside = self.base.sample(rng);
+            if self.valid.is_valid_configuration(&inside) {
+                continue;
+            }
+            let direction = self.base.sample(rng);
+            let surface = inside
+                .interpolate(&direction, self.step.clone())
+                .chain(iter::once(direction.clone()))
+                .find(|c| self.valid.is_valid_configuration(c));
+            if let Some(c) = surface {
+                return c;
+            }
+        }
+        self.base.sample(rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use core::cell::Cell;
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    use super::*;
+    use crate::{sample::Rectangle, space::Vector};
+
+    /// The unit square, divided in half by a wall of width 0.1 with a gap of width 0.02 at its
+    /// center.
+    fn valid(c: &Vector<2>) -> bool {
+        (c[0] - 0.5).abs() > 0.05 || (c[1] - 0.5).abs() < 0.01
+    }
+
+    fn square() -> Rectangle<Vector<2>> {
+        Rectangle {
+            min: Vector::new([0.0; 2]),
+            max: Vector::new([1.0; 2]),
+        }
+    }
+
+    #[test]
+    fn gaussian_near_wall() {
+        let sampler = Gaussian {
+            base: square(),
+            valid,
+            std_dev: 0.02,
+        };
+        let mut rng = ChaCha20Rng::seed_from_u64(2707);
+        for _ in 0..200 {
+            let c: Vector<2> = sampler.sample(&mut rng);
+            assert!(valid(&c));
+            assert!((c[0] - 0.5).abs() < 0.2);
+        }
+    }
+
+    #[test]
+    fn bridge_in_gap() {
+        let sampler = BridgeTest {
+            base: square(),
+            valid,
+            std_dev: 0.1,
+        };
+        let mut rng = ChaCha20Rng::seed_from_u64(2707);
+        let n_in_gap = (0..200)
+            .map(|_| sampler.sample(&mut rng))
+            .filter(|c: &Vector<2>| (c[0] - 0.5).abs() <= 0.05)
+            .count();
+        // a uniform sampler would put less than 0.2% of its samples in the gap
+        assert!(n_in_gap > 100);
+    }
+
+    /// A sampler which returns each of its configurations in turn.
+    struct Cycle(Vec<Vector<2>>, Cell<usize>);
+
+    impl<RNG> Sample<Vector<2>, RNG> for Cycle {
+        fn sample(&self, _: &mut RNG) -> Vector<2> {
+            let i = self.1.get();
+            self.1.set(i + 1);
+            self.0[i % self.0.len()]
+        }
+    }
+
+    #[test]
+    fn bridge_shorter_than_length() {
+        // the bridge from the origin is cut short at (1, 0), and only its midpoint is valid
+        let sampler = BridgeTest {
+            base: Cycle(
+                vec![Vector::new([0.0, 0.0]), Vector::new([1.0, 0.0])],
+                Cell::new(0),
+            ),
+            valid: |c: &Vector<2>| (c[0] - 0.5).abs() < 0.01,
+            std_dev: 1e6,
+        };
+        let mut rng = ChaCha20Rng::seed_from_u64(2707);
+        let c = sampler.sample(&mut rng);
+        assert!((c[0] - 0.5).abs() < 1e-6);
+        assert!(c[1].abs() < 1e-9);
+    }
+
+    #[test]
+    fn obstacle_based_on_surface() {
+        let sampler = ObstacleBased {
+            base: square(),
+            valid,
+            step: 0.005,
+        };
+        let mut rng = ChaCha20Rng::seed_from_u64(2707);
+        for _ in 0..200 {
+            let c: Vector<2> = sampler.sample(&mut rng);
+            assert!(valid(&c));
+            let dist_to_wall = (c[0] - 0.5).abs() - 0.05;
+            let in_gap = (c[1] - 0.5).abs() < 0.01;
+            assert!(in_gap || dist_to_wall <= 0.005 + 1e-9);
+        }
+    }
+}
@@ -0,0 +1,196 @@
+//! Shared machinery for paths made of circular arcs of a fixed turning radius and straight lines.
+
+use core::marker::PhantomData;
+
+use num_traits::{Float, FloatConst, NumCast};
+
+use super::{Angle, Pose2d, Vector};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// A single motion primitive of a curve.
+pub(super) enum Segment {
+    /// Turn counterclockwise at the minimum turning radius.
+    Left,
+    /// Drive straight.
+    Straight,
+    /// Turn clockwise at the minimum turning radius.
+    Right,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// A path of up to five segments, normalized to a unit turning radius.
+///
+/// Each segment has a signed length: segments with negative length are driven in reverse.
+/// Paths with fewer than five segments are padded with empty straight segments.
+pub(super) struct CurvePath<T> {
+    pub(super) segments: [(Segment, T); 5],
+}
+
+impl<T: Float> CurvePath<T> {
+    /// Construct a path from its segment types and signed lengths.
+    pub(super) const fn new(types: [Segment; 5], lengths: [T; 5]) -> Self {
+        Self {
+            segments: [
+                (types[0], lengths[0]),
+                (types[1], lengths[1]),
+                (types[2], lengths[2]),
+                (types[3], lengths[3]),
+                (types[4], lengths[4]),
+            ],
+        }
+    }
+
+    /// Get the total length of this path, regardless of direction.
+    pub(super) fn length(&self) -> T {
+        self.segments
+            .iter()
+            .fold(T::zero(), |total, &(_, len)| total + len.abs())
+    }
+
+    /// Get the pose reached after following this path for a length of `s`, starting from the
+    /// origin with a heading of zero.
+    /// Returns a tuple of the x-position, y-position, and heading reached.
+    pub(super) fn sample(&self, s: T) -> (T, T, T) {
+        let (mut x, mut y, mut phi) = (T::zero(), T::zero(), T::zero());
+        let mut remaining = s;
+        for &(segment, len) in &self.segments {
+            if remaining <= T::zero() {
+                break;
+            }
+            let v = if len < T::zero() {
+                len.max(-remaining)
+            } else {
+                len.min(remaining)
+            };
+            remaining = remaining - v.abs();
+            match segment {
+                Segment::Left => {
+                    x = x + (phi + v).sin() - phi.sin();
+                    y = y - (phi + v).cos() + phi.cos();
+                    phi = phi + v;
+                }
+                Segment::Straight => {
+                    x = x + v * phi.cos();
+                    y = y + v * phi.sin();
+                }
+                Segment::Right => {
+                    x = x - (phi - v).sin() + phi.sin();
+                    y = y + (phi - v).cos() - phi.cos();
+                    phi = phi - v;
+                }
+            }
+        }
+        (x, y, phi)
+    }
+}
+
+/// Express `end` in the frame of `start`, scaled so that the turning radius is 1.
+/// Returns a tuple of the x-position, y-position, and heading of `end` in that frame.
+pub(super) fn local_frame<T>(start: &Pose2d<T>, end: &Pose2d<T>, turning_radius: T) -> (T, T, T)
+where
+    T: Float + FloatConst,
+{
+    let dx = end.position[0] - start.position[0];
+    let dy = end.position[1] - start.position[1];
+    let (s, c) = start.angle.get().sin_cos();
+    (
+        (c * dx + s * dy) / turning_radius,
+        (c * dy - s * dx) / turning_radius,
+        end.angle.get() - start.angle.get(),
+    )
+}
+
+/// Wrap an angle in radians into the range [0, 2pi).
+fn wrap<T: Float + FloatConst>(angle: T) -> Angle<T> {
+    let mut a = angle % T::TAU();
+    if a < T::zero() {
+        a = a + T::TAU();
+    }
+    if a >= T::TAU() {
+        a = T::zero();
+    }
+    unsafe { Angle::new_unchecked(a) }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The interpolation radius for poses which follow curves of bounded curvature.
+///
+/// `S` is the curve family, such as [`Dubins`](super::Dubins) or
+/// [`ReedsShepp`](super::ReedsShepp), which also determines the minimum turning radius.
+pub struct CurveStep<S, T> {
+    /// The curve family to follow.
+    pub curve: S,
+    /// The greatest distance along the curve between interpolation steps.
+    pub step: T,
+}
+
+#[derive(Clone, Debug)]
+/// An iterator over the steps of interpolation along a curve between two poses.
+///
+/// Each step is exactly the step length along the curve from the previous one.
+pub struct CurveInterpolation<P, T> {
+    start: Pose2d<T>,
+    path: CurvePath<T>,
+    turning_radius: T,
+    /// The length along the normalized path of each step.
+    step: T,
+    /// The number of steps taken so far.
+    k: usize,
+    /// The total number of steps.
+    n: usize,
+    pose: PhantomData<fn() -> P>,
+}
+
+impl<P, T> CurveInterpolation<P, T>
+where
+    T: Float,
+{
+    /// Construct an interpolation along `path` from `start` with steps of length `step`.
+    pub(super) fn new(start: Pose2d<T>, path: CurvePath<T>, turning_radius: T, step: T) -> Self {
+        let len = path.length() * turning_radius;
+        let n = if len <= step {
+            0
+        } else {
+            <usize as NumCast>::from((len / step).floor())
+                .expect("cannot interpolate with negative or NaN radius")
+        };
+        Self {
+            start,
+            path,
+            turning_radius,
+            step: step / turning_radius,
+            k: 0,
+            n,
+            pose: PhantomData,
+        }
+    }
+}
+
+impl<P, T> Iterator for CurveInterpolation<P, T>
+where
+    P: From<Pose2d<T>>,
+    T: Float + FloatConst,
+{
+    type Item = P;
+    fn next(&mut self) -> Option<Self::Item> {
+        (self.k < self.n).then(|| {
+            self.k += 1;
+            let (x, y, phi) = self
+                .path
+                .sample(self.step * <T as NumCast>::from(self.k).unwrap());
+            let (s, c) = self.start.angle.get().sin_cos();
+            let rho = self.turning_radius;
+            P::from(Pose2d {
+                position: Vector([
+                    self.start.position[0] + rho * (c * x - s * y),
+                    self.start.position[1] + rho * (s * x + c * y),
+                ]),
+                angle: wrap(self.start.angle.get() + phi),
+            })
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.n - self.k, Some(self.n - self.k))
+    }
+}
@@ -0,0 +1,312 @@
+use core::cmp::Ordering;
+
+use num_traits::{Float, FloatConst};
+
+use crate::{
+    metric::{Euclidean, Metric},
+    nn::{DistanceAabb, KdKey},
+    sample::Sample,
+    space::Interpolate,
+};
+
+use super::{
+    curve::{local_frame, CurveInterpolation, CurvePath, CurveStep, Segment},
+    Pose2d,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The family of Dubins curves: shortest paths for a car which may only drive forward with a
+/// bounded turning radius.
+///
+/// As a metric, this measures the length of the shortest Dubins curve between two
+/// [`DubinsPose`]s.
+/// Dubins curves are not symmetric: the distance from `a` to `b` is generally not equal to the
+/// distance from `b` to `a`.
+/// Since a Dubins car cannot reverse, bidirectional planners (such as
+/// [`RrtConnect`](crate::geo::RrtConnect)) will produce paths whose goal-side half must be
+/// followed backward; use [`ReedsShepp`](super::ReedsShepp) for those instead.
+///
+/// # Citation
+///
+/// ```bibtex
+/// @article{dubins1957curves,
+///   title={On curves of minimal length with a constraint on average curvature, and with
+///          prescribed initial and terminal positions and tangents},
+///   author={Dubins, Lester E},
+///   journal={American Journal of Mathematics},
+///   volume={79},
+///   number={3},
+///   pages={497--516},
+///   year={1957},
+///   publisher={JSTOR}
+/// }
+/// ```
+pub struct Dubins<T = f64> {
+    /// The minimum turning radius of the car.
+    pub turning_radius: T,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// A 2D pose for a car which follows [`Dubins`] curves.
+///
+/// Interpolation between `DubinsPose`s follows the shortest Dubins curve between them.
+pub struct DubinsPose<T = f64>(pub Pose2d<T>);
+
+impl<T> From<Pose2d<T>> for DubinsPose<T> {
+    fn from(value: Pose2d<T>) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> Dubins<T>
+where
+    T: Float + FloatConst,
+{
+    /// Compute the shortest Dubins curve from `start` to `end`, normalized to unit turning radius.
+    fn path(&self, start: &Pose2d<T>, end: &Pose2d<T>) -> CurvePath<T> {
+        let (x, y, phi) = local_frame(start, end, self.turning_radius);
+        shortest_path(x, y, phi)
+    }
+}
+
+/// Wrap an angle into the range [0, 2pi).
+fn mod2pi<T: Float + FloatConst>(x: T) -> T {
+    let v = x % T::TAU();
+    if v < T::zero() {
+        v + T::TAU()
+    } else {
+        v
+    }
+}
+
+/// Compute the shortest Dubins curve from the origin with heading zero to `(x, y)` with heading
+/// `phi`, all at unit turning radius.
+#[expect(clippy::many_single_char_names)]
+fn shortest_path<T: Float + FloatConst>(x: T, y: T, phi: T) -> CurvePath<T> {
+    use Segment::{Left as L, Right as R, Straight as S};
+
+    let two = T::one() + T::one();
+    let d = x.hypot(y);
+    let theta = mod2pi(y.atan2(x));
+    let a = mod2pi(-theta);
+    let b = mod2pi(phi - theta);
+    let (sa, ca) = a.sin_cos();
+    let (sb, cb) = b.sin_cos();
+    let c_ab = (a - b).cos();
+
+    let mut candidates: [Option<([Segment; 3], [T; 3])>; 6] = [None; 6];
+
+    // LSL
+    let p_sq = two + d * d - two * c_ab + two * d * (sa - sb);
+    if p_sq >= T::zero() {
+        let tmp = (cb - ca).atan2(d + sa - sb);
+        candidates[0] = Some(([L, S, L], [mod2pi(tmp - a), p_sq.sqrt(), mod2pi(b - tmp)]));
+    }
+
+    // RSR
+    let p_sq = two + d * d - two * c_ab + two * d * (sb - sa);
+    if p_sq >= T::zero() {
+        let tmp = (ca - cb).atan2(d - sa + sb);
+        candidates[1] = Some(([R, S, R], [mod2pi(a - tmp), p_sq.sqrt(), mod2pi(tmp - b)]));
+    }
+
+    // LSR
+    let p_sq = -two + d * d + two * c_ab + two * d * (sa + sb);
+    if p_sq >= T::zero() {
+        let p = p_sq.sqrt();
+        let tmp = (-ca - cb).atan2(d + sa + sb) - (-two).atan2(p);
+        candidates[2] = Some(([L, S, R], [mod2pi(tmp - a), p, mod2pi(tmp - b)]));
+    }
+
+    // RSL
+    let p_sq = -two + d * d + two * c_ab - two * d * (sa + sb);
+    if p_sq >= T::zero() {
+        let p = p_sq.sqrt();
+        let tmp = (ca + cb).atan2(d - sa - sb) - two.atan2(p);
+        candidates[3] = Some(([R, S, L], [mod2pi(a - tmp), p, mod2pi(b - tmp)]));
+    }
+
+    let eight = two * two * two;
+    let six = two + two + two;
+
+    // RLR
+    let tmp = (six - d * d + two * c_ab + two * d * (sa - sb)) / eight;
+    if tmp.abs() <= T::one() {
+        let p = mod2pi(T::TAU() - tmp.acos());
+        let t = mod2pi(a - (ca - cb).atan2(d - sa + sb) + p / two);
+        candidates[4] = Some(([R, L, R], [t, p, mod2pi(a - b - t + p)]));
+    }
+
+    // LRL
+    let tmp = (six - d * d + two * c_ab + two * d * (sb - sa)) / eight;
+    if tmp.abs() <= T::one() {
+        let p = mod2pi(T::TAU() - tmp.acos());
+        let t = mod2pi(-a - (ca - cb).atan2(d + sa - sb) + p / two);
+        candidates[5] = Some(([L, R, L], [t, p, mod2pi(b - a - t + p)]));
+    }
+
+    let (types, lengths) = candidates
+        .into_iter()
+        .flatten()
+        .min_by(|(_, l1), (_, l2)| {
+            let len1 = l1[0] + l1[1] + l1[2];
+            let len2 = l2[0] + l2[1] + l2[2];
+            len1.partial_cmp(&len2).unwrap_or(Ordering::Equal)
+        })
+        .expect("at least one Dubins word must be feasible");
+
+    CurvePath::new(
+        [types[0], types[1], types[2], S, S],
+        [lengths[0], lengths[1], lengths[2], T::zero(), T::zero()],
+    )
+}
+
+impl<T> Metric<DubinsPose<T>> for Dubins<T>
+where
+    T: Float + FloatConst,
+{
+    type Distance = T;
+
+    fn distance(&self, c1: &DubinsPose<T>, c2: &DubinsPose<T>) -> Self::Distance {
+        self.path(&c1.0, &c2.0).length() * self.turning_radius
+    }
+}
+
+impl<T> DistanceAabb<DubinsPose<T>> for Dubins<T>
+where
+    T: Float + FloatConst,
+{
+    /// Every Dubins curve is at least as long as the straight line between its endpoints, so the
+    /// Euclidean distance to the box bounds the curve length to any pose inside it.
+    fn distance_to_aabb(
+        &self,
+        c: &DubinsPose<T>,
+        aabb_lo: &DubinsPose<T>,
+        aabb_hi: &DubinsPose<T>,
+    ) -> Self::Distance {
+        Euclidean.distance_to_aabb(&c.0.position, &aabb_lo.0.position, &aabb_hi.0.position)
+    }
+}
+
+impl<T> Interpolate for DubinsPose<T>
+where
+    T: Float + FloatConst,
+{
+    type Distance = CurveStep<Dubins<T>, T>;
+    type Interpolation<'a>
+        = CurveInterpolation<Self, T>
+    where
+        T: 'a;
+
+    fn interpolate(&self, end: &Self, radius: Self::Distance) -> Self::Interpolation<'_> {
+        CurveInterpolation::new(
+            self.0,
+            radius.curve.path(&self.0, &end.0),
+            radius.curve.turning_radius,
+            radius.step,
+        )
+    }
+}
+
+impl<T, RNG> Sample<Self, RNG> for DubinsPose<T>
+where
+    T: Clone,
+{
+    fn sample(&self, _: &mut RNG) -> Self {
+        self.clone()
+    }
+}
+
+impl<T> KdKey for DubinsPose<T>
+where
+    Pose2d<T>: KdKey,
+    Self: Clone,
+{
+    fn dimension() -> usize {
+        Pose2d::<T>::dimension()
+    }
+
+    fn assign(&mut self, src: &Self, k: usize) {
+        self.0.assign(&src.0, k);
+    }
+
+    fn compare(&self, rhs: &Self, k: usize) -> Ordering {
+        self.0.compare(&rhs.0, k)
+    }
+
+    fn lower_bound() -> Self {
+        Self(Pose2d::lower_bound())
+    }
+
+    fn upper_bound() -> Self {
+        Self(Pose2d::upper_bound())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    use super::*;
+    use crate::{
+        sample::Rectangle,
+        space::{Angle, Vector},
+    };
+
+    #[test]
+    fn straight_ahead() {
+        let dubins = Dubins {
+            turning_radius: 1.0,
+        };
+        let start = DubinsPose(Pose2d {
+            position: Vector([0.0, 0.0]),
+            angle: Angle::new(0.0),
+        });
+        let end = DubinsPose(Pose2d {
+            position: Vector([3.0, 0.0]),
+            angle: Angle::new(0.0),
+        });
+        assert!((dubins.distance(&start, &end) - 3.0).abs() < 1e-9);
+        // turning around costs at least a half-circle
+        assert!(dubins.distance(&end, &start) > 3.0 + core::f64::consts::PI);
+    }
+
+    #[test]
+    fn reaches_goal() {
+        let region = Rectangle {
+            min: Vector([-5.0; 2]),
+            max: Vector([5.0; 2]),
+        };
+        let mut rng = ChaCha20Rng::seed_from_u64(2707);
+        let radius = CurveStep {
+            curve: Dubins {
+                turning_radius: 0.8,
+            },
+            step: 0.05,
+        };
+        for _ in 0..500 {
+            let start: DubinsPose = region.sample(&mut rng);
+            let end: DubinsPose = region.sample(&mut rng);
+            let mut prev = start;
+            for pose in start.interpolate(&end, radius) {
+                assert!(
+                    Euclidean.distance(&prev.0.position, &pose.0.position) <= radius.step + 1e-9
+                );
+                prev = pose;
+            }
+            assert!(Euclidean.distance(&prev.0.position, &end.0.position) <= radius.step + 1e-9);
+
+            // the full curve must land exactly on the goal
+            let path = radius.curve.path(&start.0, &end.0);
+            let (x, y, phi) = path.sample(path.length());
+            let (gx, gy, gphi) = local_frame(&start.0, &end.0, radius.curve.turning_radius);
+            assert!((x - gx).abs() < 1e-6 && (y - gy).abs() < 1e-6);
+            assert!(((phi - gphi).sin()).abs() < 1e-6 && (phi - gphi).cos() > 0.0);
+            assert!(
+                radius.curve.distance(&start, &end)
+                    >= Euclidean.distance(&start.0.position, &end.0.position) - 1e-9
+            );
+        }
+    }
+}
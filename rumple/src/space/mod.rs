@@ -1,15 +1,23 @@
 //! State spaces, or configuration spaces.
 
 mod angle;
+mod curve;
+mod dubins;
 mod orient;
 mod pose2d;
 mod pose3d;
+mod product;
+mod reeds_shepp;
 mod vector;
 
 pub use angle::Angle;
-pub use orient::Orient;
+pub use curve::{CurveInterpolation, CurveStep};
+pub use dubins::{Dubins, DubinsPose};
+pub use orient::{Orient, OrientInterpolation};
 pub use pose2d::Pose2d;
-pub use pose3d::Pose3d;
+pub use pose3d::{Pose3d, Pose3dInterpolation};
+pub use product::{Product, ProductInterpolation, WeightedProductDistance};
+pub use reeds_shepp::{ReedsShepp, ReedsSheppPose};
 pub use vector::Vector;
 
 use crate::{metric::Metric, nn::DistanceAabb};
@@ -76,6 +84,46 @@ where
     }
 }
 
+impl<T, MP, MA> Metric<Pose3d<T>> for WeightedPoseDistance<T, MP, MA>
+where
+    MP: Metric<Vector<3, T>, Distance = T>,
+    MA: Metric<Orient<T>, Distance = T>,
+    T: Float,
+{
+    type Distance = T;
+    fn distance(&self, c1: &Pose3d<T>, c2: &Pose3d<T>) -> Self::Distance {
+        let pd = self.position_metric.distance(&c1.position, &c2.position);
+        let ad = self.angle_metric.distance(&c1.orient, &c2.orient);
+
+        self.position_weight * pd + self.angle_weight * ad
+    }
+}
+
+impl<T, MP, MA> DistanceAabb<Pose3d<T>> for WeightedPoseDistance<T, MP, MA>
+where
+    MP: DistanceAabb<Vector<3, T>, Distance = T>,
+    MA: DistanceAabb<Orient<T>, Distance = T>,
+    T: Float,
+{
+    fn distance_to_aabb(
+        &self,
+        c: &Pose3d<T>,
+        aabb_lo: &Pose3d<T>,
+        aabb_hi: &Pose3d<T>,
+    ) -> Self::Distance {
+        self.position_weight
+            * self.position_metric.distance_to_aabb(
+                &c.position,
+                &aabb_lo.position,
+                &aabb_hi.position,
+            )
+            + self.angle_weight
+                * self
+                    .angle_metric
+                    .distance_to_aabb(&c.orient, &aabb_lo.orient, &aabb_hi.orient)
+    }
+}
+
 /// The trait for linear interpolation between configurations.
 pub trait Interpolate: Sized {
     /// The radius to which interpolation may be limited.
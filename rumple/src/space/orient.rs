@@ -1,8 +1,420 @@
+use core::ops::Mul;
+
+use num_traits::{float::Float, FloatConst, NumCast};
+
+use crate::{nn::KdKey, sample::Sample, space::Interpolate};
+
+use super::Vector;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(C)]
 /// An orientation in 3D.
-pub struct Orient<T> {
+///
+/// Orientations are stored as unit quaternions, which uniquely model the special orthogonal group
+/// in 3 dimensions (_SO_(3)) up to sign: a quaternion and its negation represent the same rotation.
+pub struct Orient<T = f64> {
     x: T,
     y: T,
     z: T,
     w: T,
 }
+
+impl<T> Orient<T> {
+    /// Construct a new orientation from the components of a quaternion, normalizing it.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the quaternion has zero or non-finite norm.
+    pub fn new(x: T, y: T, z: T, w: T) -> Self
+    where
+        T: Float,
+    {
+        let norm = (x * x + y * y + z * z + w * w).sqrt();
+        assert!(
+            norm.is_normal(),
+            "cannot construct an orientation from a quaternion with zero or non-finite norm"
+        );
+        Self {
+            x: x / norm,
+            y: y / norm,
+            z: z / norm,
+            w: w / norm,
+        }
+    }
+
+    /// Construct a new orientation from the components of a quaternion without normalizing it.
+    ///
+    /// The quaternion `(x, y, z, w)` must have unit norm.
+    /// This is only checked in debug builds; otherwise, a non-unit quaternion yields meaningless
+    /// rotations and distances.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, this function will panic if the quaternion does not have unit norm.
+    pub fn new_unchecked(x: T, y: T, z: T, w: T) -> Self
+    where
+        T: Float,
+    {
+        debug_assert!(
+            (x * x + y * y + z * z + w * w - T::one()).abs() <= T::epsilon().sqrt(),
+            "orientation quaternion must have unit norm"
+        );
+        Self { x, y, z, w }
+    }
+
+    #[must_use]
+    /// Get the identity orientation, which performs no rotation.
+    pub fn identity() -> Self
+    where
+        T: Float,
+    {
+        Self {
+            x: T::zero(),
+            y: T::zero(),
+            z: T::zero(),
+            w: T::one(),
+        }
+    }
+
+    /// Construct the orientation which rotates counterclockwise by `angle` radians about `axis`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `axis` has zero or non-finite length.
+    pub fn from_axis_angle(axis: &Vector<3, T>, angle: T) -> Self
+    where
+        T: Float,
+    {
+        let norm = (axis[0] * axis[0] + axis[1] * axis[1] + axis[2] * axis[2]).sqrt();
+        assert!(norm.is_normal(), "rotation axis must have nonzero length");
+        let half = angle / (T::one() + T::one());
+        let s = half.sin() / norm;
+        Self {
+            x: axis[0] * s,
+            y: axis[1] * s,
+            z: axis[2] * s,
+            w: half.cos(),
+        }
+    }
+
+    /// Get the `x` component of this quaternion.
+    pub const fn x(&self) -> T
+    where
+        T: Copy,
+    {
+        self.x
+    }
+
+    /// Get the `y` component of this quaternion.
+    pub const fn y(&self) -> T
+    where
+        T: Copy,
+    {
+        self.y
+    }
+
+    /// Get the `z` component of this quaternion.
+    pub const fn z(&self) -> T
+    where
+        T: Copy,
+    {
+        self.z
+    }
+
+    /// Get the `w` (real) component of this quaternion.
+    pub const fn w(&self) -> T
+    where
+        T: Copy,
+    {
+        self.w
+    }
+
+    #[must_use]
+    /// Renormalize this orientation to unit length, correcting for accumulated floating-point
+    /// error.
+    pub fn normalize(self) -> Self
+    where
+        T: Float,
+    {
+        Self::new(self.x, self.y, self.z, self.w)
+    }
+
+    #[must_use]
+    /// Get the inverse of this orientation, which undoes its rotation.
+    pub fn inverse(self) -> Self
+    where
+        T: Float,
+    {
+        Self {
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+            w: self.w,
+        }
+    }
+
+    /// Compute the dot product of `self` and `other` as 4-vectors.
+    pub fn dot(&self, other: &Self) -> T
+    where
+        T: Float,
+    {
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    }
+
+    /// Compute the angle of the smallest rotation which takes `self` to `other`.
+    /// The result is always between 0 and pi.
+    pub fn angle_to(&self, other: &Self) -> T
+    where
+        T: Float,
+    {
+        (T::one() + T::one()) * self.dot(other).abs().min(T::one()).acos()
+    }
+
+    /// Rotate a vector by this orientation.
+    pub fn rotate(&self, v: &Vector<3, T>) -> Vector<3, T>
+    where
+        T: Float,
+    {
+        // v' = v + 2w (u x v) + 2 u x (u x v), where u is the vector part
+        let two = T::one() + T::one();
+        let u = [self.x, self.y, self.z];
+        let t = cross(&u, v);
+        let t = [two * t[0], two * t[1], two * t[2]];
+        let ut = cross(&u, &t);
+        Vector::new([
+            v[0] + self.w * t[0] + ut[0],
+            v[1] + self.w * t[1] + ut[1],
+            v[2] + self.w * t[2] + ut[2],
+        ])
+    }
+
+    #[must_use]
+    /// Spherically interpolate between `self` and `other` along the shortest rotation, where `t =
+    /// 0` yields `self` and `t = 1` yields `other`.
+    pub fn slerp(&self, other: &Self, t: T) -> Self
+    where
+        T: Float,
+    {
+        let mut dot = self.dot(other);
+        let mut end = *other;
+        if dot < T::zero() {
+            // take the short way around
+            dot = -dot;
+            end = Self {
+                x: -end.x,
+                y: -end.y,
+                z: -end.z,
+                w: -end.w,
+            };
+        }
+        let (s0, s1) = if dot > T::one() - T::epsilon().sqrt() {
+            // nearly parallel: fall back to linear interpolation
+            (T::one() - t, t)
+        } else {
+            let theta = dot.acos();
+            let sin_theta = theta.sin();
+            (
+                ((T::one() - t) * theta).sin() / sin_theta,
+                (t * theta).sin() / sin_theta,
+            )
+        };
+        Self::new(
+            s0 * self.x + s1 * end.x,
+            s0 * self.y + s1 * end.y,
+            s0 * self.z + s1 * end.z,
+            s0 * self.w + s1 * end.w,
+        )
+    }
+}
+
+/// Compute the cross product of two 3-vectors.
+fn cross<T: Float>(a: &[T; 3], b: &[T; 3]) -> [T; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+impl<T: Float> Mul for Orient<T> {
+    type Output = Self;
+
+    /// Compose two orientations, such that `(a * b).rotate(v) == a.rotate(b.rotate(v))`.
+    fn mul(self, rhs: Self) -> Self {
+        Self {
+            x: self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y,
+            y: self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x,
+            z: self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w,
+            w: self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z,
+        }
+    }
+}
+
+impl<T: Float> Default for Orient<T> {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl<T> Interpolate for Orient<T>
+where
+    T: Float + FloatConst,
+{
+    /// This distance is the angle of rotation between orientations.
+    type Distance = T;
+
+    type Interpolation<'a>
+        = OrientInterpolation<T>
+    where
+        T: 'a;
+
+    fn interpolate(&self, end: &Self, radius: Self::Distance) -> Self::Interpolation<'_> {
+        let angle = self.angle_to(end);
+        let n = <usize as NumCast>::from((angle / radius).floor())
+            .expect("cannot interpolate with negative or NaN radius");
+        OrientInterpolation {
+            start: *self,
+            end: *end,
+            step: if n == 0 { T::zero() } else { radius / angle },
+            k: 0,
+            n,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+/// An iterator over the steps of spherical linear interpolation between two orientations.
+pub struct OrientInterpolation<T> {
+    start: Orient<T>,
+    end: Orient<T>,
+    /// The fraction of the distance covered by each step.
+    step: T,
+    /// The number of steps taken so far.
+    k: usize,
+    /// The total number of steps.
+    n: usize,
+}
+
+impl<T: Float> Iterator for OrientInterpolation<T> {
+    type Item = Orient<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        (self.k < self.n).then(|| {
+            self.k += 1;
+            let t = self.step * <T as NumCast>::from(self.k).unwrap();
+            self.start.slerp(&self.end, t)
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.n - self.k, Some(self.n - self.k))
+    }
+}
+
+impl<T, RNG> Sample<Self, RNG> for Orient<T>
+where
+    T: Clone,
+{
+    fn sample(&self, _: &mut RNG) -> Self {
+        self.clone()
+    }
+}
+
+impl<T> KdKey for Orient<T>
+where
+    T: Float,
+{
+    fn dimension() -> usize {
+        4
+    }
+
+    fn assign(&mut self, src: &Self, k: usize) {
+        match k {
+            0 => self.x = src.x,
+            1 => self.y = src.y,
+            2 => self.z = src.z,
+            3 => self.w = src.w,
+            _ => panic!("cannot assign dimension greater than 3"),
+        }
+    }
+
+    fn compare(&self, rhs: &Self, k: usize) -> core::cmp::Ordering {
+        match k {
+            0 => self.x.partial_cmp(&rhs.x),
+            1 => self.y.partial_cmp(&rhs.y),
+            2 => self.z.partial_cmp(&rhs.z),
+            3 => self.w.partial_cmp(&rhs.w),
+            _ => panic!("cannot test dimension greater than 3"),
+        }
+        .unwrap()
+    }
+
+    fn lower_bound() -> Self {
+        Self {
+            x: T::min_value(),
+            y: T::min_value(),
+            z: T::min_value(),
+            w: T::min_value(),
+        }
+    }
+
+    fn upper_bound() -> Self {
+        Self {
+            x: T::max_value(),
+            y: T::max_value(),
+            z: T::max_value(),
+            w: T::max_value(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::f64::consts::{FRAC_PI_2, PI};
+
+    use super::*;
+
+    fn close(a: &Vector<3>, b: &Vector<3>) -> bool {
+        a.iter().zip(b.iter()).all(|(x, y)| (x - y).abs() < 1e-9)
+    }
+
+    #[test]
+    fn rotate_compose() {
+        let z90 = Orient::from_axis_angle(&Vector::new([0.0, 0.0, 1.0]), FRAC_PI_2);
+        let x90 = Orient::from_axis_angle(&Vector::new([1.0, 0.0, 0.0]), FRAC_PI_2);
+        let v = Vector::new([1.0, 0.0, 0.0]);
+        assert!(close(&z90.rotate(&v), &Vector::new([0.0, 1.0, 0.0])));
+        assert!(close(
+            &(x90 * z90).rotate(&v),
+            &Vector::new([0.0, 0.0, 1.0])
+        ));
+        assert!(close(&(z90.inverse() * z90).rotate(&v), &v));
+        assert!((z90.angle_to(&x90) - 2.0 * PI / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn interpolate_steps() {
+        let start = Orient::identity();
+        let end = Orient::from_axis_angle(&Vector::new([0.0, 1.0, 0.0]), PI * 0.9);
+        let radius = 0.1;
+        let mut prev = start;
+        for q in start.interpolate(&end, radius) {
+            assert!(prev.angle_to(&q) <= radius + 1e-9);
+            prev = q;
+        }
+        assert!(prev.angle_to(&end) <= radius + 1e-9);
+
+        // interpolation must take the short way around, even if the signs differ
+        let neg_end = Orient::new(-end.x(), -end.y(), -end.z(), -end.w());
+        assert_eq!(
+            start.interpolate(&end, radius).count(),
+            start.interpolate(&neg_end, radius).count()
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic = "orientation quaternion must have unit norm"]
+    fn unchecked_not_unit() {
+        let _ = Orient::new_unchecked(1.0, 1.0, 0.0, 0.0);
+    }
+}
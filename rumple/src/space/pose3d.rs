@@ -1,9 +1,230 @@
-use super::{Orient, Vector};
+use core::{cmp::Ordering, ops::Mul};
 
-/// A position in 3 dimensions.
-pub struct Pose3d<T> {
+use num_traits::{float::Float, FloatConst, NumCast};
+
+use crate::{
+    metric::{Euclidean, Metric},
+    nn::KdKey,
+    sample::Sample,
+    space::Interpolate,
+};
+
+use super::{Orient, PoseRadius, Vector};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// A pose in 3 dimensions.
+pub struct Pose3d<T = f64> {
     /// The translation vector.
     pub position: Vector<3, T>,
     /// The orientation.
     pub orient: Orient<T>,
 }
+
+impl<T: Float> Pose3d<T> {
+    #[must_use]
+    /// Get the identity pose, which performs no translation or rotation.
+    pub fn identity() -> Self {
+        Self {
+            position: Vector::new([T::zero(); 3]),
+            orient: Orient::identity(),
+        }
+    }
+
+    #[must_use]
+    /// Get the inverse of this pose, such that `pose * pose.inverse()` is the identity.
+    pub fn inverse(self) -> Self {
+        let orient = self.orient.inverse();
+        let p = orient.rotate(&self.position);
+        Self {
+            position: Vector::new([-p[0], -p[1], -p[2]]),
+            orient,
+        }
+    }
+
+    /// Transform a point in the local frame of this pose into the global frame.
+    pub fn transform(&self, v: &Vector<3, T>) -> Vector<3, T> {
+        let r = self.orient.rotate(v);
+        Vector::new([
+            r[0] + self.position[0],
+            r[1] + self.position[1],
+            r[2] + self.position[2],
+        ])
+    }
+}
+
+impl<T: Float> Mul for Pose3d<T> {
+    type Output = Self;
+
+    /// Compose two poses, such that `(a * b).transform(v) == a.transform(b.transform(v))`.
+    fn mul(self, rhs: Self) -> Self {
+        Self {
+            position: self.transform(&rhs.position),
+            orient: (self.orient * rhs.orient).normalize(),
+        }
+    }
+}
+
+impl<T> Interpolate for Pose3d<T>
+where
+    T: Float + FloatConst,
+{
+    type Distance = PoseRadius<T>;
+    type Interpolation<'a>
+        = Pose3dInterpolation<T>
+    where
+        T: 'a;
+    fn interpolate(&self, end: &Self, radius: Self::Distance) -> Self::Interpolation<'_> {
+        let pos_dist = Euclidean.distance(&self.position, &end.position);
+        let ang_dist = self.orient.angle_to(&end.orient);
+        let ang_n = <usize as NumCast>::from((ang_dist / radius.angle_dist).floor()).unwrap();
+        let pos_n = <usize as NumCast>::from((pos_dist / radius.position_dist).floor()).unwrap();
+        let n = match ang_n.cmp(&pos_n) {
+            Ordering::Less => pos_n,
+            Ordering::Greater | Ordering::Equal => ang_n,
+        };
+        Pose3dInterpolation {
+            start: *self,
+            end: *end,
+            k: 0,
+            n,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+/// An iterator over the steps of interpolation between two 3D poses.
+///
+/// Positions are interpolated linearly and orientations are spherically interpolated, with all
+/// steps evenly spaced between the start and end.
+pub struct Pose3dInterpolation<T> {
+    start: Pose3d<T>,
+    end: Pose3d<T>,
+    /// The number of steps taken so far.
+    k: usize,
+    /// The total number of steps.
+    n: usize,
+}
+
+impl<T: Float> Iterator for Pose3dInterpolation<T> {
+    type Item = Pose3d<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        (self.k < self.n).then(|| {
+            self.k += 1;
+            // fencepost problem - there are `n + 1` gaps between samples and the borders
+            let t =
+                <T as NumCast>::from(self.k).unwrap() / <T as NumCast>::from(self.n + 1).unwrap();
+            let (p0, p1) = (&self.start.position, &self.end.position);
+            Pose3d {
+                position: Vector::new([
+                    p0[0] + t * (p1[0] - p0[0]),
+                    p0[1] + t * (p1[1] - p0[1]),
+                    p0[2] + t * (p1[2] - p0[2]),
+                ]),
+                orient: self.start.orient.slerp(&self.end.orient, t),
+            }
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.n - self.k, Some(self.n - self.k))
+    }
+}
+
+impl<T, RNG> Sample<Self, RNG> for Pose3d<T>
+where
+    T: Clone,
+{
+    fn sample(&self, _: &mut RNG) -> Self {
+        self.clone()
+    }
+}
+
+impl<T> KdKey for Pose3d<T>
+where
+    Vector<3, T>: KdKey,
+    Orient<T>: KdKey,
+    Self: Clone,
+{
+    fn dimension() -> usize {
+        7
+    }
+
+    fn assign(&mut self, src: &Self, k: usize) {
+        match k {
+            k if k < 3 => self.position.assign(&src.position, k),
+            k if k < 7 => self.orient.assign(&src.orient, k - 3),
+            _ => panic!("cannot assign dimension greater than 6"),
+        }
+    }
+
+    fn compare(&self, rhs: &Self, k: usize) -> Ordering {
+        match k {
+            k if k < 3 => self.position.compare(&rhs.position, k),
+            k if k < 7 => self.orient.compare(&rhs.orient, k - 3),
+            _ => panic!("cannot test dimension greater than 6"),
+        }
+    }
+
+    fn lower_bound() -> Self {
+        Self {
+            position: Vector::lower_bound(),
+            orient: Orient::lower_bound(),
+        }
+    }
+
+    fn upper_bound() -> Self {
+        Self {
+            position: Vector::upper_bound(),
+            orient: Orient::upper_bound(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::f64::consts::FRAC_PI_2;
+
+    use super::*;
+
+    #[test]
+    fn interp() {
+        let x1 = Pose3d {
+            position: Vector::new([0.0, 1.0, 2.0]),
+            orient: Orient::identity(),
+        };
+        let x2 = Pose3d {
+            position: Vector::new([3.0, -1.0, 2.5]),
+            orient: Orient::from_axis_angle(&Vector::new([1.0, 1.0, 0.0]), 3.0 * FRAC_PI_2),
+        };
+        let grow_radius = PoseRadius {
+            angle_dist: 0.1,
+            position_dist: 0.2,
+        };
+
+        let mut prev = x1;
+        for new in x1.interpolate(&x2, grow_radius) {
+            assert!(
+                Euclidean.distance(&prev.position, &new.position)
+                    <= grow_radius.position_dist + 1e-5
+            );
+            assert!(prev.orient.angle_to(&new.orient) <= grow_radius.angle_dist + 1e-5);
+            prev = new;
+        }
+
+        assert!(
+            Euclidean.distance(&prev.position, &x2.position) <= grow_radius.position_dist + 1e-5
+        );
+        assert!(prev.orient.angle_to(&x2.orient) <= grow_radius.angle_dist + 1e-5);
+    }
+
+    #[test]
+    fn compose_inverse() {
+        let pose = Pose3d {
+            position: Vector::new([1.0, 2.0, 3.0]),
+            orient: Orient::from_axis_angle(&Vector::new([0.0, 1.0, 1.0]), 0.7),
+        };
+        let v = Vector::new([-0.5, 0.25, 4.0]);
+        let round_trip = (pose * pose.inverse()).transform(&v);
+        assert!(Euclidean.distance(&round_trip, &v) < 1e-9);
+    }
+}
@@ -0,0 +1,414 @@
+//! Product (compound) spaces, made of tuples of other spaces.
+
+use core::{
+    cmp::Ordering,
+    iter::{Fuse, Map},
+};
+
+use num_traits::Float;
+
+use crate::{
+    metric::Metric,
+    nn::{DistanceAabb, KdKey},
+};
+
+use super::Interpolate;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+/// A configuration in a product space, made of a tuple of configurations.
+///
+/// `Product` implements the same space traits as the tuple it contains by delegating to it.
+/// Samplers for a `Product` are also `Product`s, containing a tuple of samplers.
+pub struct Product<T>(pub T);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// A distance metric for tuples of configurations that takes a weighted sum of the distances
+/// between each of their components.
+///
+/// `M` is a tuple of metrics, one for each component, and `weights` contains the weight of each
+/// component.
+/// All component metrics must share the same distance type.
+pub struct WeightedProductDistance<M, T, const N: usize> {
+    /// The metric for each component.
+    pub metrics: M,
+    /// The weight to apply to the distance of each component.
+    pub weights: [T; N],
+}
+
+#[derive(Clone, Debug)]
+/// An iterator over the steps of interpolation between two tuples of configurations.
+///
+/// Each component is interpolated independently with its own radius.
+/// Once a component has reached its end, it is held there until every other component has also
+/// reached its end.
+pub struct ProductInterpolation<'a, C, I> {
+    end: &'a C,
+    interpolations: I,
+}
+
+/// Find which component of a product contains axis `k`, given the dimension of each component.
+/// Returns the index of that component and the index of the axis within it.
+fn split_axis(dims: &[usize], mut k: usize) -> (usize, usize) {
+    for (i, &d) in dims.iter().enumerate() {
+        if k < d {
+            return (i, k);
+        }
+        k -= d;
+    }
+    panic!("axis out of range for product space");
+}
+
+/// Implement the space traits for tuples whose components are `$c`, with metrics `$m`,
+/// interpolations `$i`, and tuple indices `$idx`.
+macro_rules! product_impl {
+    ($n: literal; $(($c: ident, $m: ident, $i: ident, $idx: tt)),+) => {
+        impl<$($c),+> Interpolate for ($($c,)+)
+        where
+            $($c: Interpolate + Clone,)+
+        {
+            /// This distance is the interpolation radius of each component.
+            type Distance = ($($c::Distance,)+);
+
+            type Interpolation<'a>
+                = ProductInterpolation<'a, Self, ($(Fuse<$c::Interpolation<'a>>,)+)>
+            where
+                Self: 'a;
+
+            fn interpolate<'a>(
+                &'a self,
+                end: &'a Self,
+                radius: Self::Distance,
+            ) -> Self::Interpolation<'a> {
+                ProductInterpolation {
+                    end,
+                    interpolations: ($(self.$idx.interpolate(&end.$idx, radius.$idx).fuse(),)+),
+                }
+            }
+        }
+
+        impl<'a, $($c, $i),+> Iterator for ProductInterpolation<'a, ($($c,)+), ($(Fuse<$i>,)+)>
+        where
+            $($c: Clone, $i: Iterator<Item = $c>,)+
+        {
+            type Item = ($($c,)+);
+            fn next(&mut self) -> Option<Self::Item> {
+                let steps = ($(self.interpolations.$idx.next(),)+);
+                if $(steps.$idx.is_none())&&+ {
+                    return None;
+                }
+                Some(($(steps.$idx.unwrap_or_else(|| self.end.$idx.clone()),)+))
+            }
+        }
+
+        impl<$($c),+> KdKey for ($($c,)+)
+        where
+            $($c: KdKey,)+
+        {
+            fn dimension() -> usize {
+                0 $(+ $c::dimension())+
+            }
+
+            fn compare(&self, rhs: &Self, k: usize) -> Ordering {
+                match split_axis(&[$($c::dimension()),+], k) {
+                    $(($idx, k) => self.$idx.compare(&rhs.$idx, k),)+
+                    _ => unreachable!(),
+                }
+            }
+
+            fn assign(&mut self, src: &Self, k: usize) {
+                match split_axis(&[$($c::dimension()),+], k) {
+                    $(($idx, k) => self.$idx.assign(&src.$idx, k),)+
+                    _ => unreachable!(),
+                }
+            }
+
+            fn lower_bound() -> Self {
+                ($($c::lower_bound(),)+)
+            }
+
+            fn upper_bound() -> Self {
+                ($($c::upper_bound(),)+)
+            }
+        }
+
+        impl<T, $($c, $m),+> Metric<($($c,)+)> for WeightedProductDistance<($($m,)+), T, $n>
+        where
+            T: Float,
+            $($m: Metric<$c, Distance = T>,)+
+        {
+            type Distance = T;
+
+            fn distance(&self, c1: &($($c,)+), c2: &($($c,)+)) -> Self::Distance {
+                T::zero()
+                    $(+ self.weights[$idx] * self.metrics.$idx.distance(&c1.$idx, &c2.$idx))+
+            }
+        }
+
+        impl<T, $($c, $m),+> DistanceAabb<($($c,)+)>
+            for WeightedProductDistance<($($m,)+), T, $n>
+        where
+            T: Float,
+            $($m: DistanceAabb<$c, Distance = T>,)+
+        {
+            fn distance_to_aabb(
+                &self,
+                c: &($($c,)+),
+                aabb_lo: &($($c,)+),
+                aabb_hi: &($($c,)+),
+            ) -> Self::Distance {
+                T::zero()
+                    $(+ self.weights[$idx]
+                        * self.metrics.$idx.distance_to_aabb(
+                            &c.$idx,
+                            &aabb_lo.$idx,
+                            &aabb_hi.$idx,
+                        ))+
+            }
+        }
+    };
+}
+
+impl<C: Interpolate> Interpolate for Product<C> {
+    type Distance = C::Distance;
+
+    type Interpolation<'a>
+        = Map<C::Interpolation<'a>, fn(C) -> Self>
+    where
+        Self: 'a;
+
+    fn interpolate<'a>(&'a self, end: &'a Self, radius: Self::Distance) -> Self::Interpolation<'a> {
+        self.0.interpolate(&end.0, radius).map(Product)
+    }
+}
+
+impl<C: KdKey> KdKey for Product<C> {
+    fn dimension() -> usize {
+        C::dimension()
+    }
+
+    fn compare(&self, rhs: &Self, k: usize) -> Ordering {
+        self.0.compare(&rhs.0, k)
+    }
+
+    fn assign(&mut self, src: &Self, k: usize) {
+        self.0.assign(&src.0, k);
+    }
+
+    fn lower_bound() -> Self {
+        Self(C::lower_bound())
+    }
+
+    fn upper_bound() -> Self {
+        Self(C::upper_bound())
+    }
+}
+
+impl<C, M, T, const N: usize> Metric<Product<C>> for WeightedProductDistance<M, T, N>
+where
+    Self: Metric<C>,
+{
+    type Distance = <Self as Metric<C>>::Distance;
+
+    fn distance(&self, c1: &Product<C>, c2: &Product<C>) -> Self::Distance {
+        self.distance(&c1.0, &c2.0)
+    }
+}
+
+impl<C, M, T, const N: usize> DistanceAabb<Product<C>> for WeightedProductDistance<M, T, N>
+where
+    Self: DistanceAabb<C>,
+{
+    fn distance_to_aabb(
+        &self,
+        c: &Product<C>,
+        aabb_lo: &Product<C>,
+        aabb_hi: &Product<C>,
+    ) -> Self::Distance {
+        self.distance_to_aabb(&c.0, &aabb_lo.0, &aabb_hi.0)
+    }
+}
+
+product_impl!(2; (A, MA, IA, 0), (B, MB, IB, 1));
+product_impl!(3; (A, MA, IA, 0), (B, MB, IB, 1), (C, MC, IC, 2));
+product_impl!(4; (A, MA, IA, 0), (B, MB, IB, 1), (C, MC, IC, 2), (D, MD, ID, 3));
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use rand::{distributions::Bernoulli, SeedableRng};
+    use rand_chacha::ChaCha20Rng;
+
+    use super::*;
+    use crate::{
+        geo::{rrt, rrt_connect},
+        metric::{Euclidean, SquaredEuclidean},
+        nn::KdTreeMap,
+        sample::Rectangle,
+        space::{Angle, Pose2d, PoseRadius, Vector, WeightedPoseDistance},
+        time::Solved,
+        valid::AlwaysValid,
+    };
+
+    #[test]
+    fn axes() {
+        type Key = (Vector<2>, Angle, Vector<3>);
+        assert_eq!(Key::dimension(), 6);
+        let mut x: Key = (Vector([0.0; 2]), Angle::new(0.0), Vector([0.0; 3]));
+        let y: Key = (Vector([1.0, 2.0]), Angle::new(3.0), Vector([4.0, 5.0, 6.0]));
+        for k in 0..6 {
+            assert_eq!(x.compare(&y, k), Ordering::Less);
+            x.assign(&y, k);
+            assert_eq!(x.compare(&y, k), Ordering::Equal);
+        }
+        assert_eq!(x, y);
+    }
+
+    #[test]
+    fn interpolate_uneven() {
+        let start = (Vector([0.0]), Vector([0.0, 0.0]));
+        let end = (Vector([1.0]), Vector([0.0, 0.35]));
+        let steps: Vec<_> = start.interpolate(&end, (0.1, 0.1)).collect();
+        assert_eq!(steps.len(), 10);
+        let mut prev = start;
+        for step in steps.iter().chain([&end]) {
+            assert!(Euclidean.distance(&prev.0, &step.0) <= 0.1 + 1e-9);
+            assert!(Euclidean.distance(&prev.1, &step.1) <= 0.1 + 1e-9);
+            prev = *step;
+        }
+        // the shorter component is held at its end once it arrives
+        assert_eq!(steps[9].1, end.1);
+    }
+
+    #[test]
+    fn mobile_manipulator() {
+        let start = (
+            Pose2d {
+                position: Vector([0.0f32, 0.0]),
+                angle: Angle::new(0.0),
+            },
+            Vector([0.0f32; 7]),
+        );
+        let goal = (
+            Pose2d {
+                position: Vector([2.0, 1.0]),
+                angle: Angle::new(3.0),
+            },
+            Vector([1.0; 7]),
+        );
+        let metric = WeightedProductDistance {
+            metrics: (
+                WeightedPoseDistance {
+                    position_metric: Euclidean,
+                    position_weight: 1.0,
+                    angle_metric: Euclidean,
+                    angle_weight: 0.5,
+                },
+                SquaredEuclidean,
+            ),
+            weights: [1.0, 0.25],
+        };
+        let radius = (
+            PoseRadius {
+                angle_dist: 0.3,
+                position_dist: 0.3,
+            },
+            0.3,
+        );
+        let path = rrt(
+            start,
+            KdTreeMap::new(metric),
+            &AlwaysValid,
+            &(
+                Rectangle {
+                    min: Vector([-1.0; 2]),
+                    max: Vector([3.0; 2]),
+                },
+                Rectangle {
+                    min: Vector([-1.0; 7]),
+                    max: Vector([2.0; 7]),
+                },
+            ),
+            &goal,
+            radius,
+            &mut Solved::new(),
+            &Bernoulli::new(0.1).unwrap(),
+            &mut ChaCha20Rng::seed_from_u64(2707),
+        )
+        .unwrap();
+
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&goal));
+        for w in path.windows(2) {
+            assert!(Euclidean.distance(&w[0].0.position, &w[1].0.position) <= 0.3 + 1e-5);
+            assert!(Euclidean.distance(&w[0].1, &w[1].1) <= 0.3 + 1e-5);
+        }
+    }
+
+    #[test]
+    fn product_mobile_manipulator() {
+        let start = Product((
+            Pose2d {
+                position: Vector([0.0f32, 0.0]),
+                angle: Angle::new(0.0),
+            },
+            Vector([0.0f32; 7]),
+        ));
+        let goal = Product((
+            Pose2d {
+                position: Vector([2.0, 1.0]),
+                angle: Angle::new(3.0),
+            },
+            Vector([1.0; 7]),
+        ));
+        let metric = WeightedProductDistance {
+            metrics: (
+                WeightedPoseDistance {
+                    position_metric: SquaredEuclidean,
+                    position_weight: 1.0,
+                    angle_metric: SquaredEuclidean,
+                    angle_weight: 0.5,
+                },
+                SquaredEuclidean,
+            ),
+            weights: [1.0, 0.25],
+        };
+        assert_eq!(Product::<(Pose2d<f32>, Vector<7, f32>)>::dimension(), 10);
+
+        let radius = (
+            PoseRadius {
+                angle_dist: 0.3,
+                position_dist: 0.3,
+            },
+            0.3,
+        );
+        let path = rrt_connect(
+            start,
+            &goal,
+            KdTreeMap::new(metric),
+            &AlwaysValid,
+            &Product((
+                Rectangle {
+                    min: Vector([-1.0; 2]),
+                    max: Vector([3.0; 2]),
+                },
+                Rectangle {
+                    min: Vector([-1.0; 7]),
+                    max: Vector([2.0; 7]),
+                },
+            )),
+            radius,
+            &mut Solved::new(),
+            &mut ChaCha20Rng::seed_from_u64(2707),
+        )
+        .unwrap();
+
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&goal));
+        for w in path.windows(2) {
+            let (Product(a), Product(b)) = (&w[0], &w[1]);
+            assert!(Euclidean.distance(&a.0.position, &b.0.position) <= 0.3 + 1e-5);
+            assert!(Euclidean.distance(&a.1, &b.1) <= 0.3 + 1e-5);
+        }
+    }
+}
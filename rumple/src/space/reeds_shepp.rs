@@ -0,0 +1,637 @@
+// variable names follow the notation of Reeds and Shepp
+#![expect(clippy::many_single_char_names)]
+
+use core::cmp::Ordering;
+
+use num_traits::{Float, FloatConst, NumCast};
+
+use crate::{
+    metric::{Euclidean, Metric},
+    nn::{DistanceAabb, KdKey},
+    sample::Sample,
+    space::Interpolate,
+};
+
+use super::{
+    curve::{
+        local_frame, CurveInterpolation, CurvePath, CurveStep, Segment,
+        Segment::{Left as L, Right as R, Straight as S},
+    },
+    Pose2d,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The family of Reeds-Shepp curves: shortest paths for a car which may drive forward or in
+/// reverse with a bounded turning radius.
+///
+/// As a metric, this measures the length of the shortest Reeds-Shepp curve between two
+/// [`ReedsSheppPose`]s.
+/// Unlike [`Dubins`](super::Dubins) curves, Reeds-Shepp curves are symmetric, so they are suitable
+/// for bidirectional planners such as [`RrtConnect`](crate::geo::RrtConnect).
+///
+/// # Citation
+///
+/// ```bibtex
+/// @article{reeds1990optimal,
+///   title={Optimal paths for a car that goes both forwards and backwards},
+///   author={Reeds, James and Shepp, Lawrence},
+///   journal={Pacific Journal of Mathematics},
+///   volume={145},
+///   number={2},
+///   pages={367--393},
+///   year={1990},
+///   publisher={Mathematical Sciences Publishers}
+/// }
+/// ```
+pub struct ReedsShepp<T = f64> {
+    /// The minimum turning radius of the car.
+    pub turning_radius: T,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// A 2D pose for a car which follows [`ReedsShepp`] curves.
+///
+/// Interpolation between `ReedsSheppPose`s follows the shortest Reeds-Shepp curve between them.
+pub struct ReedsSheppPose<T = f64>(pub Pose2d<T>);
+
+impl<T> From<Pose2d<T>> for ReedsSheppPose<T> {
+    fn from(value: Pose2d<T>) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> ReedsShepp<T>
+where
+    T: Float + FloatConst,
+{
+    /// Compute the shortest Reeds-Shepp curve from `start` to `end`, normalized to unit turning
+    /// radius.
+    fn path(&self, start: &Pose2d<T>, end: &Pose2d<T>) -> CurvePath<T> {
+        let (x, y, phi) = local_frame(start, end, self.turning_radius);
+        let mut best = Best {
+            path: None,
+            length: T::infinity(),
+        };
+        csc(x, y, phi, &mut best);
+        ccc(x, y, phi, &mut best);
+        cccc(x, y, phi, &mut best);
+        ccsc(x, y, phi, &mut best);
+        ccscc(x, y, phi, &mut best);
+        best.path
+            .expect("at least one Reeds-Shepp word must be feasible")
+    }
+}
+
+const LRL: [Segment; 5] = [L, R, L, S, S];
+const RLR: [Segment; 5] = [R, L, R, S, S];
+const LRLR: [Segment; 5] = [L, R, L, R, S];
+const RLRL: [Segment; 5] = [R, L, R, L, S];
+const LRSL: [Segment; 5] = [L, R, S, L, S];
+const RLSR: [Segment; 5] = [R, L, S, R, S];
+const LSRL: [Segment; 5] = [L, S, R, L, S];
+const RSLR: [Segment; 5] = [R, S, L, R, S];
+const LRSR: [Segment; 5] = [L, R, S, R, S];
+const RLSL: [Segment; 5] = [R, L, S, L, S];
+const RSRL: [Segment; 5] = [R, S, R, L, S];
+const LSLR: [Segment; 5] = [L, S, L, R, S];
+const LSR: [Segment; 5] = [L, S, R, S, S];
+const RSL: [Segment; 5] = [R, S, L, S, S];
+const LSL: [Segment; 5] = [L, S, L, S, S];
+const RSR: [Segment; 5] = [R, S, R, S, S];
+const LRSLR: [Segment; 5] = [L, R, S, L, R];
+const RLSRL: [Segment; 5] = [R, L, S, R, L];
+
+/// The shortest path found so far while searching over Reeds-Shepp words.
+struct Best<T> {
+    path: Option<CurvePath<T>>,
+    length: T,
+}
+
+impl<T: Float> Best<T> {
+    /// Replace the current best path with the given one if it is shorter.
+    fn offer(&mut self, types: [Segment; 5], lengths: [T; 5]) {
+        let path = CurvePath::new(types, lengths);
+        let length = path.length();
+        if length < self.length {
+            self.path = Some(path);
+            self.length = length;
+        }
+    }
+}
+
+/// Convert a constant to `T`.
+fn k<T: NumCast>(x: f64) -> T {
+    T::from(x).unwrap()
+}
+
+/// The tolerance for accepting a segment length as nonnegative or nonpositive.
+fn zero<T: Float>() -> T {
+    T::epsilon() * k(10.0)
+}
+
+/// Wrap an angle into the range (-pi, pi].
+fn mod2pi<T: Float + FloatConst>(x: T) -> T {
+    let v = x % T::TAU();
+    if v < -T::PI() {
+        v + T::TAU()
+    } else if v > T::PI() {
+        v - T::TAU()
+    } else {
+        v
+    }
+}
+
+/// Convert `(x, y)` to polar coordinates `(r, theta)`.
+fn polar<T: Float>(x: T, y: T) -> (T, T) {
+    (x.hypot(y), y.atan2(x))
+}
+
+/// Compute the first and last segment lengths of the CCCC words.
+fn tau_omega<T: Float + FloatConst>(u: T, v: T, xi: T, eta: T, phi: T) -> (T, T) {
+    let delta = mod2pi(u - v);
+    let a = u.sin() - delta.sin();
+    let b = u.cos() - delta.cos() - T::one();
+    let t1 = (eta * a - xi * b).atan2(xi * a + eta * b);
+    let t2 = k::<T>(2.0) * (delta.cos() - v.cos() - u.cos()) + k(3.0);
+    let tau = if t2 < T::zero() {
+        mod2pi(t1 + T::PI())
+    } else {
+        mod2pi(t1)
+    };
+    (tau, mod2pi(tau - u + v - phi))
+}
+
+/// Formula 8.1 in Reeds and Shepp.
+fn lp_sp_lp<T: Float + FloatConst>(x: T, y: T, phi: T) -> Option<(T, T, T)> {
+    let (u, t) = polar(x - phi.sin(), y - T::one() + phi.cos());
+    if t >= -zero::<T>() {
+        let v = mod2pi(phi - t);
+        if v >= -zero::<T>() {
+            return Some((t, u, v));
+        }
+    }
+    None
+}
+
+/// Formula 8.2 in Reeds and Shepp.
+fn lp_sp_rp<T: Float + FloatConst>(x: T, y: T, phi: T) -> Option<(T, T, T)> {
+    let (u1, t1) = polar(x + phi.sin(), y - T::one() - phi.cos());
+    let u1 = u1 * u1;
+    let four = k(4.0);
+    if u1 >= four {
+        let u = (u1 - four).sqrt();
+        let t = mod2pi(t1 + k::<T>(2.0).atan2(u));
+        let v = mod2pi(t - phi);
+        if t >= -zero::<T>() && v >= -zero::<T>() {
+            return Some((t, u, v));
+        }
+    }
+    None
+}
+
+/// Formulas 8.3 and 8.4 in Reeds and Shepp, correcting a typo in the paper.
+fn lp_rm_l<T: Float + FloatConst>(x: T, y: T, phi: T) -> Option<(T, T, T)> {
+    let (u1, theta) = polar(x - phi.sin(), y - T::one() + phi.cos());
+    if u1 <= k(4.0) {
+        let u = k::<T>(-2.0) * (k::<T>(0.25) * u1).asin();
+        let t = mod2pi(theta + k::<T>(0.5) * u + T::PI());
+        let v = mod2pi(phi - t + u);
+        if t >= -zero::<T>() && u <= zero() {
+            return Some((t, u, v));
+        }
+    }
+    None
+}
+
+/// Formula 8.7 in Reeds and Shepp.
+fn lp_rup_lum_rm<T: Float + FloatConst>(x: T, y: T, phi: T) -> Option<(T, T, T)> {
+    let xi = x + phi.sin();
+    let eta = y - T::one() - phi.cos();
+    let rho = k::<T>(0.25) * (k::<T>(2.0) + xi.hypot(eta));
+    if rho <= T::one() {
+        let u = rho.acos();
+        let (t, v) = tau_omega(u, -u, xi, eta, phi);
+        if t >= -zero::<T>() && v <= zero() {
+            return Some((t, u, v));
+        }
+    }
+    None
+}
+
+/// Formula 8.8 in Reeds and Shepp.
+fn lp_rum_lum_rp<T: Float + FloatConst>(x: T, y: T, phi: T) -> Option<(T, T, T)> {
+    let xi = x + phi.sin();
+    let eta = y - T::one() - phi.cos();
+    let rho = (k::<T>(20.0) - xi * xi - eta * eta) / k(16.0);
+    if rho >= T::zero() && rho <= T::one() {
+        let u = -rho.acos();
+        if u >= -T::FRAC_PI_2() {
+            let (t, v) = tau_omega(u, u, xi, eta, phi);
+            if t >= -zero::<T>() && v >= -zero::<T>() {
+                return Some((t, u, v));
+            }
+        }
+    }
+    None
+}
+
+/// Formula 8.9 in Reeds and Shepp.
+fn lp_rm_sm_lm<T: Float + FloatConst>(x: T, y: T, phi: T) -> Option<(T, T, T)> {
+    let (rho, theta) = polar(x - phi.sin(), y - T::one() + phi.cos());
+    let two = k::<T>(2.0);
+    if rho >= two {
+        let r = (rho * rho - k(4.0)).sqrt();
+        let u = two - r;
+        let t = mod2pi(theta + r.atan2(-two));
+        let v = mod2pi(phi - T::FRAC_PI_2() - t);
+        if t >= -zero::<T>() && u <= zero() && v <= zero() {
+            return Some((t, u, v));
+        }
+    }
+    None
+}
+
+/// Formula 8.10 in Reeds and Shepp.
+fn lp_rm_sm_rm<T: Float + FloatConst>(x: T, y: T, phi: T) -> Option<(T, T, T)> {
+    let xi = x + phi.sin();
+    let eta = y - T::one() - phi.cos();
+    let (rho, theta) = polar(-eta, xi);
+    let two = k::<T>(2.0);
+    if rho >= two {
+        let t = theta;
+        let u = two - rho;
+        let v = mod2pi(t + T::FRAC_PI_2() - phi);
+        if t >= -zero::<T>() && u <= zero() && v <= zero() {
+            return Some((t, u, v));
+        }
+    }
+    None
+}
+
+/// Formula 8.11 in Reeds and Shepp, correcting a typo in the paper.
+fn lp_rm_s_lm_rp<T: Float + FloatConst>(x: T, y: T, phi: T) -> Option<(T, T, T)> {
+    let xi = x + phi.sin();
+    let eta = y - T::one() - phi.cos();
+    let (rho, _) = polar(xi, eta);
+    let two = k::<T>(2.0);
+    let four = k::<T>(4.0);
+    if rho >= two {
+        let u = four - (rho * rho - four).sqrt();
+        if u <= zero() {
+            let t = mod2pi(((four - u) * xi - two * eta).atan2(-two * xi + (u - four) * eta));
+            let v = mod2pi(t - phi);
+            if t >= -zero::<T>() && v >= -zero::<T>() {
+                return Some((t, u, v));
+            }
+        }
+    }
+    None
+}
+
+/// Search over the words with a straight segment between two turns.
+fn csc<T: Float + FloatConst>(x: T, y: T, phi: T, best: &mut Best<T>) {
+    let z = T::zero();
+    if let Some((t, u, v)) = lp_sp_lp(x, y, phi) {
+        best.offer(LSL, [t, u, v, z, z]);
+    }
+    // timeflip
+    if let Some((t, u, v)) = lp_sp_lp(-x, y, -phi) {
+        best.offer(LSL, [-t, -u, -v, z, z]);
+    }
+    // reflect
+    if let Some((t, u, v)) = lp_sp_lp(x, -y, -phi) {
+        best.offer(RSR, [t, u, v, z, z]);
+    }
+    // timeflip + reflect
+    if let Some((t, u, v)) = lp_sp_lp(-x, -y, phi) {
+        best.offer(RSR, [-t, -u, -v, z, z]);
+    }
+    if let Some((t, u, v)) = lp_sp_rp(x, y, phi) {
+        best.offer(LSR, [t, u, v, z, z]);
+    }
+    if let Some((t, u, v)) = lp_sp_rp(-x, y, -phi) {
+        best.offer(LSR, [-t, -u, -v, z, z]);
+    }
+    if let Some((t, u, v)) = lp_sp_rp(x, -y, -phi) {
+        best.offer(RSL, [t, u, v, z, z]);
+    }
+    if let Some((t, u, v)) = lp_sp_rp(-x, -y, phi) {
+        best.offer(RSL, [-t, -u, -v, z, z]);
+    }
+}
+
+/// Search over the words with three consecutive turns.
+fn ccc<T: Float + FloatConst>(x: T, y: T, phi: T, best: &mut Best<T>) {
+    let z = T::zero();
+    if let Some((t, u, v)) = lp_rm_l(x, y, phi) {
+        best.offer(LRL, [t, u, v, z, z]);
+    }
+    if let Some((t, u, v)) = lp_rm_l(-x, y, -phi) {
+        best.offer(LRL, [-t, -u, -v, z, z]);
+    }
+    if let Some((t, u, v)) = lp_rm_l(x, -y, -phi) {
+        best.offer(RLR, [t, u, v, z, z]);
+    }
+    if let Some((t, u, v)) = lp_rm_l(-x, -y, phi) {
+        best.offer(RLR, [-t, -u, -v, z, z]);
+    }
+
+    // backwards
+    let (s, c) = phi.sin_cos();
+    let xb = x * c + y * s;
+    let yb = x * s - y * c;
+    if let Some((t, u, v)) = lp_rm_l(xb, yb, phi) {
+        best.offer(LRL, [v, u, t, z, z]);
+    }
+    if let Some((t, u, v)) = lp_rm_l(-xb, yb, -phi) {
+        best.offer(LRL, [-v, -u, -t, z, z]);
+    }
+    if let Some((t, u, v)) = lp_rm_l(xb, -yb, -phi) {
+        best.offer(RLR, [v, u, t, z, z]);
+    }
+    if let Some((t, u, v)) = lp_rm_l(-xb, -yb, phi) {
+        best.offer(RLR, [-v, -u, -t, z, z]);
+    }
+}
+
+/// Search over the words with four consecutive turns.
+fn cccc<T: Float + FloatConst>(x: T, y: T, phi: T, best: &mut Best<T>) {
+    let z = T::zero();
+    if let Some((t, u, v)) = lp_rup_lum_rm(x, y, phi) {
+        best.offer(LRLR, [t, u, -u, v, z]);
+    }
+    if let Some((t, u, v)) = lp_rup_lum_rm(-x, y, -phi) {
+        best.offer(LRLR, [-t, -u, u, -v, z]);
+    }
+    if let Some((t, u, v)) = lp_rup_lum_rm(x, -y, -phi) {
+        best.offer(RLRL, [t, u, -u, v, z]);
+    }
+    if let Some((t, u, v)) = lp_rup_lum_rm(-x, -y, phi) {
+        best.offer(RLRL, [-t, -u, u, -v, z]);
+    }
+    if let Some((t, u, v)) = lp_rum_lum_rp(x, y, phi) {
+        best.offer(LRLR, [t, u, u, v, z]);
+    }
+    if let Some((t, u, v)) = lp_rum_lum_rp(-x, y, -phi) {
+        best.offer(LRLR, [-t, -u, -u, -v, z]);
+    }
+    if let Some((t, u, v)) = lp_rum_lum_rp(x, -y, -phi) {
+        best.offer(RLRL, [t, u, u, v, z]);
+    }
+    if let Some((t, u, v)) = lp_rum_lum_rp(-x, -y, phi) {
+        best.offer(RLRL, [-t, -u, -u, -v, z]);
+    }
+}
+
+/// Search over the words with two turns, a straight segment, and one more turn.
+fn ccsc<T: Float + FloatConst>(x: T, y: T, phi: T, best: &mut Best<T>) {
+    let z = T::zero();
+    let h = T::FRAC_PI_2();
+    if let Some((t, u, v)) = lp_rm_sm_lm(x, y, phi) {
+        best.offer(LRSL, [t, -h, u, v, z]);
+    }
+    if let Some((t, u, v)) = lp_rm_sm_lm(-x, y, -phi) {
+        best.offer(LRSL, [-t, h, -u, -v, z]);
+    }
+    if let Some((t, u, v)) = lp_rm_sm_lm(x, -y, -phi) {
+        best.offer(RLSR, [t, -h, u, v, z]);
+    }
+    if let Some((t, u, v)) = lp_rm_sm_lm(-x, -y, phi) {
+        best.offer(RLSR, [-t, h, -u, -v, z]);
+    }
+    if let Some((t, u, v)) = lp_rm_sm_rm(x, y, phi) {
+        best.offer(LRSR, [t, -h, u, v, z]);
+    }
+    if let Some((t, u, v)) = lp_rm_sm_rm(-x, y, -phi) {
+        best.offer(LRSR, [-t, h, -u, -v, z]);
+    }
+    if let Some((t, u, v)) = lp_rm_sm_rm(x, -y, -phi) {
+        best.offer(RLSL, [t, -h, u, v, z]);
+    }
+    if let Some((t, u, v)) = lp_rm_sm_rm(-x, -y, phi) {
+        best.offer(RLSL, [-t, h, -u, -v, z]);
+    }
+
+    // backwards
+    let (s, c) = phi.sin_cos();
+    let xb = x * c + y * s;
+    let yb = x * s - y * c;
+    if let Some((t, u, v)) = lp_rm_sm_lm(xb, yb, phi) {
+        best.offer(LSRL, [v, u, -h, t, z]);
+    }
+    if let Some((t, u, v)) = lp_rm_sm_lm(-xb, yb, -phi) {
+        best.offer(LSRL, [-v, -u, h, -t, z]);
+    }
+    if let Some((t, u, v)) = lp_rm_sm_lm(xb, -yb, -phi) {
+        best.offer(RSLR, [v, u, -h, t, z]);
+    }
+    if let Some((t, u, v)) = lp_rm_sm_lm(-xb, -yb, phi) {
+        best.offer(RSLR, [-v, -u, h, -t, z]);
+    }
+    if let Some((t, u, v)) = lp_rm_sm_rm(xb, yb, phi) {
+        best.offer(RSRL, [v, u, -h, t, z]);
+    }
+    if let Some((t, u, v)) = lp_rm_sm_rm(-xb, yb, -phi) {
+        best.offer(RSRL, [-v, -u, h, -t, z]);
+    }
+    if let Some((t, u, v)) = lp_rm_sm_rm(xb, -yb, -phi) {
+        best.offer(LSLR, [v, u, -h, t, z]);
+    }
+    if let Some((t, u, v)) = lp_rm_sm_rm(-xb, -yb, phi) {
+        best.offer(LSLR, [-v, -u, h, -t, z]);
+    }
+}
+
+/// Search over the words with two turns, a straight segment, and two more turns.
+fn ccscc<T: Float + FloatConst>(x: T, y: T, phi: T, best: &mut Best<T>) {
+    let h = T::FRAC_PI_2();
+    if let Some((t, u, v)) = lp_rm_s_lm_rp(x, y, phi) {
+        best.offer(LRSLR, [t, -h, u, -h, v]);
+    }
+    if let Some((t, u, v)) = lp_rm_s_lm_rp(-x, y, -phi) {
+        best.offer(LRSLR, [-t, h, -u, h, -v]);
+    }
+    if let Some((t, u, v)) = lp_rm_s_lm_rp(x, -y, -phi) {
+        best.offer(RLSRL, [t, -h, u, -h, v]);
+    }
+    if let Some((t, u, v)) = lp_rm_s_lm_rp(-x, -y, phi) {
+        best.offer(RLSRL, [-t, h, -u, h, -v]);
+    }
+}
+
+impl<T> Metric<ReedsSheppPose<T>> for ReedsShepp<T>
+where
+    T: Float + FloatConst,
+{
+    type Distance = T;
+
+    fn distance(&self, c1: &ReedsSheppPose<T>, c2: &ReedsSheppPose<T>) -> Self::Distance {
+        self.path(&c1.0, &c2.0).length() * self.turning_radius
+    }
+}
+
+impl<T> DistanceAabb<ReedsSheppPose<T>> for ReedsShepp<T>
+where
+    T: Float + FloatConst,
+{
+    /// Every Reeds-Shepp curve is at least as long as the straight line between its endpoints, so
+    /// the Euclidean distance to the box bounds the curve length to any pose inside it.
+    fn distance_to_aabb(
+        &self,
+        c: &ReedsSheppPose<T>,
+        aabb_lo: &ReedsSheppPose<T>,
+        aabb_hi: &ReedsSheppPose<T>,
+    ) -> Self::Distance {
+        Euclidean.distance_to_aabb(&c.0.position, &aabb_lo.0.position, &aabb_hi.0.position)
+    }
+}
+
+impl<T> Interpolate for ReedsSheppPose<T>
+where
+    T: Float + FloatConst,
+{
+    type Distance = CurveStep<ReedsShepp<T>, T>;
+    type Interpolation<'a>
+        = CurveInterpolation<Self, T>
+    where
+        T: 'a;
+
+    fn interpolate(&self, end: &Self, radius: Self::Distance) -> Self::Interpolation<'_> {
+        CurveInterpolation::new(
+            self.0,
+            radius.curve.path(&self.0, &end.0),
+            radius.curve.turning_radius,
+            radius.step,
+        )
+    }
+}
+
+impl<T, RNG> Sample<Self, RNG> for ReedsSheppPose<T>
+where
+    T: Clone,
+{
+    fn sample(&self, _: &mut RNG) -> Self {
+        self.clone()
+    }
+}
+
+impl<T> KdKey for ReedsSheppPose<T>
+where
+    Pose2d<T>: KdKey,
+    Self: Clone,
+{
+    fn dimension() -> usize {
+        Pose2d::<T>::dimension()
+    }
+
+    fn assign(&mut self, src: &Self, k: usize) {
+        self.0.assign(&src.0, k);
+    }
+
+    fn compare(&self, rhs: &Self, k: usize) -> Ordering {
+        self.0.compare(&rhs.0, k)
+    }
+
+    fn lower_bound() -> Self {
+        Self(Pose2d::lower_bound())
+    }
+
+    fn upper_bound() -> Self {
+        Self(Pose2d::upper_bound())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    use super::*;
+    use crate::{
+        geo::RrtConnect,
+        nn::KdTreeMap,
+        sample::Rectangle,
+        space::{Angle, Dubins, DubinsPose, Vector},
+        time::Solved,
+        valid::AlwaysValid,
+    };
+
+    #[test]
+    fn reaches_goal() {
+        let region = Rectangle {
+            min: Vector([-5.0; 2]),
+            max: Vector([5.0; 2]),
+        };
+        let mut rng = ChaCha20Rng::seed_from_u64(2707);
+        let radius = CurveStep {
+            curve: ReedsShepp {
+                turning_radius: 0.8,
+            },
+            step: 0.05,
+        };
+        let dubins = Dubins {
+            turning_radius: 0.8,
+        };
+        for _ in 0..500 {
+            let start: ReedsSheppPose = region.sample(&mut rng);
+            let end: ReedsSheppPose = region.sample(&mut rng);
+            let mut prev = start;
+            for pose in start.interpolate(&end, radius) {
+                assert!(
+                    Euclidean.distance(&prev.0.position, &pose.0.position) <= radius.step + 1e-9
+                );
+                prev = pose;
+            }
+            assert!(Euclidean.distance(&prev.0.position, &end.0.position) <= radius.step + 1e-9);
+
+            // the full curve must land exactly on the goal
+            let path = radius.curve.path(&start.0, &end.0);
+            let (x, y, phi) = path.sample(path.length());
+            let (gx, gy, gphi) = local_frame(&start.0, &end.0, radius.curve.turning_radius);
+            assert!((x - gx).abs() < 1e-6 && (y - gy).abs() < 1e-6);
+            assert!(((phi - gphi).sin()).abs() < 1e-6 && (phi - gphi).cos() > 0.0);
+
+            // reversing is never worse than driving forward only, and the curves are symmetric
+            let rs = radius.curve.distance(&start, &end);
+            assert!(rs <= dubins.distance(&DubinsPose(start.0), &DubinsPose(end.0)) + 1e-9);
+            assert!((rs - radius.curve.distance(&end, &start)).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn rrtc_parallel_park() {
+        let curve = ReedsShepp {
+            turning_radius: 1.0,
+        };
+        let start = ReedsSheppPose(Pose2d {
+            position: Vector([0.0, 0.0]),
+            angle: Angle::new(0.0),
+        });
+        // directly beside the start, which requires backing up
+        let goal = ReedsSheppPose(Pose2d {
+            position: Vector([0.0, 1.0]),
+            angle: Angle::new(0.0),
+        });
+        let radius = CurveStep { curve, step: 0.25 };
+        let mut rrtc = RrtConnect::new(KdTreeMap::new(curve), start, goal, &AlwaysValid);
+        let res = rrtc
+            .grow(
+                &Rectangle {
+                    min: Vector([-3.0; 2]),
+                    max: Vector([3.0; 2]),
+                },
+                radius,
+                &mut Solved::new(),
+                &mut ChaCha20Rng::seed_from_u64(2707),
+            )
+            .unwrap();
+
+        assert_eq!(res.first(), Some(&start));
+        assert_eq!(res.last(), Some(&goal));
+        assert!(
+            res.windows(2)
+                .all(|a| curve.distance(&a[0], &a[1]) <= radius.step + 1e-6),
+            "all transitions must be within growth radius"
+        );
+    }
+}
@@ -1,6 +1,11 @@
 //! State and transition validation.
 
-use crate::space::Interpolate;
+use num_traits::{Float, NumCast};
+
+use crate::{
+    kino::{Integrate, Propagate},
+    space::Interpolate,
+};
 
 /// A trait for types that can determine whether a configuration is valid.
 ///
@@ -93,12 +98,127 @@ where
 {
     /// Validate a transition by linearly interpolating between start and end states.
     /// This does not respect the dynamics of the controller, but it was good enough for OMPL, so
-    /// it's good enough for us.
+    /// it's good enough for us. For a validator which does respect the dynamics, use
+    /// [`Repropagate`].
     fn is_valid_transition(&self, _: &P, start: &C, _: &U, _: D, end: &C) -> bool {
         GeoValidate::is_valid_transition(self, start, end)
     }
 }
 
+#[derive(Clone, Copy, Debug)]
+/// A dynamic validator that re-propagates a control at a fixed resolution and checks every
+/// intermediate state along the resulting trajectory.
+///
+/// Unlike the [`DynamicValidate`] implementation for [`SampleInterpolate`], this follows the true
+/// trajectory of the system, so motions which swing through obstacles between their start and end
+/// states are rejected. The user must provide an individual state validator (`V`) and a duration
+/// between each checked state (`D`).
+pub struct Repropagate<V, D> {
+    valid: V,
+    resolution: D,
+}
+
+impl<V, D: Float> Repropagate<V, D> {
+    /// Construct a new validator which checks states at most `resolution` apart in time.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `resolution` is not positive.
+    pub fn new(valid: V, resolution: D) -> Self {
+        assert!(
+            resolution > D::zero(),
+            "repropagation resolution must be positive"
+        );
+        Self { valid, resolution }
+    }
+}
+
+impl<V, D, C> Validate<C> for Repropagate<V, D>
+where
+    V: Validate<C>,
+{
+    fn is_valid_configuration(&self, c: &C) -> bool {
+        self.valid.is_valid_configuration(c)
+    }
+}
+
+impl<V, P, C, U, D> DynamicValidate<P, C, U, D> for Repropagate<V, D>
+where
+    V: Validate<C>,
+    P: Propagate<C, U, D>,
+    D: Float,
+{
+    /// Validate a transition by holding `control` from `start` for successive sub-intervals of at
+    /// most `self.resolution`, checking each intermediate state.
+    /// `end` is assumed to have already been validated.
+    /// Transitions with a negative or NaN `duration` are never valid.
+    fn is_valid_transition(
+        &self,
+        propagator: &P,
+        start: &C,
+        control: &U,
+        duration: D,
+        _: &C,
+    ) -> bool {
+        if duration.is_nan() || duration < D::zero() {
+            return false;
+        }
+        // the number of sub-intervals only fails to fit in a `usize` if it is enormous
+        let Some(n) = <usize as NumCast>::from((duration / self.resolution).ceil()) else {
+            return false;
+        };
+        if n <= 1 {
+            return true;
+        }
+        let h = duration / <D as NumCast>::from(n).unwrap();
+        let mut state = propagator.propagate(start, control, h);
+        for _ in 1..n {
+            if !self.valid.is_valid_configuration(&state) {
+                return false;
+            }
+            state = propagator.propagate(&state, control, h);
+        }
+        true
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+/// A dynamic validator that checks every intermediate state visited by an integrator.
+///
+/// The propagator must implement [`Integrate`]; each sub-step of the integration is checked by the
+/// wrapped state validator `V`.
+pub struct CheckSubsteps<V>(pub V);
+
+impl<V, C> Validate<C> for CheckSubsteps<V>
+where
+    V: Validate<C>,
+{
+    fn is_valid_configuration(&self, c: &C) -> bool {
+        self.0.is_valid_configuration(c)
+    }
+}
+
+impl<V, P, C, U, T> DynamicValidate<P, C, U, T> for CheckSubsteps<V>
+where
+    V: Validate<C>,
+    P: Integrate<C, U, T>,
+    C: Clone,
+    T: Float,
+{
+    fn is_valid_transition(
+        &self,
+        propagator: &P,
+        start: &C,
+        control: &U,
+        duration: T,
+        _: &C,
+    ) -> bool {
+        propagator
+            .substeps(start, control, duration)
+            .all(|c| self.0.is_valid_configuration(&c))
+    }
+}
+
 /// A validator for dynamic systems.
 ///
 /// `P` is a state propagator, `C` is the configuration type, `U` is the control type, and `D` is
@@ -122,3 +242,56 @@ pub trait GeoValidate<C>: Validate<C> {
     /// This function may assume that `start` and `end` are already valid.
     fn is_valid_transition(&self, start: &C, end: &C) -> bool;
 }
+
+#[cfg(test)]
+mod tests {
+    use core::f64::consts::PI;
+
+    use super::*;
+    use crate::{kino::Rk4, space::Vector};
+
+    #[test]
+    fn swing_through_obstacle() {
+        // a unicycle with state (x, y, heading) and control angular velocity
+        let unicycle = Rk4 {
+            dynamics: |x: &Vector<3>, &omega: &f64| Vector::new([x[2].cos(), x[2].sin(), omega]),
+            step: 0.01,
+        };
+        // a box obstacle which lies on the arc but not on the straight line between its endpoints
+        let free = |x: &Vector<3>| !((0.5..=1.5).contains(&x[0]) && (0.5..=1.5).contains(&x[1]));
+
+        let start = Vector::new([0.0, 0.0, 0.0]);
+        // turning at unit rate for pi seconds sweeps a half-circle through (1, 1)
+        let end = unicycle.propagate(&start, &1.0, PI);
+        assert!(free(&start) && free(&end));
+
+        let interp = SampleInterpolate::new(free, 0.01);
+        assert!(DynamicValidate::is_valid_transition(
+            &interp, &unicycle, &start, &1.0, PI, &end
+        ));
+
+        let repropagate = Repropagate::new(free, 0.01);
+        assert!(!repropagate.is_valid_transition(&unicycle, &start, &1.0, PI, &end));
+        assert!(repropagate.is_valid_transition(&unicycle, &start, &0.0, 0.4, &end));
+    }
+
+    #[test]
+    fn repropagate_bad_duration() {
+        let unicycle = Rk4 {
+            dynamics: |x: &Vector<3>, &omega: &f64| Vector::new([x[2].cos(), x[2].sin(), omega]),
+            step: 0.01,
+        };
+        let start = Vector::new([0.0, 0.0, 0.0]);
+        let repropagate = Repropagate::new(|_: &Vector<3>| true, 0.01);
+        assert!(repropagate.is_valid_transition(&unicycle, &start, &1.0, 0.0, &start));
+        for duration in [-0.5, -2.0, f64::NAN] {
+            assert!(!repropagate.is_valid_transition(&unicycle, &start, &1.0, duration, &start));
+        }
+    }
+
+    #[test]
+    #[should_panic = "repropagation resolution must be positive"]
+    fn repropagate_zero_resolution() {
+        let _ = Repropagate::new(|_: &Vector<3>| true, 0.0);
+    }
+}